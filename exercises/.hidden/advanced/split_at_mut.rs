@@ -0,0 +1,42 @@
+// hint: Take `let ptr = slice.as_mut_ptr()`, then build the halves from ptr and ptr.add(mid)
+#[test]
+fn splits_in_the_middle() {
+    let mut v = [1, 2, 3, 4, 5, 6];
+    let (a, b) = split_at_mut(&mut v, 3);
+    assert_eq!(a, &[1, 2, 3]);
+    assert_eq!(b, &[4, 5, 6]);
+}
+
+// hint: The second half has len - mid elements
+#[test]
+fn uneven_halves() {
+    let mut v = [1, 2, 3, 4, 5];
+    let (a, b) = split_at_mut(&mut v, 1);
+    assert_eq!(a, &[1]);
+    assert_eq!(b, &[2, 3, 4, 5]);
+}
+
+#[test]
+fn split_at_the_ends() {
+    let mut v = [1, 2, 3];
+    assert_eq!(split_at_mut(&mut v, 0).1, &[1, 2, 3]);
+    assert_eq!(split_at_mut(&mut v, 3).0, &[1, 2, 3]);
+}
+
+#[test]
+fn halves_are_mutable() {
+    let mut v = [1, 2, 3, 4];
+    let (a, b) = split_at_mut(&mut v, 2);
+    a[0] = 10;
+    b[0] = 30;
+    assert_eq!(v, [10, 2, 30, 4]);
+}
+
+// hint: Check `assert!(mid <= len)` before creating any slices, so a bad
+// hint: `mid` panics instead of reading past the end
+#[test]
+#[should_panic(expected = "mid <= len")]
+fn mid_past_the_end_panics() {
+    let mut v = [1, 2, 3];
+    split_at_mut(&mut v, 4);
+}
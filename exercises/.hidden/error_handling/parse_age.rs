@@ -0,0 +1,30 @@
+// hint: input.parse::<u32>() does the hard work; map its error with map_err
+#[test]
+fn valid_age() {
+    assert_eq!(parse_age("42"), Ok(42));
+}
+
+// hint: Any text that doesn't parse as a u32 is AgeError::InvalidFormat
+#[test]
+fn not_a_number() {
+    assert_eq!(parse_age("abc"), Err(AgeError::InvalidFormat));
+    assert_eq!(parse_age(""), Err(AgeError::InvalidFormat));
+}
+
+// hint: A u32 can't hold "-5", so parsing it fails too
+#[test]
+fn negative_number() {
+    assert_eq!(parse_age("-5"), Err(AgeError::InvalidFormat));
+}
+
+// hint: Compare with `age > 150` after parsing and return AgeError::TooOld
+#[test]
+fn too_old() {
+    assert_eq!(parse_age("151"), Err(AgeError::TooOld));
+}
+
+// hint: 150 itself is still allowed
+#[test]
+fn oldest_allowed() {
+    assert_eq!(parse_age("150"), Ok(150));
+}
@@ -0,0 +1,29 @@
+// hint: With no space in the string, the whole string is the first word
+#[test]
+fn single_word() {
+    assert_eq!(first_word("hello"), "hello");
+}
+
+// hint: Find the index of the first b' ' in s.as_bytes() and return &s[0..i]
+#[test]
+fn two_words() {
+    assert_eq!(first_word("hello world"), "hello");
+}
+
+// hint: Stop at the FIRST space, not the last one
+#[test]
+fn several_words() {
+    assert_eq!(first_word("the quick brown fox"), "the");
+}
+
+#[test]
+fn empty_string() {
+    assert_eq!(first_word(""), "");
+}
+
+// hint: Return a slice of `s` itself; building a new String can't be returned as &str
+#[test]
+fn borrows_from_input() {
+    let s = String::from("borrowed slice");
+    assert_eq!(first_word(&s).as_ptr(), s.as_ptr());
+}
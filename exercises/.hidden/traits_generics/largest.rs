@@ -0,0 +1,27 @@
+// hint: Start from list[0] and keep whichever item compares greater with `>`
+#[test]
+fn integers() {
+    assert_eq!(largest(&[34, 50, 25, 100, 65]), 100);
+}
+
+// hint: Don't start from 0: every item in the list might be negative
+#[test]
+fn negative_integers() {
+    assert_eq!(largest(&[-7, -3, -12]), -3);
+}
+
+#[test]
+fn chars() {
+    assert_eq!(largest(&['y', 'm', 'a', 'q']), 'y');
+}
+
+#[test]
+fn floats() {
+    assert_eq!(largest(&[1.5, 0.25, 3.75, 2.0]), 3.75);
+}
+
+// hint: A list with one item has that item as its largest
+#[test]
+fn single_item() {
+    assert_eq!(largest(&[42]), 42);
+}
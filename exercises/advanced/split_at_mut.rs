@@ -0,0 +1,17 @@
+// ============================================================================
+// EXERCISE: advanced/split_at_mut
+// ============================================================================
+// Split `slice` into two mutable halves at index `mid`: [0, mid) and
+// [mid, len). Panic if `mid` is past the end.
+//
+// Two `&mut` borrows of one slice are rejected by the borrow checker even
+// when they don't overlap, so write this one with raw pointers and
+// `std::slice::from_raw_parts_mut` (no fair calling slice::split_at_mut!).
+//
+// Lesson:  cargo run -- run advanced::unsafe_functions
+// Check:   cargo run -- exercise check advanced/split_at_mut
+// ============================================================================
+
+fn split_at_mut(slice: &mut [i32], mid: usize) -> (&mut [i32], &mut [i32]) {
+    todo!()
+}
@@ -0,0 +1,20 @@
+// ============================================================================
+// EXERCISE: error_handling/parse_age
+// ============================================================================
+// Parse an age such as "42". Return AgeError::InvalidFormat when the text
+// isn't a whole, non-negative number, and AgeError::TooOld for ages over 150.
+//
+// Lesson:  cargo run -- run error_handling::custom_errors
+// Check:   cargo run -- exercise check error_handling/parse_age
+// ============================================================================
+
+#[derive(Debug, PartialEq)]
+enum AgeError {
+    InvalidFormat,
+    NegativeAge,
+    TooOld,
+}
+
+fn parse_age(input: &str) -> Result<u32, AgeError> {
+    todo!()
+}
@@ -0,0 +1,13 @@
+// ============================================================================
+// EXERCISE: ownership/first_word
+// ============================================================================
+// Return the first word of `s`: everything before the first space, or all of
+// `s` if it has no space. Return a slice of `s` rather than a new String.
+//
+// Lesson:  cargo run -- run ownership::string_slices
+// Check:   cargo run -- exercise check ownership/first_word
+// ============================================================================
+
+fn first_word(s: &str) -> &str {
+    todo!()
+}
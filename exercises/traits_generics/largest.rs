@@ -0,0 +1,13 @@
+// ============================================================================
+// EXERCISE: traits_generics/largest
+// ============================================================================
+// Return the largest item in `list`, which is never empty. It must work for
+// any type that can be compared and copied: integers, floats, chars...
+//
+// Lesson:  cargo run -- run traits_generics::generic_functions
+// Check:   cargo run -- exercise check traits_generics/largest
+// ============================================================================
+
+fn largest<T: PartialOrd + Copy>(list: &[T]) -> T {
+    todo!()
+}
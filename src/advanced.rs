@@ -7,8 +7,22 @@
 // - Advanced types (type aliases, never type, DST)
 // - Macros (declarative and procedural)
 // - Attributes and conditional compilation
+//
+// The unsafe lessons are only compiled with the `unsafe-lessons` feature,
+// and the FFI one with `ffi-lessons`; both are on by default.
 // ============================================================================
 
+// `loop { break 5 }` and the hand-rolled my_vec! macro are teaching examples.
+#![allow(
+    clippy::never_loop,
+    clippy::useless_vec,
+    clippy::vec_init_then_push,
+)]
+
+use crate::lesson::{lesson, Difficulty, Section};
+use std::io::{self, Write};
+
+#[cfg(feature = "unsafe-lessons")]
 /// Demonstrates unsafe Rust basics
 /// 
 /// UNSAFE:
@@ -19,8 +33,8 @@
 ///   3. Access/modify mutable static variables
 ///   4. Implement unsafe traits
 ///   5. Access fields of unions
-pub fn unsafe_basics() {
-    println!("\n--- Unsafe Basics ---");
+pub fn unsafe_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Unsafe Basics ---")?;
     
     // Raw pointers
     let mut num = 5;
@@ -28,31 +42,34 @@ pub fn unsafe_basics() {
     let r1 = &num as *const i32; // Immutable raw pointer
     let r2 = &mut num as *mut i32; // Mutable raw pointer
     
-    println!("Raw pointers created (safe)");
+    writeln!(out, "Raw pointers created (safe)")?;
     
     // Dereferencing requires unsafe
     unsafe {
-        println!("r1 points to: {}", *r1);
-        println!("r2 points to: {}", *r2);
+        writeln!(out, "r1 points to: {}", *r1)?;
+        writeln!(out, "r2 points to: {}", *r2)?;
     }
     
     // Creating arbitrary raw pointer (dangerous!)
     let address = 0x012345usize;
     let _r = address as *const i32;
     // unsafe { println!("{}", *r); } // Would likely crash!
+    
+    Ok(())
 }
 
+#[cfg(feature = "unsafe-lessons")]
 /// Demonstrates unsafe functions
-pub fn unsafe_functions() {
-    println!("\n--- Unsafe Functions ---");
+pub fn unsafe_functions(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Unsafe Functions ---")?;
     
-    unsafe fn dangerous() {
-        println!("This is an unsafe function!");
+    unsafe fn dangerous(out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "This is an unsafe function!")
     }
     
     // Must call in unsafe block
     unsafe {
-        dangerous();
+        dangerous(out)?;
     }
     
     // Safe abstraction over unsafe code
@@ -60,11 +77,18 @@ pub fn unsafe_functions() {
     let r = &mut v[..];
     
     let (a, b) = split_at_mut(r, 3);
-    println!("First half: {:?}", a);
-    println!("Second half: {:?}", b);
+    writeln!(out, "First half: {:?}", a)?;
+    writeln!(out, "Second half: {:?}", b)?;
+    
+    Ok(())
 }
 
-fn split_at_mut(slice: &mut [i32], mid: usize) -> (&mut [i32], &mut [i32]) {
+#[cfg(feature = "unsafe-lessons")]
+/// Splits `slice` into two non-overlapping mutable halves at `mid`, like
+/// `<[T]>::split_at_mut`: a safe function built on unsafe code
+///
+/// Panics if `mid > slice.len()`.
+pub fn split_at_mut(slice: &mut [i32], mid: usize) -> (&mut [i32], &mut [i32]) {
     let len = slice.len();
     let ptr = slice.as_mut_ptr();
     
@@ -78,93 +102,108 @@ fn split_at_mut(slice: &mut [i32], mid: usize) -> (&mut [i32], &mut [i32]) {
     }
 }
 
+#[cfg(feature = "ffi-lessons")]
 /// Demonstrates calling external C functions
-pub fn extern_functions() {
-    println!("\n--- Extern Functions ---");
+pub fn extern_functions(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Extern Functions ---")?;
     
     // Calling C standard library
     unsafe {
-        println!("Absolute value of -3: {}", abs(-3));
+        writeln!(out, "Absolute value of -3: {}", abs(-3))?;
     }
     
-    println!("FFI (Foreign Function Interface) allows calling C code");
+    writeln!(out, "FFI (Foreign Function Interface) allows calling C code")?;
+    
+    Ok(())
 }
 
+#[cfg(feature = "ffi-lessons")]
 extern "C" {
     fn abs(input: i32) -> i32;
 }
 
+#[cfg(feature = "unsafe-lessons")]
 /// Demonstrates static variables
 /// 
 /// STATIC:
 /// - Global variables with 'static lifetime
 /// - Must be immutable or accessed in unsafe block
-pub fn static_variables() {
-    println!("\n--- Static Variables ---");
+pub fn static_variables(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Static Variables ---")?;
     
     static HELLO_WORLD: &str = "Hello, world!";
-    println!("Static string: {}", HELLO_WORLD);
+    writeln!(out, "Static string: {}", HELLO_WORLD)?;
     
     // Mutable static (unsafe to access)
     static mut COUNTER: u32 = 0;
     
-    unsafe {
+    let count = unsafe {
         COUNTER += 1;
-        println!("Counter: {}", COUNTER);
-    }
+        COUNTER // Copy the value out instead of borrowing the static
+    };
+    writeln!(out, "Counter: {}", count)?;
     
-    println!("Note: Mutable statics are unsafe due to data races!");
+    writeln!(out, "Note: Mutable statics are unsafe due to data races!")?;
+    
+    Ok(())
 }
 
+#[cfg(feature = "unsafe-lessons")]
 /// Demonstrates unsafe traits
-pub fn unsafe_traits() {
-    println!("\n--- Unsafe Traits ---");
+pub fn unsafe_traits(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Unsafe Traits ---")?;
     
-    println!("Unsafe traits require unsafe impl");
-    println!("Example: Send and Sync are unsafe traits");
-    println!("Compiler implements them automatically when safe");
+    writeln!(out, "Unsafe traits require unsafe impl")?;
+    writeln!(out, "Example: Send and Sync are unsafe traits")?;
+    writeln!(out, "Compiler implements them automatically when safe")?;
     
     // Custom unsafe trait
+    /// # Safety
+    /// Implementors must uphold the invariants the trait promises
     unsafe trait Foo {
-        fn foo(&self);
+        fn foo(&self, out: &mut dyn Write) -> io::Result<()>;
     }
     
     struct Bar;
     
     unsafe impl Foo for Bar {
-        fn foo(&self) {
-            println!("Foo implementation");
+        fn foo(&self, out: &mut dyn Write) -> io::Result<()> {
+            writeln!(out, "Foo implementation")
         }
     }
     
     let bar = Bar;
-    bar.foo();
+    bar.foo(out)?;
+    
+    Ok(())
 }
 
 /// Demonstrates advanced trait features
-pub fn advanced_traits() {
-    println!("\n--- Advanced Traits ---");
+pub fn advanced_traits(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Advanced Traits ---")?;
     
     // Associated types
     let counter = Counter { count: 0 };
     for (i, num) in counter.enumerate().take(5) {
-        println!("  Item {}: {}", i, num);
+        writeln!(out, "  Item {}: {}", i, num)?;
     }
     
     // Default type parameters
     let p1 = Point { x: 5, y: 10 };
     let p2 = Point { x: 1, y: 2 };
     let p3 = p1 + p2;
-    println!("Point addition: ({}, {})", p3.x, p3.y);
+    writeln!(out, "Point addition: ({}, {})", p3.x, p3.y)?;
     
     // Fully qualified syntax
     let person = Human;
-    Pilot::fly(&person);
-    Wizard::fly(&person);
-    person.fly();
+    Pilot::fly(&person, out)?;
+    Wizard::fly(&person, out)?;
+    person.fly(out)?;
     
     // Disambiguate with fully qualified syntax
-    println!("Human's name: {}", <Human as Animal>::baby_name());
+    writeln!(out, "Human's name: {}", <Human as Animal>::baby_name())?;
+    
+    Ok(())
 }
 
 struct Counter {
@@ -202,30 +241,30 @@ impl std::ops::Add for Point {
 }
 
 trait Pilot {
-    fn fly(&self);
+    fn fly(&self, out: &mut dyn Write) -> io::Result<()>;
 }
 
 trait Wizard {
-    fn fly(&self);
+    fn fly(&self, out: &mut dyn Write) -> io::Result<()>;
 }
 
 struct Human;
 
 impl Pilot for Human {
-    fn fly(&self) {
-        println!("This is your captain speaking.");
+    fn fly(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "This is your captain speaking.")
     }
 }
 
 impl Wizard for Human {
-    fn fly(&self) {
-        println!("Up!");
+    fn fly(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "Up!")
     }
 }
 
 impl Human {
-    fn fly(&self) {
-        println!("*waving arms furiously*");
+    fn fly(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "*waving arms furiously*")
     }
 }
 
@@ -240,14 +279,14 @@ impl Animal for Human {
 }
 
 /// Demonstrates type aliases
-pub fn type_aliases() {
-    println!("\n--- Type Aliases ---");
+pub fn type_aliases(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Type Aliases ---")?;
     
     type Kilometers = i32;
     
     let x: i32 = 5;
     let y: Kilometers = 5;
-    println!("x + y = {}", x + y); // Same type!
+    writeln!(out, "x + y = {}", x + y)?; // Same type!
     
     // Useful for long types
     type Thunk = Box<dyn Fn() + Send + 'static>;
@@ -261,14 +300,16 @@ pub fn type_aliases() {
         Ok(String::from("file contents"))
     }
     
-    println!("Read result: {:?}", read_file());
+    writeln!(out, "Read result: {:?}", read_file())?;
+    
+    Ok(())
 }
 
 /// Demonstrates the never type
-pub fn never_type() {
-    println!("\n--- Never Type ---");
+pub fn never_type(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Never Type ---")?;
     
-    println!("The ! type represents computations that never return");
+    writeln!(out, "The ! type represents computations that never return")?;
     
     // Functions that never return
     fn _diverges() -> ! {
@@ -286,37 +327,41 @@ pub fn never_type() {
         break 5; // But break can return a value
     };
     
-    println!("Never type is useful for type system completeness");
+    writeln!(out, "Never type is useful for type system completeness")?;
+    
+    Ok(())
 }
 
 /// Demonstrates dynamically sized types (DST)
-pub fn dynamically_sized_types() {
-    println!("\n--- Dynamically Sized Types ---");
+pub fn dynamically_sized_types(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Dynamically Sized Types ---")?;
     
-    println!("DSTs have size known only at runtime");
-    println!("Examples: str, [T], dyn Trait");
+    writeln!(out, "DSTs have size known only at runtime")?;
+    writeln!(out, "Examples: str, [T], dyn Trait")?;
     
     // str is a DST (must use &str or Box<str>)
     let s1: &str = "Hello";
-    println!("String slice: {}", s1);
+    writeln!(out, "String slice: {}", s1)?;
     
     // [T] is a DST (must use &[T] or Box<[T]>)
     let arr: &[i32] = &[1, 2, 3];
-    println!("Slice: {:?}", arr);
+    writeln!(out, "Slice: {:?}", arr)?;
     
     // Trait objects are DSTs
     let shape: &dyn std::fmt::Display = &42;
-    println!("Trait object: {}", shape);
+    writeln!(out, "Trait object: {}", shape)?;
     
-    println!("\nSized trait:");
-    println!("  - Automatically implemented for types with known size");
-    println!("  - Generic functions have implicit Sized bound");
-    println!("  - Use ?Sized to opt out");
+    writeln!(out, "\nSized trait:")?;
+    writeln!(out, "  - Automatically implemented for types with known size")?;
+    writeln!(out, "  - Generic functions have implicit Sized bound")?;
+    writeln!(out, "  - Use ?Sized to opt out")?;
+    
+    Ok(())
 }
 
 /// Demonstrates function pointers
-pub fn function_pointers() {
-    println!("\n--- Function Pointers ---");
+pub fn function_pointers(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Function Pointers ---")?;
     
     fn add_one(x: i32) -> i32 {
         x + 1
@@ -324,35 +369,23 @@ pub fn function_pointers() {
     
     // Function pointer type: fn
     let f: fn(i32) -> i32 = add_one;
-    println!("Function pointer result: {}", f(5));
+    writeln!(out, "Function pointer result: {}", f(5))?;
     
     // Passing function pointers
     let numbers = vec![1, 2, 3];
     let result: Vec<i32> = numbers.iter().map(|&x| add_one(x)).collect();
-    println!("Mapped with function: {:?}", result);
+    writeln!(out, "Mapped with function: {:?}", result)?;
     
     // Functions implement Fn, FnMut, and FnOnce
     do_twice(add_one, 5);
+    
+    Ok(())
 }
 
 fn do_twice(f: fn(i32) -> i32, arg: i32) -> i32 {
     f(arg) + f(arg)
 }
 
-/// Demonstrates declarative macros
-pub fn declarative_macros() {
-    println!("\n--- Declarative Macros ---");
-    
-    // Using custom macro
-    let v = my_vec![1, 2, 3];
-    println!("Created with macro: {:?}", v);
-    
-    // Macros can take variable arguments
-    say_hello!();
-    say_hello!("Alice");
-    say_hello!("Bob", "Charlie");
-}
-
 // Define a simple macro
 macro_rules! my_vec {
     ( $( $x:expr ),* ) => {
@@ -366,28 +399,45 @@ macro_rules! my_vec {
     };
 }
 
+// The first argument is the writer to greet on
 macro_rules! say_hello {
-    () => {
-        println!("Hello!");
+    ($out:expr) => {
+        writeln!($out, "Hello!")?;
     };
-    ($name:expr) => {
-        println!("Hello, {}!", $name);
+    ($out:expr, $name:expr) => {
+        writeln!($out, "Hello, {}!", $name)?;
     };
-    ($name1:expr, $name2:expr) => {
-        println!("Hello, {} and {}!", $name1, $name2);
+    ($out:expr, $name1:expr, $name2:expr) => {
+        writeln!($out, "Hello, {} and {}!", $name1, $name2)?;
     };
 }
 
+/// Demonstrates declarative macros
+pub fn declarative_macros(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Declarative Macros ---")?;
+    
+    // Using custom macro
+    let v = my_vec![1, 2, 3];
+    writeln!(out, "Created with macro: {:?}", v)?;
+    
+    // Macros can take variable arguments
+    say_hello!(out);
+    say_hello!(out, "Alice");
+    say_hello!(out, "Bob", "Charlie");
+    
+    Ok(())
+}
+
 /// Demonstrates attributes
-pub fn attributes() {
-    println!("\n--- Attributes ---");
+pub fn attributes(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Attributes ---")?;
     
-    println!("Common attributes:");
-    println!("  #[derive(...)] - Auto-implement traits");
-    println!("  #[cfg(...)] - Conditional compilation");
-    println!("  #[test] - Mark test functions");
-    println!("  #[allow(...)] - Suppress warnings");
-    println!("  #[deprecated] - Mark as deprecated");
+    writeln!(out, "Common attributes:")?;
+    writeln!(out, "  #[derive(...)] - Auto-implement traits")?;
+    writeln!(out, "  #[cfg(...)] - Conditional compilation")?;
+    writeln!(out, "  #[test] - Mark test functions")?;
+    writeln!(out, "  #[allow(...)] - Suppress warnings")?;
+    writeln!(out, "  #[deprecated] - Mark as deprecated")?;
     
     #[allow(dead_code)]
     fn unused_function() {
@@ -395,65 +445,78 @@ pub fn attributes() {
     }
     
     #[cfg(target_os = "windows")]
-    fn windows_only() {
-        println!("This is Windows!");
+    fn windows_only(out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "This is Windows!")
     }
     
     #[cfg(target_os = "linux")]
-    fn linux_only() {
-        println!("This is Linux!");
+    fn linux_only(out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "This is Linux!")
     }
     
     #[cfg(target_os = "windows")]
-    windows_only();
+    windows_only(out)?;
     
     #[cfg(target_os = "linux")]
-    linux_only();
+    linux_only(out)?;
+    
+    Ok(())
 }
 
 /// Demonstrates conditional compilation
-pub fn conditional_compilation() {
-    println!("\n--- Conditional Compilation ---");
+pub fn conditional_compilation(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Conditional Compilation ---")?;
     
     #[cfg(debug_assertions)]
-    println!("Debug mode enabled");
+    writeln!(out, "Debug mode enabled")?;
     
     #[cfg(not(debug_assertions))]
-    println!("Release mode");
+    writeln!(out, "Release mode")?;
     
     // Feature flags
-    println!("Use feature flags in Cargo.toml:");
-    println!("  [features]");
-    println!("  feature_name = []");
-    println!("\nThen use: #[cfg(feature = \"feature_name\")]");
+    writeln!(out, "Use feature flags in Cargo.toml:")?;
+    writeln!(out, "  [features]")?;
+    writeln!(out, "  feature_name = []")?;
+    writeln!(out, "\nThen use: #[cfg(feature = \"feature_name\")]")?;
+    
+    // This project's own Cargo.toml has one feature per section, plus
+    // lesson packs such as unsafe-lessons that imply the advanced section
+    writeln!(out, "\nThis build's lesson packs:")?;
+    writeln!(out, "  unsafe-lessons: {}", cfg!(feature = "unsafe-lessons"))?;
+    writeln!(out, "  ffi-lessons: {}", cfg!(feature = "ffi-lessons"))?;
+    writeln!(out, "Leave them out with: cargo run --no-default-features --features advanced")?;
+    
+    Ok(())
 }
 
 /// Demonstrates advanced patterns
-pub fn advanced_patterns() {
-    println!("\n--- Advanced Patterns ---");
+pub fn advanced_patterns(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Advanced Patterns ---")?;
     
     // @ bindings
     let msg = Message::Hello { id: 5 };
     
     match msg {
         Message::Hello { id: id_var @ 3..=7 } => {
-            println!("Found id in range: {}", id_var);
+            writeln!(out, "Found id in range: {}", id_var)?;
         }
         Message::Hello { id: 10..=12 } => {
-            println!("Found id in another range");
+            writeln!(out, "Found id in another range")?;
         }
         Message::Hello { id } => {
-            println!("Found other id: {}", id);
+            writeln!(out, "Found other id: {}", id)?;
         }
     }
     
     // Match guards
     let num = Some(4);
     match num {
-        Some(x) if x < 5 => println!("Less than five: {}", x),
-        Some(x) => println!("Greater or equal: {}", x),
+        Some(x) if x < 5 => writeln!(out, "Less than five: {}", x)?,
+        Some(x) => writeln!(out, "Greater or equal: {}", x)?,
         None => (),
     }
+    
+    Ok(())
 }
 
 enum Message {
@@ -461,56 +524,100 @@ enum Message {
 }
 
 /// Demonstrates best practices
-pub fn best_practices() {
-    println!("\n--- Best Practices ---");
+pub fn best_practices(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Best Practices ---")?;
+    
+    writeln!(out, "1. Minimize unsafe code")?;
+    writeln!(out, "   - Encapsulate in safe abstractions")?;
+    writeln!(out, "   - Document safety invariants")?;
     
-    println!("1. Minimize unsafe code");
-    println!("   - Encapsulate in safe abstractions");
-    println!("   - Document safety invariants");
+    writeln!(out, "\n2. Use type system for correctness")?;
+    writeln!(out, "   - Leverage traits and generics")?;
+    writeln!(out, "   - Make invalid states unrepresentable")?;
     
-    println!("\n2. Use type system for correctness");
-    println!("   - Leverage traits and generics");
-    println!("   - Make invalid states unrepresentable");
+    writeln!(out, "\n3. Prefer iterators over loops")?;
+    writeln!(out, "   - More expressive and often faster")?;
+    writeln!(out, "   - Zero-cost abstractions")?;
     
-    println!("\n3. Prefer iterators over loops");
-    println!("   - More expressive and often faster");
-    println!("   - Zero-cost abstractions");
+    writeln!(out, "\n4. Use Result for error handling")?;
+    writeln!(out, "   - Don't panic in library code")?;
+    writeln!(out, "   - Provide good error messages")?;
     
-    println!("\n4. Use Result for error handling");
-    println!("   - Don't panic in library code");
-    println!("   - Provide good error messages");
+    writeln!(out, "\n5. Write documentation")?;
+    writeln!(out, "   - Use /// for public APIs")?;
+    writeln!(out, "   - Include examples in docs")?;
     
-    println!("\n5. Write documentation");
-    println!("   - Use /// for public APIs");
-    println!("   - Include examples in docs");
+    writeln!(out, "\n6. Write tests")?;
+    writeln!(out, "   - Unit tests with #[test]")?;
+    writeln!(out, "   - Integration tests in tests/")?;
+    writeln!(out, "   - Doc tests in documentation")?;
     
-    println!("\n6. Write tests");
-    println!("   - Unit tests with #[test]");
-    println!("   - Integration tests in tests/");
-    println!("   - Doc tests in documentation");
+    Ok(())
 }
 
 // ============================================================================
 // PUBLIC INTERFACE
 // ============================================================================
 
-pub fn run_all_examples() {
-    unsafe_basics();
-    unsafe_functions();
-    extern_functions();
-    static_variables();
-    unsafe_traits();
-    advanced_traits();
-    type_aliases();
-    never_type();
-    dynamically_sized_types();
-    function_pointers();
-    declarative_macros();
-    attributes();
-    conditional_compilation();
-    advanced_patterns();
-    best_practices();
-}
+/// This module's lessons, in teaching order
+pub static SECTION: Section = Section {
+    id: "advanced",
+    number: 10,
+    title: "ADVANCED FEATURES",
+    icon: "🚀",
+    lessons: &[
+        #[cfg(feature = "unsafe-lessons")]
+        &lesson!(unsafe_basics, "Unsafe Basics")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["unsafe", "raw-pointers"]),
+        #[cfg(feature = "unsafe-lessons")]
+        &lesson!(unsafe_functions, "Unsafe Functions")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["unsafe", "slices"]),
+        #[cfg(feature = "ffi-lessons")]
+        &lesson!(extern_functions, "Extern Functions")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["unsafe", "ffi"]),
+        #[cfg(feature = "unsafe-lessons")]
+        &lesson!(static_variables, "Static Variables")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["unsafe", "statics"]),
+        #[cfg(feature = "unsafe-lessons")]
+        &lesson!(unsafe_traits, "Unsafe Traits")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["unsafe", "traits"]),
+        &lesson!(advanced_traits, "Advanced Traits")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["traits", "associated-types"]),
+        &lesson!(type_aliases, "Type Aliases")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["types"]),
+        &lesson!(never_type, "Never Type")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["types", "control-flow"]),
+        &lesson!(dynamically_sized_types, "Dynamically Sized Types")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["types", "trait-objects"]),
+        &lesson!(function_pointers, "Function Pointers")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["functions", "closures"]),
+        &lesson!(declarative_macros, "Declarative Macros")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["macros"]),
+        &lesson!(attributes, "Attributes")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["attributes"]),
+        &lesson!(conditional_compilation, "Conditional Compilation")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["attributes", "cfg"]),
+        &lesson!(advanced_patterns, "Advanced Patterns")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["pattern-matching"]),
+        &lesson!(best_practices, "Best Practices")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["idioms"]),
+    ],
+};
 
 // ============================================================================
 // UNIT TESTS
@@ -518,8 +625,6 @@ pub fn run_all_examples() {
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-    
     #[test]
     fn test_unsafe_dereference() {
         let x = 5;
@@ -530,9 +635,10 @@ mod tests {
     }
     
     #[test]
+    #[cfg(feature = "unsafe-lessons")]
     fn test_split_at_mut() {
         let mut v = vec![1, 2, 3, 4, 5];
-        let (a, b) = split_at_mut(&mut v, 3);
+        let (a, b) = super::split_at_mut(&mut v, 3);
         assert_eq!(a, &[1, 2, 3]);
         assert_eq!(b, &[4, 5]);
     }
@@ -0,0 +1,199 @@
+// ============================================================================
+// BENCHMARKS
+// ============================================================================
+// A small harness for the lessons that compare how fast things are, using
+// nothing but std:
+//
+//     let mut bench = Bench::new();
+//     bench.run("iterator sum", || numbers.iter().sum::<u64>());
+//     bench.run("for loop sum", || { ... });
+//     bench.write_table(out)?;
+//
+// Each closure is run a few times to warm caches and branch predictors up,
+// then timed with `Instant` over a number of samples. The table shows the
+// median sample, which ignores the occasional run interrupted by the OS, the
+// 95th percentile, which shows how bad those get, and each median relative
+// to the fastest.
+//
+// Results go through `black_box` so the optimizer can't notice they're
+// unused and delete the work being measured. Timings from a debug build say
+// little about real performance: compare them with `cargo run --release`.
+// ============================================================================
+
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+pub use std::hint::black_box;
+
+/// Untimed runs before sampling starts
+const WARMUP: u32 = 3;
+
+/// Timed runs per benchmark
+const SAMPLES: u32 = 25;
+
+/// One benchmark's results
+#[derive(Debug, Clone, PartialEq)]
+pub struct Measurement {
+    pub name: &'static str,
+    pub median: Duration,
+    pub p95: Duration,
+}
+
+impl Measurement {
+    /// Summarizes a benchmark's samples, which needn't be sorted
+    fn from_samples(name: &'static str, samples: &mut [Duration]) -> Measurement {
+        assert!(!samples.is_empty(), "{} has no samples", name);
+        samples.sort_unstable();
+        let middle = samples.len() / 2;
+        let median = if samples.len().is_multiple_of(2) {
+            (samples[middle - 1] + samples[middle]) / 2
+        } else {
+            samples[middle]
+        };
+        // Nearest rank: the smallest sample at least 95% of them don't exceed
+        let rank = (samples.len() * 95).div_ceil(100);
+        Measurement {
+            name,
+            median,
+            p95: samples[rank - 1],
+        }
+    }
+}
+
+/// A group of benchmarks compared in one table
+#[derive(Debug, Default)]
+pub struct Bench {
+    results: Vec<Measurement>,
+}
+
+impl Bench {
+    pub fn new() -> Bench {
+        Bench::default()
+    }
+
+    /// Warms `f` up, then times it `SAMPLES` times
+    pub fn run<T>(&mut self, name: &'static str, mut f: impl FnMut() -> T) {
+        for _ in 0..WARMUP {
+            black_box(f());
+        }
+        let mut samples: Vec<Duration> = (0..SAMPLES)
+            .map(|_| {
+                let start = Instant::now();
+                black_box(f());
+                start.elapsed()
+            })
+            .collect();
+        self.results.push(Measurement::from_samples(name, &mut samples));
+    }
+
+    /// Prints the results in the order they ran
+    pub fn write_table(&self, out: &mut dyn Write) -> io::Result<()> {
+        let fastest = self
+            .results
+            .iter()
+            .map(|result| result.median)
+            .min()
+            .unwrap_or_default()
+            // A clock too coarse to see the fastest one still gives a ratio
+            .max(Duration::from_nanos(1));
+
+        writeln!(out, "  {:<28} {:>10} {:>10} {:>8}", "benchmark", "median", "p95", "relative")?;
+        writeln!(out, "  {}", "-".repeat(59))?;
+        for result in &self.results {
+            writeln!(
+                out,
+                "  {:<28} {:>10} {:>10} {:>7.2}x",
+                result.name,
+                format_duration(result.median),
+                format_duration(result.p95),
+                result.median.as_secs_f64() / fastest.as_secs_f64()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Formats a duration with a unit that keeps it short, e.g. `12.3µs`
+pub fn format_duration(duration: Duration) -> String {
+    let nanos = duration.as_nanos();
+    if nanos < 1_000 {
+        format!("{}ns", nanos)
+    } else if nanos < 1_000_000 {
+        format!("{:.1}µs", nanos as f64 / 1e3)
+    } else if nanos < 1_000_000_000 {
+        format!("{:.2}ms", nanos as f64 / 1e6)
+    } else {
+        format!("{:.2}s", duration.as_secs_f64())
+    }
+}
+
+// ============================================================================
+// UNIT TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn micros(values: &[u64]) -> Vec<Duration> {
+        values.iter().map(|&value| Duration::from_micros(value)).collect()
+    }
+
+    #[test]
+    fn test_median_and_p95() {
+        let mut samples = micros(&[5, 1, 4, 2, 3]);
+        let result = Measurement::from_samples("odd", &mut samples);
+        assert_eq!(result.median, Duration::from_micros(3));
+        assert_eq!(result.p95, Duration::from_micros(5));
+
+        let mut samples = micros(&[4, 1, 3, 2]);
+        assert_eq!(Measurement::from_samples("even", &mut samples).median, Duration::from_nanos(2_500));
+
+        // One slow outlier in twenty stays under the 95th percentile
+        let mut samples = micros(&[10; 19]);
+        samples.push(Duration::from_millis(50));
+        let result = Measurement::from_samples("outlier", &mut samples);
+        assert_eq!((result.median, result.p95), (Duration::from_micros(10), Duration::from_micros(10)));
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_nanos(812)), "812ns");
+        assert_eq!(format_duration(Duration::from_nanos(12_345)), "12.3µs");
+        assert_eq!(format_duration(Duration::from_micros(1_250)), "1.25ms");
+        assert_eq!(format_duration(Duration::from_millis(2_500)), "2.50s");
+    }
+
+    #[test]
+    fn test_run_warms_up_then_samples() {
+        let mut calls = 0;
+        let mut bench = Bench::new();
+        bench.run("count", || calls += 1);
+        assert_eq!(calls, WARMUP + SAMPLES);
+        assert_eq!(bench.results[0].name, "count");
+        assert!(bench.results[0].median <= bench.results[0].p95);
+    }
+
+    #[test]
+    fn test_table_compares_with_the_fastest() {
+        let measured = |name, median| Measurement {
+            name,
+            median: Duration::from_micros(median),
+            p95: Duration::from_micros(median * 2),
+        };
+        let bench = Bench {
+            results: vec![measured("slow", 30), measured("fast", 10)],
+        };
+        let mut out = Vec::new();
+        bench.write_table(&mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let rows: Vec<&str> = text.lines().skip(2).map(str::trim_end).collect();
+        assert_eq!(
+            rows,
+            [
+                "  slow                             30.0µs     60.0µs    3.00x",
+                "  fast                             10.0µs     20.0µs    1.00x",
+            ]
+        );
+    }
+}
@@ -0,0 +1,611 @@
+// ============================================================================
+// COMMAND-LINE INTERFACE
+// ============================================================================
+// Parses the arguments given to the `happyr` binary and resolves lesson
+// selectors such as `concurrency`, `ownership::lifetime_basics`,
+// `*::closure_*` or `basics,ownership` against the lesson registry.
+// ============================================================================
+
+use crate::export;
+use crate::isolate;
+use crate::lesson::{Difficulty, Lesson, Section};
+use crate::playlist::{self, Playlist};
+use crate::report::Format;
+use crate::sched;
+use std::fmt;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// The usage text printed by `happyr help`
+pub const USAGE: &str = "\
+Usage: happyr [COMMAND]
+
+Commands:
+  (none)              Run every section, in order
+  list                List every section and its examples, with their
+                      difficulty and tags
+  interactive         Browse the sections and run one lesson at a time
+  progress            Show how many lessons of each section you've finished
+  next                Run the first lesson you haven't finished
+  quiz <SECTION>      Test yourself on a section, e.g. 'happyr quiz ownership'
+  exercise list       List the exercises in exercises/
+  exercise check <ID> Check your solution, e.g. 'happyr exercise check ownership/first_word'
+  run <SELECTOR>...   Run the selected sections or examples
+  playlists           List the learning paths, e.g. 'happyr run --playlist beginner'
+  search <QUERY>...   Find lessons by title, explanation, code or output,
+                      e.g. 'happyr search weak' or 'happyr search scan'
+  watch <LESSON>      Run a lesson, then rebuild and re-run it whenever a
+                      file in src/ changes, showing compile errors
+  show <LESSON>       Show a lesson's source with each line of output under
+                      the statement that printed it
+  export --html <DIR> Write every lesson, with its source and output, as a
+                      static site (or --markdown <DIR> for Markdown files)
+  help                Show this message
+
+Options:
+  --isolate           Run each lesson in its own process, then summarize
+                      which ones panicked, hung or failed
+  --timeout <SECS>    Kill an isolated or watched lesson after SECS
+                      seconds (default 10, implies --isolate)
+  --format <FORMAT>   text (default), or json/ndjson for one record per
+                      lesson with its output, status and timing
+  --deterministic     Let the concurrency lessons' threads take turns in
+                      an order picked by a seed, so every run prints the
+                      same thing (logical time replaces sleeps)
+  --seed <N>          The seed for --deterministic (default 0, implies it)
+
+Filters (for run, next and list):
+  --tag <TAG>         Only lessons tagged TAG, e.g. iterators or unsafe
+                      (repeat to require several)
+  --max-difficulty <LEVEL>
+                      Only lessons up to 1 (beginner), 2 (intermediate)
+                      or 3 (advanced)
+  --playlist <NAME>   Only the lessons of a playlist
+
+Selectors:
+  concurrency                   a whole section
+  ownership::lifetime_basics    a single example
+  ownership::*, *::closure_*    glob patterns (* and ?)
+  basics,ownership              comma-separated lists";
+
+/// A parsed command line
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    RunAll,
+    List,
+    Interactive,
+    Progress,
+    Next,
+    Quiz(String),
+    ExerciseList,
+    ExerciseCheck(String),
+    Run(Vec<Selector>),
+    Playlists,
+    Search(String),
+    Show(String),
+    Watch(String),
+    Export(export::Format, PathBuf),
+    /// Runs one lesson on behalf of an `--isolate` parent process
+    Lesson(String),
+    Help,
+}
+
+/// Options that change how lessons run, accepted anywhere on the command line
+#[derive(Debug, Default, PartialEq)]
+pub struct Options {
+    /// Run each lesson in a child process, killed after this long
+    pub isolate: Option<Duration>,
+    pub format: Format,
+    pub filter: Filter,
+    /// How the concurrency lessons schedule their threads
+    pub schedule: sched::Mode,
+}
+
+/// Narrows the lessons a command works on to those with the given tags,
+/// difficulty and playlist
+#[derive(Debug, Default, PartialEq)]
+pub struct Filter {
+    /// Tags a lesson must all have
+    pub tags: Vec<String>,
+    pub max_difficulty: Option<Difficulty>,
+    pub playlist: Option<&'static Playlist>,
+}
+
+impl Filter {
+    pub fn is_empty(&self) -> bool {
+        *self == Filter::default()
+    }
+
+    pub fn matches(&self, lesson: &dyn Lesson) -> bool {
+        self.tags
+            .iter()
+            .all(|tag| lesson.tags().iter().any(|have| have.eq_ignore_ascii_case(tag)))
+            && self.max_difficulty.is_none_or(|max| lesson.difficulty() <= max)
+            && self.playlist.is_none_or(|playlist| playlist.contains(lesson))
+    }
+
+    /// Keeps the selected lessons that match, failing if none do
+    pub fn apply(&self, selection: Selection) -> Result<Selection, String> {
+        let filtered: Selection = selection
+            .into_iter()
+            .map(|(section, lessons)| {
+                let lessons: Vec<_> = lessons.into_iter().filter(|lesson| self.matches(*lesson)).collect();
+                (section, lessons)
+            })
+            .filter(|(_, lessons)| !lessons.is_empty())
+            .collect();
+        if filtered.is_empty() {
+            return Err(format!("no lesson matches '{}'", self));
+        }
+        Ok(filtered)
+    }
+}
+
+/// The filter as it was given on the command line
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut parts = Vec::new();
+        for tag in &self.tags {
+            parts.push(format!("--tag {}", tag));
+        }
+        if let Some(max) = self.max_difficulty {
+            parts.push(format!("--max-difficulty {}", max as u8));
+        }
+        if let Some(playlist) = self.playlist {
+            parts.push(format!("--playlist {}", playlist.name));
+        }
+        write!(f, "{}", parts.join(" "))
+    }
+}
+
+/// Separates the options from the command and its arguments
+pub fn parse_options<I>(args: I) -> Result<(Options, Vec<String>), String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut options = Options::default();
+    let mut rest = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--isolate" => {
+                options.isolate.get_or_insert(isolate::DEFAULT_TIMEOUT);
+            }
+            "--timeout" => {
+                let seconds = args
+                    .next()
+                    .and_then(|value| value.parse::<f64>().ok())
+                    .filter(|seconds| seconds.is_finite() && *seconds > 0.0)
+                    .ok_or("--timeout needs a number of seconds, e.g. '--timeout 5'")?;
+                options.isolate = Some(Duration::from_secs_f64(seconds));
+            }
+            "--format" => {
+                options.format = args
+                    .next()
+                    .and_then(|name| Format::parse(&name))
+                    .ok_or("--format needs one of text, json or ndjson")?;
+            }
+            "--deterministic" => {
+                if options.schedule == sched::Mode::Threads {
+                    options.schedule = sched::Mode::Seeded(sched::DEFAULT_SEED);
+                }
+            }
+            "--seed" => {
+                let seed = args
+                    .next()
+                    .and_then(|value| value.parse().ok())
+                    .ok_or("--seed needs a whole number, e.g. '--seed 42'")?;
+                options.schedule = sched::Mode::Seeded(seed);
+            }
+            "--tag" => {
+                let tag = args.next().ok_or("--tag needs a tag, e.g. '--tag iterators'")?;
+                options.filter.tags.push(tag);
+            }
+            "--max-difficulty" => {
+                options.filter.max_difficulty = Some(
+                    args.next()
+                        .and_then(|level| Difficulty::parse(&level))
+                        .ok_or("--max-difficulty needs 1, 2 or 3 (or beginner, intermediate or advanced)")?,
+                );
+            }
+            "--playlist" => {
+                let name = args.next().unwrap_or_default();
+                let names: Vec<&str> = playlist::PLAYLISTS.iter().map(|playlist| playlist.name).collect();
+                options.filter.playlist = Some(playlist::find(&name).ok_or_else(|| {
+                    format!("--playlist needs one of {}", names.join(", "))
+                })?);
+            }
+            _ => rest.push(arg),
+        }
+    }
+    Ok((options, rest))
+}
+
+/// Parses the arguments that follow the program name
+pub fn parse_args<I>(args: I) -> Result<Command, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut args = args.into_iter();
+
+    let command = match args.next() {
+        None => return Ok(Command::RunAll),
+        Some(command) => command,
+    };
+
+    match command.as_str() {
+        "list" => Ok(Command::List),
+        "interactive" => Ok(Command::Interactive),
+        "progress" => Ok(Command::Progress),
+        "next" => Ok(Command::Next),
+        "playlists" => Ok(Command::Playlists),
+        "quiz" => match (args.next(), args.next()) {
+            (Some(section), None) => Ok(Command::Quiz(section)),
+            _ => Err("quiz needs exactly one section, e.g. 'happyr quiz ownership'".to_string()),
+        },
+        "exercise" => match (args.next().as_deref(), args.next(), args.next()) {
+            (Some("list"), None, None) => Ok(Command::ExerciseList),
+            (Some("check"), Some(id), None) => Ok(Command::ExerciseCheck(id)),
+            _ => Err("use 'happyr exercise list' or 'happyr exercise check <ID>'".to_string()),
+        },
+        "search" => {
+            let query: Vec<String> = args.collect();
+            if query.is_empty() {
+                Err("search needs something to look for, e.g. 'happyr search weak'".to_string())
+            } else {
+                Ok(Command::Search(query.join(" ")))
+            }
+        }
+        "show" => match (args.next(), args.next()) {
+            (Some(id), None) => Ok(Command::Show(id)),
+            _ => Err("show needs exactly one lesson, e.g. 'happyr show ownership::string_slices'".to_string()),
+        },
+        "watch" => match (args.next(), args.next()) {
+            (Some(id), None) => Ok(Command::Watch(id)),
+            _ => Err("watch needs exactly one lesson, e.g. 'happyr watch concurrency::arc_mutex'".to_string()),
+        },
+        "export" => {
+            let format = match args.next().as_deref() {
+                Some("--html") => export::Format::Html,
+                Some("--markdown") => export::Format::Markdown,
+                _ => return Err("export needs --html <DIR> or --markdown <DIR>".to_string()),
+            };
+            match (args.next(), args.next()) {
+                (Some(dir), None) => Ok(Command::Export(format, PathBuf::from(dir))),
+                _ => Err("export needs exactly one output directory".to_string()),
+            }
+        }
+        "__lesson" => match (args.next(), args.next()) {
+            (Some(id), None) => Ok(Command::Lesson(id)),
+            _ => Err("__lesson needs exactly one lesson id".to_string()),
+        },
+        "help" | "-h" | "--help" => Ok(Command::Help),
+        "run" => {
+            let mut selectors = Vec::new();
+            for arg in args {
+                for part in arg.split(',').filter(|part| !part.is_empty()) {
+                    selectors.push(Selector::parse(part)?);
+                }
+            }
+            if selectors.is_empty() {
+                Ok(Command::RunAll)
+            } else {
+                Ok(Command::Run(selectors))
+            }
+        }
+        other => Err(format!("unknown command '{}'", other)),
+    }
+}
+
+/// A pattern selecting a section, or examples within sections
+///
+/// `section` matches section ids and `example` (after `::`) matches
+/// example function names. Both may contain `*` and `?` wildcards.
+#[derive(Debug, PartialEq)]
+pub struct Selector {
+    pub section: String,
+    pub example: Option<String>,
+}
+
+impl Selector {
+    pub fn parse(text: &str) -> Result<Selector, String> {
+        let (section, example) = match text.split_once("::") {
+            Some((section, example)) => (section, Some(example)),
+            None => (text, None),
+        };
+
+        if section.is_empty() || example == Some("") {
+            return Err(format!("invalid selector '{}'", text));
+        }
+
+        Ok(Selector {
+            section: section.to_string(),
+            example: example.map(str::to_string),
+        })
+    }
+
+    fn matches(&self, section: &str, example: &str) -> bool {
+        glob_match(&self.section, section)
+            && self
+                .example
+                .as_deref()
+                .is_none_or(|pattern| glob_match(pattern, example))
+    }
+}
+
+impl std::fmt::Display for Selector {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match &self.example {
+            Some(example) => write!(f, "{}::{}", self.section, example),
+            None => write!(f, "{}", self.section),
+        }
+    }
+}
+
+/// Matches `text` against a pattern where `*` matches any run of
+/// characters and `?` matches exactly one character
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` seen, and the text position it matched up to
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            // Let the last `*` swallow one more character and retry
+            p = star + 1;
+            t = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Lessons picked by a set of selectors, grouped by section
+pub type Selection = Vec<(&'static Section, Vec<&'static dyn Lesson>)>;
+
+/// Every lesson of every section
+pub fn select_all(sections: &[&'static Section]) -> Selection {
+    sections
+        .iter()
+        .map(|&section| (section, section.lessons.to_vec()))
+        .collect()
+}
+
+/// Resolves selectors against the sections, keeping teaching order
+///
+/// Returns every section with at least one selected lesson, paired with
+/// the selected lessons. Fails if any selector matches nothing.
+pub fn select(sections: &[&'static Section], selectors: &[Selector]) -> Result<Selection, String> {
+    for selector in selectors {
+        let found = sections.iter().any(|section| {
+            section
+                .lessons
+                .iter()
+                .any(|lesson| selector.matches(section.id, lesson.name()))
+        });
+        if !found {
+            return Err(format!("no lesson matches '{}'", selector));
+        }
+    }
+
+    Ok(sections
+        .iter()
+        .map(|&section| {
+            let lessons: Vec<_> = section
+                .lessons
+                .iter()
+                .copied()
+                .filter(|lesson| selectors.iter().any(|s| s.matches(section.id, lesson.name())))
+                .collect();
+            (section, lessons)
+        })
+        .filter(|(_, lessons)| !lessons.is_empty())
+        .collect())
+}
+
+// ============================================================================
+// UNIT TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lesson::lesson;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn noop(_: &mut dyn std::io::Write) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    use noop as ownership_basics;
+    use noop as lifetime_basics;
+    use noop as closure_basics;
+    use noop as closure_traits;
+
+    static OWNERSHIP: Section = Section {
+        id: "ownership",
+        number: 1,
+        title: "OWNERSHIP",
+        icon: "🔐",
+        lessons: &[
+            &lesson!(ownership_basics, "Ownership Basics"),
+            &lesson!(lifetime_basics, "Lifetime Basics"),
+        ],
+    };
+
+    static FUNCTIONAL: Section = Section {
+        id: "functional",
+        number: 2,
+        title: "FUNCTIONAL",
+        icon: "🔄",
+        lessons: &[
+            &lesson!(closure_basics, "Closure Basics").with_tags(&["closures"]),
+            &lesson!(closure_traits, "Closure Traits")
+                .with_difficulty(Difficulty::Advanced)
+                .with_tags(&["closures", "traits"]),
+        ],
+    };
+
+    static SECTIONS: &[&Section] = &[&OWNERSHIP, &FUNCTIONAL];
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(parse_args(args(&[])), Ok(Command::RunAll));
+        assert_eq!(parse_args(args(&["list"])), Ok(Command::List));
+        assert_eq!(parse_args(args(&["interactive"])), Ok(Command::Interactive));
+        assert_eq!(parse_args(args(&["next"])), Ok(Command::Next));
+        assert_eq!(parse_args(args(&["playlists"])), Ok(Command::Playlists));
+        assert_eq!(parse_args(args(&["quiz", "ownership"])), Ok(Command::Quiz("ownership".into())));
+        assert!(parse_args(args(&["quiz"])).is_err());
+        assert_eq!(parse_args(args(&["exercise", "list"])), Ok(Command::ExerciseList));
+        assert_eq!(
+            parse_args(args(&["exercise", "check", "ownership/first_word"])),
+            Ok(Command::ExerciseCheck("ownership/first_word".into()))
+        );
+        assert!(parse_args(args(&["exercise", "check"])).is_err());
+        assert_eq!(
+            parse_args(args(&["export", "--markdown", "book"])),
+            Ok(Command::Export(export::Format::Markdown, PathBuf::from("book")))
+        );
+        assert!(parse_args(args(&["export", "book"])).is_err());
+        assert_eq!(parse_args(args(&["show", "a::b"])), Ok(Command::Show("a::b".into())));
+        assert_eq!(parse_args(args(&["watch", "a::b"])), Ok(Command::Watch("a::b".into())));
+        assert!(parse_args(args(&["watch"])).is_err());
+        assert_eq!(parse_args(args(&["search", "weak", "rc"])), Ok(Command::Search("weak rc".into())));
+        assert!(parse_args(args(&["search"])).is_err());
+        assert_eq!(parse_args(args(&["--help"])), Ok(Command::Help));
+        assert!(parse_args(args(&["frobnicate"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_options() {
+        let (options, rest) = parse_options(args(&["run", "--isolate", "basics"])).unwrap();
+        assert_eq!(options.isolate, Some(isolate::DEFAULT_TIMEOUT));
+        assert_eq!(rest, args(&["run", "basics"]));
+
+        let (options, rest) = parse_options(args(&["--timeout", "2.5", "--isolate"])).unwrap();
+        assert_eq!(options.isolate, Some(Duration::from_millis(2500)));
+        assert!(rest.is_empty());
+
+        assert_eq!(parse_options(args(&["list"])).unwrap().0, Options::default());
+        assert!(parse_options(args(&["--timeout"])).is_err());
+        assert!(parse_options(args(&["--timeout", "-1"])).is_err());
+
+        let (options, _) = parse_options(args(&["run", "--format", "ndjson"])).unwrap();
+        assert_eq!(options.format, Format::Ndjson);
+        assert!(parse_options(args(&["--format", "yaml"])).is_err());
+
+        let (options, _) = parse_options(args(&["--deterministic"])).unwrap();
+        assert_eq!(options.schedule, sched::Mode::Seeded(sched::DEFAULT_SEED));
+        let (options, _) = parse_options(args(&["--seed", "42", "--deterministic"])).unwrap();
+        assert_eq!(options.schedule, sched::Mode::Seeded(42));
+        assert!(parse_options(args(&["--seed", "-3"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_filters() {
+        let (options, rest) =
+            parse_options(args(&["run", "--tag", "iterators", "--max-difficulty", "2", "functional"])).unwrap();
+        assert_eq!(options.filter.tags, ["iterators"]);
+        assert_eq!(options.filter.max_difficulty, Some(Difficulty::Intermediate));
+        assert_eq!(options.filter.to_string(), "--tag iterators --max-difficulty 2");
+        assert_eq!(rest, args(&["run", "functional"]));
+
+        let (options, _) = parse_options(args(&["--playlist", "beginner"])).unwrap();
+        assert_eq!(options.filter.playlist.map(|playlist| playlist.name), Some("beginner"));
+        assert!(parse_options(args(&["--playlist", "expert"])).is_err());
+        assert!(parse_options(args(&["--max-difficulty", "hard"])).is_err());
+        assert!(parse_options(args(&["--tag"])).is_err());
+    }
+
+    #[test]
+    fn test_filter_by_tag_and_difficulty() {
+        let filter = Filter {
+            tags: vec!["closures".into()],
+            max_difficulty: Some(Difficulty::Intermediate),
+            playlist: None,
+        };
+        let names: Vec<&str> = filter
+            .apply(select_all(SECTIONS))
+            .unwrap()
+            .iter()
+            .flat_map(|(_, lessons)| lessons.iter().map(|lesson| lesson.name()))
+            .collect();
+        assert_eq!(names, ["closure_basics"]);
+
+        let nothing = Filter {
+            tags: vec!["unsafe".into()],
+            ..Filter::default()
+        };
+        assert_eq!(
+            nothing.apply(select_all(SECTIONS)).err().as_deref(),
+            Some("no lesson matches '--tag unsafe'")
+        );
+    }
+
+    #[test]
+    fn test_parse_selectors() {
+        let command = parse_args(args(&["run", "basics,ownership::lifetime_basics"]));
+        assert_eq!(
+            command,
+            Ok(Command::Run(vec![
+                Selector { section: "basics".into(), example: None },
+                Selector {
+                    section: "ownership".into(),
+                    example: Some("lifetime_basics".into()),
+                },
+            ]))
+        );
+        assert!(parse_args(args(&["run", "::closure_basics"])).is_err());
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("concurrency", "concurrency"));
+        assert!(glob_match("closure_*", "closure_traits"));
+        assert!(glob_match("*_basics", "ownership_basics"));
+        assert!(glob_match("own?rship", "ownership"));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match("closure_*", "iterator_basics"));
+        assert!(!glob_match("own", "ownership"));
+    }
+
+    #[test]
+    fn test_select_keeps_teaching_order() {
+        let selectors = vec![
+            Selector::parse("*::closure_traits").unwrap(),
+            Selector::parse("ownership").unwrap(),
+        ];
+        let selected = select(SECTIONS, &selectors).unwrap();
+
+        let names: Vec<Vec<&str>> = selected
+            .iter()
+            .map(|(_, lessons)| lessons.iter().map(|lesson| lesson.name()).collect())
+            .collect();
+        assert_eq!(
+            names,
+            vec![vec!["ownership_basics", "lifetime_basics"], vec!["closure_traits"]]
+        );
+    }
+
+    #[test]
+    fn test_select_unknown_lesson() {
+        let selectors = vec![Selector::parse("ownership::nope").unwrap()];
+        assert!(select(SECTIONS, &selectors).is_err());
+    }
+}
@@ -8,10 +8,20 @@
 // - Other collections (VecDeque, HashSet, BTreeMap, etc.)
 // ============================================================================
 
+// vec! and push are spelled out to show how vectors are built.
+#![allow(
+    clippy::useless_vec,
+    clippy::vec_init_then_push,
+)]
+
+use crate::bench::{black_box, Bench};
+use crate::lesson::{lesson, Difficulty, Section};
+use std::io::{self, Write};
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::collections::VecDeque;
 use std::collections::BTreeMap;
+use std::collections::LinkedList;
 
 /// Demonstrates vector basics
 /// 
@@ -19,36 +29,36 @@ use std::collections::BTreeMap;
 /// - Growable arrays stored on the heap
 /// - Type: Vec<T>
 /// - Can only store values of the same type
-pub fn vector_basics() {
-    println!("\n--- Vector Basics ---");
+pub fn vector_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Vector Basics ---")?;
     
     // Creating vectors
     let v1: Vec<i32> = Vec::new();
-    println!("Empty vector: {:?}", v1);
+    writeln!(out, "Empty vector: {:?}", v1)?;
     
     let v2 = vec![1, 2, 3]; // vec! macro
-    println!("Vector with values: {:?}", v2);
+    writeln!(out, "Vector with values: {:?}", v2)?;
     
     // Adding elements
     let mut v3 = Vec::new();
     v3.push(5);
     v3.push(6);
     v3.push(7);
-    println!("After pushes: {:?}", v3);
+    writeln!(out, "After pushes: {:?}", v3)?;
     
     // Accessing elements
     let third = &v2[2]; // Panics if out of bounds
-    println!("Third element: {}", third);
+    writeln!(out, "Third element: {}", third)?;
     
     match v2.get(2) { // Returns Option<&T>
-        Some(third) => println!("Third element (safe): {}", third),
-        None => println!("No third element"),
+        Some(third) => writeln!(out, "Third element (safe): {}", third)?,
+        None => writeln!(out, "No third element")?,
     }
     
     // Iterating
-    println!("Iterating over vector:");
+    writeln!(out, "Iterating over vector:")?;
     for i in &v2 {
-        println!("  {}", i);
+        writeln!(out, "  {}", i)?;
     }
     
     // Mutable iteration
@@ -56,51 +66,55 @@ pub fn vector_basics() {
     for i in &mut v4 {
         *i += 50;
     }
-    println!("After mutation: {:?}", v4);
+    writeln!(out, "After mutation: {:?}", v4)?;
+    
+    Ok(())
 }
 
 /// Demonstrates advanced vector operations
-pub fn vector_operations() {
-    println!("\n--- Vector Operations ---");
+pub fn vector_operations(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Vector Operations ---")?;
     
     let mut v = vec![1, 2, 3, 4, 5];
     
     // Pop removes last element
     let last = v.pop();
-    println!("Popped: {:?}, Vector: {:?}", last, v);
+    writeln!(out, "Popped: {:?}, Vector: {:?}", last, v)?;
     
     // Insert at index
     v.insert(2, 99);
-    println!("After insert: {:?}", v);
+    writeln!(out, "After insert: {:?}", v)?;
     
     // Remove at index
     let removed = v.remove(2);
-    println!("Removed: {}, Vector: {:?}", removed, v);
+    writeln!(out, "Removed: {}, Vector: {:?}", removed, v)?;
     
     // Length and capacity
-    println!("Length: {}, Capacity: {}", v.len(), v.capacity());
+    writeln!(out, "Length: {}, Capacity: {}", v.len(), v.capacity())?;
     
     // Clear vector
     let mut v2 = vec![1, 2, 3];
     v2.clear();
-    println!("After clear: {:?}", v2);
+    writeln!(out, "After clear: {:?}", v2)?;
     
     // Extend with another vector
     let mut v3 = vec![1, 2, 3];
     let v4 = vec![4, 5, 6];
     v3.extend(v4);
-    println!("After extend: {:?}", v3);
+    writeln!(out, "After extend: {:?}", v3)?;
     
     // Concatenate with append
     let mut v5 = vec![1, 2];
     let mut v6 = vec![3, 4];
     v5.append(&mut v6);
-    println!("After append: {:?}, v6: {:?}", v5, v6);
+    writeln!(out, "After append: {:?}, v6: {:?}", v5, v6)?;
+    
+    Ok(())
 }
 
 /// Demonstrates storing different types in vectors using enums
-pub fn vector_enum_storage() {
-    println!("\n--- Vector with Different Types ---");
+pub fn vector_enum_storage(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Vector with Different Types ---")?;
     
     let row = vec![
         SpreadsheetCell::Int(3),
@@ -110,11 +124,13 @@ pub fn vector_enum_storage() {
     
     for cell in &row {
         match cell {
-            SpreadsheetCell::Int(i) => println!("Integer: {}", i),
-            SpreadsheetCell::Float(f) => println!("Float: {}", f),
-            SpreadsheetCell::Text(s) => println!("Text: {}", s),
+            SpreadsheetCell::Int(i) => writeln!(out, "Integer: {}", i)?,
+            SpreadsheetCell::Float(f) => writeln!(out, "Float: {}", f)?,
+            SpreadsheetCell::Text(s) => writeln!(out, "Text: {}", s)?,
         }
     }
+    
+    Ok(())
 }
 
 enum SpreadsheetCell {
@@ -129,26 +145,26 @@ enum SpreadsheetCell {
 /// - UTF-8 encoded, growable text
 /// - Type: String (owned) vs &str (borrowed)
 /// - Collection of bytes
-pub fn string_operations() {
-    println!("\n--- String Operations ---");
+pub fn string_operations(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- String Operations ---")?;
     
     // Creating strings
     let mut s1 = String::new();
     let s2 = "initial contents".to_string();
     let s3 = String::from("initial contents");
-    println!("Strings: '{}', '{}', '{}'", s1, s2, s3);
+    writeln!(out, "Strings: '{}', '{}', '{}'", s1, s2, s3)?;
     
     // Updating strings
     s1.push_str("hello");
     s1.push(' '); // Push single char
     s1.push_str("world");
-    println!("Built string: '{}'", s1);
+    writeln!(out, "Built string: '{}'", s1)?;
     
     // Concatenation with +
     let s4 = String::from("Hello, ");
     let s5 = String::from("world!");
     let s6 = s4 + &s5; // s4 moved, s5 borrowed
-    println!("Concatenated: '{}'", s6);
+    writeln!(out, "Concatenated: '{}'", s6)?;
     // println!("{}", s4); // ❌ Error! s4 was moved
     
     // Format macro (doesn't take ownership)
@@ -156,33 +172,37 @@ pub fn string_operations() {
     let s8 = String::from("tac");
     let s9 = String::from("toe");
     let s10 = format!("{}-{}-{}", s7, s8, s9);
-    println!("Formatted: '{}'", s10);
-    println!("Still valid: {}, {}, {}", s7, s8, s9);
+    writeln!(out, "Formatted: '{}'", s10)?;
+    writeln!(out, "Still valid: {}, {}, {}", s7, s8, s9)?;
+    
+    Ok(())
 }
 
 /// Demonstrates string indexing and slicing
-pub fn string_indexing() {
-    println!("\n--- String Indexing ---");
+pub fn string_indexing(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- String Indexing ---")?;
     
-    let s = String::from("hello");
-    // let c = s[0]; // ❌ Error! Can't index strings directly
+    let _s = String::from("hello");
+    // let c = _s[0]; // ❌ Error! Can't index strings directly
     
     // String slicing (be careful with UTF-8!)
     let hello = "Здравствуйте"; // Russian "Hello"
     let s = &hello[0..4]; // First 4 bytes (2 chars in Cyrillic)
-    println!("Slice: '{}'", s);
+    writeln!(out, "Slice: '{}'", s)?;
     
     // Iterating over chars
-    println!("Characters:");
+    writeln!(out, "Characters:")?;
     for c in "नमस्ते".chars() { // Hindi "Hello"
-        println!("  {}", c);
+        writeln!(out, "  {}", c)?;
     }
     
     // Iterating over bytes
-    println!("Bytes:");
+    writeln!(out, "Bytes:")?;
     for b in "hello".bytes() {
-        println!("  {}", b);
+        writeln!(out, "  {}", b)?;
     }
+    
+    Ok(())
 }
 
 /// Demonstrates HashMap basics
@@ -191,8 +211,8 @@ pub fn string_indexing() {
 /// - Store key-value pairs
 /// - Type: HashMap<K, V>
 /// - Keys must implement Eq and Hash
-pub fn hashmap_basics() {
-    println!("\n--- HashMap Basics ---");
+pub fn hashmap_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- HashMap Basics ---")?;
     
     // Creating hash maps
     let mut scores = HashMap::new();
@@ -200,32 +220,34 @@ pub fn hashmap_basics() {
     // Inserting values
     scores.insert(String::from("Blue"), 10);
     scores.insert(String::from("Yellow"), 50);
-    println!("Scores: {:?}", scores);
+    writeln!(out, "Scores: {:?}", scores)?;
     
     // Accessing values
     let team_name = String::from("Blue");
     let score = scores.get(&team_name);
     match score {
-        Some(s) => println!("Blue team score: {}", s),
-        None => println!("Team not found"),
+        Some(s) => writeln!(out, "Blue team score: {}", s)?,
+        None => writeln!(out, "Team not found")?,
     }
     
     // Iterating
-    println!("All scores:");
+    writeln!(out, "All scores:")?;
     for (key, value) in &scores {
-        println!("  {}: {}", key, value);
+        writeln!(out, "  {}: {}", key, value)?;
     }
     
     // Creating from vectors
     let teams = vec![String::from("Red"), String::from("Green")];
     let initial_scores = vec![20, 30];
     let scores2: HashMap<_, _> = teams.iter().zip(initial_scores.iter()).collect();
-    println!("Scores from vectors: {:?}", scores2);
+    writeln!(out, "Scores from vectors: {:?}", scores2)?;
+    
+    Ok(())
 }
 
 /// Demonstrates HashMap ownership
-pub fn hashmap_ownership() {
-    println!("\n--- HashMap Ownership ---");
+pub fn hashmap_ownership(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- HashMap Ownership ---")?;
     
     let field_name = String::from("Favorite color");
     let field_value = String::from("Blue");
@@ -240,24 +262,26 @@ pub fn hashmap_ownership() {
     let value = String::from("Value");
     let mut map2 = HashMap::new();
     map2.insert(&key, &value);
-    println!("Key still valid: {}", key);
+    writeln!(out, "Key still valid: {}", key)?;
+    
+    Ok(())
 }
 
 /// Demonstrates HashMap updates
-pub fn hashmap_updates() {
-    println!("\n--- HashMap Updates ---");
+pub fn hashmap_updates(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- HashMap Updates ---")?;
     
     let mut scores = HashMap::new();
     
     // Overwriting values
     scores.insert(String::from("Blue"), 10);
     scores.insert(String::from("Blue"), 25);
-    println!("After overwrite: {:?}", scores);
+    writeln!(out, "After overwrite: {:?}", scores)?;
     
     // Only insert if key doesn't exist
     scores.entry(String::from("Yellow")).or_insert(50);
     scores.entry(String::from("Blue")).or_insert(50); // Won't insert
-    println!("After or_insert: {:?}", scores);
+    writeln!(out, "After or_insert: {:?}", scores)?;
     
     // Update based on old value
     let text = "hello world wonderful world";
@@ -268,7 +292,9 @@ pub fn hashmap_updates() {
         *count += 1;
     }
     
-    println!("Word counts: {:?}", map);
+    writeln!(out, "Word counts: {:?}", map)?;
+    
+    Ok(())
 }
 
 /// Demonstrates HashSet
@@ -277,8 +303,8 @@ pub fn hashmap_updates() {
 /// - Collection of unique values
 /// - Type: HashSet<T>
 /// - Like HashMap but only keys, no values
-pub fn hashset_basics() {
-    println!("\n--- HashSet Basics ---");
+pub fn hashset_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- HashSet Basics ---")?;
     
     let mut books = HashSet::new();
     
@@ -287,37 +313,39 @@ pub fn hashset_basics() {
     books.insert("Programming Rust");
     books.insert("The Rust Book"); // Duplicate, won't be added
     
-    println!("Books: {:?}", books);
-    println!("Number of books: {}", books.len());
+    writeln!(out, "Books: {:?}", books)?;
+    writeln!(out, "Number of books: {}", books.len())?;
     
     // Checking membership
     if books.contains("The Rust Book") {
-        println!("We have The Rust Book!");
+        writeln!(out, "We have The Rust Book!")?;
     }
     
     // Set operations
     let set1: HashSet<_> = [1, 2, 3, 4].iter().cloned().collect();
     let set2: HashSet<_> = [3, 4, 5, 6].iter().cloned().collect();
     
-    println!("Set 1: {:?}", set1);
-    println!("Set 2: {:?}", set2);
+    writeln!(out, "Set 1: {:?}", set1)?;
+    writeln!(out, "Set 2: {:?}", set2)?;
     
     // Union
     let union: HashSet<_> = set1.union(&set2).cloned().collect();
-    println!("Union: {:?}", union);
+    writeln!(out, "Union: {:?}", union)?;
     
     // Intersection
     let intersection: HashSet<_> = set1.intersection(&set2).cloned().collect();
-    println!("Intersection: {:?}", intersection);
+    writeln!(out, "Intersection: {:?}", intersection)?;
     
     // Difference
     let difference: HashSet<_> = set1.difference(&set2).cloned().collect();
-    println!("Difference: {:?}", difference);
+    writeln!(out, "Difference: {:?}", difference)?;
+    
+    Ok(())
 }
 
 /// Demonstrates VecDeque (double-ended queue)
-pub fn vecdeque_basics() {
-    println!("\n--- VecDeque Basics ---");
+pub fn vecdeque_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- VecDeque Basics ---")?;
     
     let mut deque = VecDeque::new();
     
@@ -326,18 +354,20 @@ pub fn vecdeque_basics() {
     deque.push_back(2);
     deque.push_front(0);
     
-    println!("Deque: {:?}", deque);
+    writeln!(out, "Deque: {:?}", deque)?;
     
     // Pop from both ends
     let back = deque.pop_back();
     let front = deque.pop_front();
-    println!("Popped back: {:?}, front: {:?}", back, front);
-    println!("Remaining: {:?}", deque);
+    writeln!(out, "Popped back: {:?}, front: {:?}", back, front)?;
+    writeln!(out, "Remaining: {:?}", deque)?;
+    
+    Ok(())
 }
 
 /// Demonstrates BTreeMap (sorted map)
-pub fn btreemap_basics() {
-    println!("\n--- BTreeMap Basics ---");
+pub fn btreemap_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- BTreeMap Basics ---")?;
     
     let mut map = BTreeMap::new();
     
@@ -346,30 +376,193 @@ pub fn btreemap_basics() {
     map.insert(2, "two");
     
     // Automatically sorted by key
-    println!("BTreeMap (sorted): {:?}", map);
+    writeln!(out, "BTreeMap (sorted): {:?}", map)?;
     
     for (key, value) in &map {
-        println!("  {}: {}", key, value);
+        writeln!(out, "  {}: {}", key, value)?;
     }
+    
+    Ok(())
+}
+
+/// Benchmarks Vec, VecDeque and LinkedList
+/// 
+/// CHOOSING A SEQUENCE:
+/// - Vec: one contiguous buffer, the fastest to push onto and walk through
+/// - VecDeque: a ring buffer, so pushing at the front is as cheap as the back
+/// - LinkedList: one allocation per element, scattered around memory
+/// - Vec::insert(0, x) shifts every element, so it's O(n) per insert
+/// - Reach for Vec first; switch when a benchmark says so
+pub fn sequence_benchmarks(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Vec vs VecDeque vs LinkedList ---")?;
+    
+    const N: u64 = 1_000;
+    
+    let mut bench = Bench::new();
+    bench.run("Vec push_back", || {
+        let mut v = Vec::new();
+        for i in 0..black_box(N) {
+            v.push(i);
+        }
+        v
+    });
+    bench.run("VecDeque push_back", || {
+        let mut d = VecDeque::new();
+        for i in 0..black_box(N) {
+            d.push_back(i);
+        }
+        d
+    });
+    bench.run("LinkedList push_back", || {
+        let mut l = LinkedList::new();
+        for i in 0..black_box(N) {
+            l.push_back(i);
+        }
+        l
+    });
+    writeln!(out, "Appending {} numbers:", N)?;
+    bench.write_table(out)?;
+    
+    let mut bench = Bench::new();
+    bench.run("Vec insert(0, ..)", || {
+        let mut v = Vec::new();
+        for i in 0..black_box(N) {
+            v.insert(0, i);
+        }
+        v
+    });
+    bench.run("VecDeque push_front", || {
+        let mut d = VecDeque::new();
+        for i in 0..black_box(N) {
+            d.push_front(i);
+        }
+        d
+    });
+    bench.run("LinkedList push_front", || {
+        let mut l = LinkedList::new();
+        for i in 0..black_box(N) {
+            l.push_front(i);
+        }
+        l
+    });
+    writeln!(out, "\nPrepending {} numbers:", N)?;
+    bench.write_table(out)?;
+    
+    let v: Vec<u64> = (0..N).collect();
+    let d: VecDeque<u64> = (0..N).collect();
+    let l: LinkedList<u64> = (0..N).collect();
+    let mut bench = Bench::new();
+    bench.run("Vec sum", || black_box(&v).iter().sum::<u64>());
+    bench.run("VecDeque sum", || black_box(&d).iter().sum::<u64>());
+    bench.run("LinkedList sum", || black_box(&l).iter().sum::<u64>());
+    writeln!(out, "\nSumming {} numbers:", N)?;
+    bench.write_table(out)?;
+    
+    Ok(())
+}
+
+/// Benchmarks HashMap against BTreeMap
+/// 
+/// CHOOSING A MAP:
+/// - HashMap: O(1) average lookups, iterates in no particular order
+/// - BTreeMap: O(log n) lookups, but keeps its keys sorted
+/// - Needing sorted output can make BTreeMap the faster choice overall
+pub fn map_benchmarks(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- HashMap vs BTreeMap ---")?;
+    
+    const N: u64 = 1_000;
+    // Spread the keys out so they don't arrive already sorted
+    let keys: Vec<u64> = (0..N).map(|i| i.wrapping_mul(2_654_435_761) % 1_000_003).collect();
+    
+    let mut bench = Bench::new();
+    bench.run("HashMap insert", || {
+        let mut map = HashMap::new();
+        for &key in black_box(&keys) {
+            map.insert(key, key);
+        }
+        map
+    });
+    bench.run("BTreeMap insert", || {
+        let mut map = BTreeMap::new();
+        for &key in black_box(&keys) {
+            map.insert(key, key);
+        }
+        map
+    });
+    writeln!(out, "Inserting {} keys:", N)?;
+    bench.write_table(out)?;
+    
+    let hash: HashMap<u64, u64> = keys.iter().map(|&key| (key, key)).collect();
+    let tree: BTreeMap<u64, u64> = keys.iter().map(|&key| (key, key)).collect();
+    let mut bench = Bench::new();
+    bench.run("HashMap get", || {
+        black_box(&keys).iter().filter(|key| hash.contains_key(key)).count()
+    });
+    bench.run("BTreeMap get", || {
+        black_box(&keys).iter().filter(|key| tree.contains_key(key)).count()
+    });
+    writeln!(out, "\nLooking up {} keys:", N)?;
+    bench.write_table(out)?;
+    
+    let mut bench = Bench::new();
+    bench.run("HashMap collect + sort", || {
+        let mut entries: Vec<_> = black_box(&hash).iter().collect();
+        entries.sort();
+        entries
+    });
+    bench.run("BTreeMap iterate", || black_box(&tree).iter().collect::<Vec<_>>());
+    writeln!(out, "\nListing {} entries in key order:", N)?;
+    bench.write_table(out)?;
+    
+    Ok(())
 }
 
 // ============================================================================
 // PUBLIC INTERFACE
 // ============================================================================
 
-pub fn run_all_examples() {
-    vector_basics();
-    vector_operations();
-    vector_enum_storage();
-    string_operations();
-    string_indexing();
-    hashmap_basics();
-    hashmap_ownership();
-    hashmap_updates();
-    hashset_basics();
-    vecdeque_basics();
-    btreemap_basics();
-}
+/// This module's lessons, in teaching order
+pub static SECTION: Section = Section {
+    id: "collections",
+    number: 5,
+    title: "COLLECTIONS",
+    icon: "📦",
+    lessons: &[
+        &lesson!(vector_basics, "Vector Basics").with_tags(&["std::collections", "vectors"]),
+        &lesson!(vector_operations, "Vector Operations")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["std::collections", "vectors", "iterators"]),
+        &lesson!(vector_enum_storage, "Vector with Different Types")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["std::collections", "vectors", "enums"]),
+        &lesson!(string_operations, "String Operations").with_tags(&["strings"]),
+        &lesson!(string_indexing, "String Indexing")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["strings", "utf-8"]),
+        &lesson!(hashmap_basics, "HashMap Basics").with_tags(&["std::collections", "hashmap"]),
+        &lesson!(hashmap_ownership, "HashMap Ownership")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["std::collections", "hashmap", "ownership"]),
+        &lesson!(hashmap_updates, "HashMap Updates")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["std::collections", "hashmap"]),
+        &lesson!(hashset_basics, "HashSet Basics")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["std::collections", "hashset"]),
+        &lesson!(vecdeque_basics, "VecDeque Basics")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["std::collections", "vecdeque"]),
+        &lesson!(btreemap_basics, "BTreeMap Basics")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["std::collections", "btreemap"]),
+        &lesson!(sequence_benchmarks, "Vec vs VecDeque vs LinkedList")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["std::collections", "vectors", "vecdeque", "performance"]),
+        &lesson!(map_benchmarks, "HashMap vs BTreeMap")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["std::collections", "hashmap", "btreemap", "performance"]),
+    ],
+};
 
 // ============================================================================
 // UNIT TESTS
@@ -0,0 +1,479 @@
+// ============================================================================
+// COMMANDS
+// ============================================================================
+// What each `happyr` command does once its arguments are parsed: one
+// function per command, which prints to stdout and returns a `Failure` for
+// `main` to report and exit with. `Runner` is shared by every command that
+// runs lessons, so they all record progress, honor `--isolate` and write
+// `--format json` the same way.
+// ============================================================================
+
+use crate::cli::{self, Filter, Options, Selector};
+use crate::lesson::{Lesson, Section};
+use crate::progress::{self, Progress, Status};
+use crate::report::{self, Format};
+use crate::{exercise, export, interactive, isolate, json, playlist, quiz, registry, search, show, watch};
+use std::fmt;
+use std::io::{self, IsTerminal, Write};
+use std::path::Path;
+use std::time::Duration;
+
+/// Why a command didn't succeed
+#[derive(Debug)]
+pub struct Failure {
+    /// For stderr, after "error: ", unless what went wrong was already
+    /// printed
+    pub message: Option<String>,
+    /// What the process should exit with
+    pub code: i32,
+    /// The kind of I/O error behind it, if any, so `main` can tell stdout
+    /// being closed early, as by `happyr list | head`, from a real problem
+    pub kind: Option<io::ErrorKind>,
+}
+
+impl Failure {
+    fn new(message: impl Into<String>) -> Failure {
+        Failure {
+            message: Some(message.into()),
+            code: 1,
+            kind: None,
+        }
+    }
+
+    /// `error`, explained as "<context>: <error>"
+    fn io(context: impl fmt::Display, error: io::Error) -> Failure {
+        Failure {
+            kind: Some(error.kind()),
+            ..Failure::new(format!("{}: {}", context, error))
+        }
+    }
+
+    /// A failure whose explanation is already on the screen
+    fn silent() -> Failure {
+        Failure {
+            message: None,
+            code: 1,
+            kind: None,
+        }
+    }
+}
+
+impl From<io::Error> for Failure {
+    fn from(error: io::Error) -> Failure {
+        Failure {
+            kind: Some(error.kind()),
+            ..Failure::new(error.to_string())
+        }
+    }
+}
+
+/// Runs lessons one after another and records them in the learner's
+/// progress, either in this process or, with `--isolate`, each in a child
+/// process so a lesson that panics or hangs doesn't end the run. With
+/// `--format json` or `ndjson` it writes records instead of lesson output.
+pub struct Runner {
+    progress: Progress,
+    timeout: Option<Duration>,
+    format: Format,
+    reports: Vec<isolate::Report>,
+    /// The JSON records collected for `--format json`
+    records: Vec<json::Value>,
+}
+
+impl Runner {
+    pub fn new(options: &Options) -> Runner {
+        Runner {
+            progress: open_progress(),
+            timeout: options.isolate,
+            format: options.format,
+            reports: Vec::new(),
+            records: Vec::new(),
+        }
+    }
+
+    /// Whether output is for people rather than programs
+    fn is_text(&self) -> bool {
+        self.format == Format::Text
+    }
+
+    pub fn banner(&self, section: &Section) -> io::Result<()> {
+        if self.is_text() {
+            let mut out = io::stdout().lock();
+            writeln!(out, "\n{}", section.banner())?;
+            writeln!(out, "{}", "=".repeat(70))?;
+        }
+        Ok(())
+    }
+
+    pub fn run(&mut self, lesson: &dyn Lesson) -> Result<(), Failure> {
+        record(&mut self.progress, lesson, Status::Started);
+        let report = match self.timeout {
+            Some(timeout) => isolate::run_lesson(&lesson.id(), timeout).map_err(|error| {
+                Failure::new(format!("can't start a process for {}: {}", lesson.id(), error))
+            })?,
+            None if !self.is_text() => report::capture(lesson),
+            None => {
+                let mut out = io::stdout().lock();
+                lesson
+                    .run(&mut out)
+                    .and_then(|_| out.flush())
+                    .map_err(|error| Failure::io(format!("{} failed", lesson.id()), error))?;
+                record(&mut self.progress, lesson, Status::Done);
+                return Ok(());
+            }
+        };
+        if report.outcome == isolate::Outcome::Passed {
+            record(&mut self.progress, lesson, Status::Done);
+        }
+
+        let mut out = io::stdout().lock();
+        match self.format {
+            Format::Text => {
+                write!(out, "{}", report.stdout)?;
+                eprint!("{}", report.stderr);
+                match &report.outcome {
+                    isolate::Outcome::Passed => {}
+                    isolate::Outcome::TimedOut => writeln!(
+                        out,
+                        "⏱️  {} timed out after {:.1}s",
+                        lesson.id(),
+                        self.timeout.unwrap_or_default().as_secs_f64()
+                    )?,
+                    _ => writeln!(out, "💥 {} didn't finish, see the summary below", lesson.id())?,
+                }
+            }
+            Format::Json => self.records.push(report::record(lesson, &report)),
+            Format::Ndjson => writeln!(out, "{}", report::record(lesson, &report))?,
+        }
+        self.reports.push(report);
+        Ok(())
+    }
+
+    /// Prints the summary of an isolated run or the JSON report, failing if
+    /// any lesson did
+    pub fn finish(self) -> Result<(), Failure> {
+        let mut out = io::stdout().lock();
+        match self.format {
+            Format::Text if self.timeout.is_none() => return Ok(()),
+            Format::Text => {
+                writeln!(out, "\n{}", "=".repeat(70))?;
+                isolate::write_summary(&self.reports, &mut out)?;
+            }
+            Format::Json => report::write_json(self.records, &self.reports, &mut out)?,
+            Format::Ndjson => {}
+        }
+        if self.reports.iter().any(|report| report.outcome != isolate::Outcome::Passed) {
+            return Err(Failure::silent());
+        }
+        Ok(())
+    }
+}
+
+/// Loads the learner's progress file, or starts an unsaved one if it can't
+/// be read
+pub fn open_progress() -> Progress {
+    let path = match Progress::default_path() {
+        Some(path) => path,
+        None => return Progress::default(),
+    };
+    match Progress::load(&path) {
+        Ok(progress) => progress,
+        Err(error) => {
+            eprintln!("warning: can't read {}: {} (progress won't be saved)", path.display(), error);
+            Progress::default()
+        }
+    }
+}
+
+fn record(progress: &mut Progress, lesson: &dyn Lesson, status: Status) {
+    if let Err(error) = progress.record(&lesson.id(), status) {
+        eprintln!("warning: can't save progress: {}", error);
+    }
+}
+
+/// `happyr` on its own: every section in order, exactly like the original
+/// `cargo run`, or every lesson passing the filter options
+pub fn run_all(options: &Options) -> Result<(), Failure> {
+    if !options.filter.is_empty() {
+        return run_selection(Ok(cli::select_all(registry::SECTIONS)), options);
+    }
+
+    let mut runner = Runner::new(options);
+    if !runner.is_text() {
+        for lesson in registry::lessons() {
+            runner.run(lesson)?;
+        }
+        return runner.finish();
+    }
+
+    let mut out = io::stdout();
+    writeln!(out, "🦀 Welcome to HappyR - Your Comprehensive Rust Learning Journey! 🦀\n")?;
+    writeln!(out, "{}", "=".repeat(70))?;
+
+    for section in registry::SECTIONS {
+        runner.banner(section)?;
+        for &lesson in section.lessons {
+            runner.run(lesson)?;
+        }
+    }
+
+    // ========================================================================
+    // CONCLUSION
+    // ========================================================================
+    writeln!(out, "{}", "\n".repeat(2))?;
+    writeln!(out, "{}", "=".repeat(70))?;
+    writeln!(out, "🎉 Congratulations! You've completed the Rust learning journey! 🎉")?;
+    writeln!(out, "{}", "=".repeat(70))?;
+    writeln!(out, "\n💡 Next Steps:")?;
+    writeln!(out, "   1. Modify the examples and experiment")?;
+    writeln!(out, "   2. Run 'cargo test' to see all unit tests")?;
+    writeln!(out, "   3. Build your own project using these concepts")?;
+    writeln!(out, "   4. Check out the Rust Book: https://doc.rust-lang.org/book/")?;
+    writeln!(out, "\nHappy Coding! 🦀\n")?;
+    runner.finish()
+}
+
+/// `happyr run <SELECTOR>...`
+pub fn run(selectors: &[Selector], options: &Options) -> Result<(), Failure> {
+    run_selection(cli::select(registry::SECTIONS, selectors), options)
+}
+
+/// Runs the selected lessons that pass the filter options
+fn run_selection(selected: Result<cli::Selection, String>, options: &Options) -> Result<(), Failure> {
+    let selected = selected
+        .and_then(|selected| options.filter.apply(selected))
+        .map_err(|message| Failure::new(format!("{}\n(use 'happyr list' to see every lesson)", message)))?;
+    let mut runner = Runner::new(options);
+    for (section, lessons) in selected {
+        runner.banner(section)?;
+        for lesson in lessons {
+            runner.run(lesson)?;
+        }
+    }
+    runner.finish()
+}
+
+/// `happyr __lesson <ID>`: runs a single lesson for an `--isolate` parent; a
+/// panic ends the process with the message on stderr, which the parent
+/// picks up
+pub fn run_child_lesson(id: &str) -> Result<(), Failure> {
+    let lesson = registry::find(id).ok_or_else(|| Failure {
+        code: 2,
+        ..Failure::new(format!("no lesson '{}'", id))
+    })?;
+    let mut out = io::stdout().lock();
+    lesson
+        .run(&mut out)
+        .and_then(|_| out.flush())
+        .map_err(|error| Failure::io(format!("{} failed", id), error))
+}
+
+/// `happyr next`: runs the first lesson along the recommended path that
+/// isn't done yet, out of those passing the filter options
+pub fn next(options: &Options) -> Result<(), Failure> {
+    let mut runner = Runner::new(options);
+    let filter = &options.filter;
+    let next = if filter.is_empty() {
+        runner.progress.next(registry::SECTIONS)
+    } else {
+        let selected = filter.apply(cli::select_all(registry::SECTIONS)).unwrap_or_default();
+        selected.into_iter().find_map(|(section, lessons)| {
+            lessons
+                .into_iter()
+                .find(|lesson| !runner.progress.is_done(&lesson.id()))
+                .map(|lesson| (section, lesson))
+        })
+    };
+    match next {
+        Some((section, lesson)) => {
+            runner.banner(section)?;
+            runner.run(lesson)?;
+            runner.finish()
+        }
+        None if filter.is_empty() => {
+            writeln!(io::stdout(), "🎉 You've finished every lesson! Try 'happyr interactive' to revisit one.")?;
+            Ok(())
+        }
+        None => {
+            writeln!(io::stdout(), "🎉 You've finished every lesson matching '{}'!", filter)?;
+            Ok(())
+        }
+    }
+}
+
+/// `happyr list`: every lesson id with its difficulty, title and tags,
+/// grouped by section, then the sections left out of this build
+pub fn list(filter: &Filter) -> Result<(), Failure> {
+    let lessons: Vec<&dyn Lesson> = registry::lessons().filter(|lesson| filter.matches(*lesson)).collect();
+    if lessons.is_empty() {
+        return Err(Failure::new(format!("no lesson matches '{}'", filter)));
+    }
+
+    let mut out = io::stdout().lock();
+    let mut current_section = "";
+    for lesson in lessons {
+        if lesson.section() != current_section {
+            current_section = lesson.section();
+            writeln!(out, "{}", current_section)?;
+        }
+        let line = format!(
+            "  {:<45} {:<13} {:<30} {}",
+            lesson.id(),
+            lesson.difficulty(),
+            lesson.title(),
+            lesson.tags().join(", ")
+        );
+        writeln!(out, "{}", line.trim_end())?;
+    }
+
+    let missing = registry::missing_features();
+    if !missing.is_empty() {
+        writeln!(
+            out,
+            "\nNot in this build: {} (rebuild with --features {} to add them)",
+            missing.join(", "),
+            missing.join(",")
+        )?;
+    }
+    Ok(())
+}
+
+/// `happyr help`
+pub fn help() -> Result<(), Failure> {
+    writeln!(io::stdout(), "{}", cli::USAGE)?;
+    Ok(())
+}
+
+/// `happyr playlists`
+pub fn playlists() -> Result<(), Failure> {
+    Ok(playlist::write_list(registry::SECTIONS, &mut io::stdout())?)
+}
+
+/// `happyr progress`: the per-section completion table and what to do next
+pub fn progress() -> Result<(), Failure> {
+    let progress = open_progress();
+    let mut out = io::stdout().lock();
+    progress.write_table(registry::SECTIONS, &mut out)?;
+    match progress.next(registry::SECTIONS) {
+        Some((_, lesson)) => {
+            writeln!(out, "\nNext up: {} ({}), run 'happyr next'", lesson.title(), lesson.id())?
+        }
+        None => writeln!(out, "\nEvery lesson is done. 🎉")?,
+    }
+    Ok(())
+}
+
+/// `happyr interactive`
+pub fn interactive() -> Result<(), Failure> {
+    Ok(interactive::run(
+        registry::SECTIONS,
+        &mut io::stdin().lock(),
+        &mut io::stdout(),
+        &mut open_progress(),
+    )?)
+}
+
+/// `happyr quiz <SECTION>`: asks the questions in a section's quiz bank
+pub fn quiz(id: &str) -> Result<(), Failure> {
+    let section = registry::section(id).ok_or_else(|| {
+        Failure::new(format!("no section '{}' (use 'happyr list' to see every section)", id))
+    })?;
+    let questions = match quiz::bank(section.id).map(quiz::parse) {
+        Some(Ok(questions)) => questions,
+        Some(Err(message)) => return Err(Failure::new(format!("quizzes/{}.quiz: {}", section.id, message))),
+        None => return Err(Failure::new(format!("there's no quiz for '{}' yet", section.id))),
+    };
+    quiz::run(section, &questions, &mut io::stdin().lock(), &mut io::stdout())?;
+    Ok(())
+}
+
+/// `happyr exercise list`: every exercise with the lesson behind it
+pub fn exercise_list() -> Result<(), Failure> {
+    let mut out = io::stdout().lock();
+    for exercise in exercise::EXERCISES {
+        writeln!(out, "  {:<30} lesson: {}", exercise.id, exercise.lesson)?;
+    }
+    writeln!(out, "\nEdit exercises/<ID>.rs, then run 'happyr exercise check <ID>'")?;
+    Ok(())
+}
+
+/// `happyr exercise check <ID>`: compiles the learner's copy of an exercise
+/// against its hidden tests
+pub fn exercise_check(id: &str) -> Result<(), Failure> {
+    let exercise = exercise::find(id).ok_or_else(|| {
+        Failure::new(format!("no exercise '{}' (use 'happyr exercise list' to see them)", id))
+    })?;
+    let path = exercise.path();
+    let source = std::fs::read_to_string(&path)
+        .map_err(|error| Failure::new(format!("can't read {}: {}", path.display(), error)))?;
+
+    writeln!(io::stdout(), "Checking {}...\n", exercise.id)?;
+    let outcome = exercise::check(exercise, &source)?;
+    match exercise::report(exercise, &outcome, &mut io::stdout())? {
+        true => Ok(()),
+        false => Err(Failure::silent()),
+    }
+}
+
+/// `happyr search <QUERY>`: lists the lessons matching `query`, then offers
+/// to run one
+pub fn search(query: &str, options: &Options) -> Result<(), Failure> {
+    let lessons: Vec<&'static dyn Lesson> = registry::lessons().collect();
+    let cache = progress::home().map(|home| home.join("search-index"));
+    let index = search::load_or_build(&lessons, cache.as_deref());
+
+    let hits: Vec<_> = index
+        .search(query)
+        .into_iter()
+        .take(10)
+        .filter_map(|hit| registry::find(&hit.id).map(|lesson| (hit, lesson)))
+        .collect();
+    search::write_results(query, &hits, &mut io::stdout())?;
+    if hits.is_empty() || !io::stdin().is_terminal() {
+        return Ok(());
+    }
+
+    let mut out = io::stdout();
+    let choice = interactive::prompt(&mut io::stdin().lock(), &mut out, "\nRun one? Number, or Enter to skip");
+    let lesson = match choice {
+        Ok(Some(choice)) => match choice.parse::<usize>() {
+            Ok(number) if (1..=hits.len()).contains(&number) => hits[number - 1].1,
+            _ => return Ok(()),
+        },
+        _ => return Ok(()),
+    };
+    let mut runner = Runner::new(options);
+    if let Some(section) = registry::section(lesson.section()) {
+        runner.banner(section)?;
+    }
+    runner.run(lesson)?;
+    runner.finish()
+}
+
+/// The lesson `id`, or a failure suggesting `happyr list`
+fn find_lesson(id: &str) -> Result<&'static dyn Lesson, Failure> {
+    registry::find(id)
+        .ok_or_else(|| Failure::new(format!("no lesson '{}' (use 'happyr list' to see every lesson)", id)))
+}
+
+/// `happyr show <LESSON>`: a lesson's source with its output interleaved
+pub fn show(id: &str) -> Result<(), Failure> {
+    Ok(show::show(find_lesson(id)?, &mut io::stdout())?)
+}
+
+/// `happyr watch <LESSON>`: re-runs a lesson every time the sources change,
+/// until interrupted
+pub fn watch(id: &str, options: &Options) -> Result<(), Failure> {
+    find_lesson(id)?;
+    let timeout = options.isolate.unwrap_or(isolate::DEFAULT_TIMEOUT);
+    watch::watch(id, timeout, &mut io::stdout())
+        .map_err(|error| Failure::new(format!("can't watch {}: {}", id, error)))
+}
+
+/// `happyr export --html <DIR>` or `--markdown <DIR>`
+pub fn export(format: export::Format, dir: &Path) -> Result<(), Failure> {
+    let files = export::export(registry::SECTIONS, format, dir)
+        .map_err(|error| Failure::new(format!("can't export to {}: {}", dir.display(), error)))?;
+    writeln!(io::stdout(), "Wrote {} files to {}", files, dir.display())?;
+    Ok(())
+}
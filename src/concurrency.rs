@@ -7,11 +7,36 @@
 // - Shared state with Mutex and Arc
 // - Send and Sync traits
 // - Thread safety guarantees
+//
+// `thread`, `mpsc` and `Mutex` come from `crate::sched` rather than std. They
+// work just like std's, unless `--deterministic` is given: then the threads
+// take turns in an order picked by a seed, so each run prints the same thing.
+//
+// The submodules hold reusable building blocks the lessons demonstrate:
+// - pool: ThreadPool, a fixed set of workers sharing a job queue
+// - steal: Executor, a work-stealing scheduler for fork-join recursion
+// - par_iter: par_iter(), iterator chains spread across scoped threads
+// - channel: a bounded channel built from Mutex and Condvar, and select!
+// - deadlock: TrackedMutex, which reports lock order inversions at runtime
 // ============================================================================
 
-use std::thread;
+pub mod channel;
+pub mod deadlock;
+pub mod par_iter;
+pub mod pool;
+pub mod steal;
+
+pub use deadlock::{LockOrderError, TrackedMutex};
+pub use par_iter::{ParIter, ParallelSlice};
+pub use pool::{JobHandle, ThreadPool};
+pub use steal::{Executor, Worker};
+
+use crate::bench::Bench;
+use crate::lesson::{lesson, Difficulty, Section, SharedOutput};
+use crate::sched::{mpsc, thread, Mutex};
+use std::io::{self, Write};
 use std::time::Duration;
-use std::sync::{mpsc, Mutex, Arc};
+use std::sync::Arc;
 
 /// Demonstrates basic thread creation
 /// 
@@ -19,26 +44,33 @@ use std::sync::{mpsc, Mutex, Arc};
 /// - Concurrent execution
 /// - thread::spawn creates new thread
 /// - join() waits for thread to finish
-pub fn thread_basics() {
-    println!("\n--- Thread Basics ---");
+pub fn thread_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Thread Basics ---")?;
+    
+    // A spawned thread can't borrow `out`, so both threads write to a
+    // shared log instead, which keeps their lines in the order they ran
+    let mut log = SharedOutput::new();
     
     // Spawn a new thread
-    let handle = thread::spawn(|| {
+    let mut thread_log = log.clone();
+    let handle = thread::spawn(move || {
         for i in 1..=5 {
-            println!("  Thread: count {}", i);
+            writeln!(thread_log, "  Thread: count {}", i).unwrap();
             thread::sleep(Duration::from_millis(1));
         }
     });
     
     // Main thread continues
     for i in 1..=3 {
-        println!("Main: count {}", i);
+        writeln!(log, "Main: count {}", i)?;
         thread::sleep(Duration::from_millis(1));
     }
     
     // Wait for spawned thread to finish
     handle.join().unwrap();
-    println!("Thread finished!");
+    writeln!(log, "Thread finished!")?;
+    
+    log.drain_into(out)
 }
 
 /// Demonstrates move closures with threads
@@ -46,19 +78,23 @@ pub fn thread_basics() {
 /// MOVE:
 /// - Threads need ownership of captured variables
 /// - Use move keyword to transfer ownership
-pub fn thread_move() {
-    println!("\n--- Thread Move ---");
+pub fn thread_move(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Thread Move ---")?;
     
     let v = vec![1, 2, 3];
+    let log = SharedOutput::new();
     
+    let mut thread_log = log.clone();
     let handle = thread::spawn(move || {
-        println!("  Thread has vector: {:?}", v);
+        writeln!(thread_log, "  Thread has vector: {:?}", v).unwrap();
     });
     
     // v is moved, can't use here
     // println!("{:?}", v); // ❌ Error!
     
     handle.join().unwrap();
+    
+    log.drain_into(out)
 }
 
 /// Demonstrates message passing with channels
@@ -68,8 +104,8 @@ pub fn thread_move() {
 /// - tx: transmitter (sender)
 /// - rx: receiver
 /// - Thread-safe communication
-pub fn channel_basics() {
-    println!("\n--- Channel Basics ---");
+pub fn channel_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Channel Basics ---")?;
     
     let (tx, rx) = mpsc::channel();
     
@@ -80,12 +116,14 @@ pub fn channel_basics() {
     });
     
     let received = rx.recv().unwrap();
-    println!("Main received: {}", received);
+    writeln!(out, "Main received: {}", received)?;
+    
+    Ok(())
 }
 
 /// Demonstrates sending multiple messages
-pub fn channel_multiple_messages() {
-    println!("\n--- Multiple Messages ---");
+pub fn channel_multiple_messages(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Multiple Messages ---")?;
     
     let (tx, rx) = mpsc::channel();
     
@@ -105,13 +143,15 @@ pub fn channel_multiple_messages() {
     
     // Receive messages as they arrive
     for received in rx {
-        println!("Main received: {}", received);
+        writeln!(out, "Main received: {}", received)?;
     }
+    
+    Ok(())
 }
 
 /// Demonstrates multiple producers
-pub fn channel_multiple_producers() {
-    println!("\n--- Multiple Producers ---");
+pub fn channel_multiple_producers(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Multiple Producers ---")?;
     
     let (tx, rx) = mpsc::channel();
     
@@ -141,8 +181,84 @@ pub fn channel_multiple_producers() {
     
     // Receive from both threads
     for received in rx.iter().take(4) {
-        println!("Received: {}", received);
+        writeln!(out, "Received: {}", received)?;
     }
+    
+    Ok(())
+}
+
+/// Demonstrates a hand-built bounded channel and select!
+/// 
+/// BOUNDED CHANNELS:
+/// - channel::bounded(n) (concurrency::channel) holds at most n values
+/// - send waits while the channel is full: backpressure on the producer
+/// - try_send and recv_timeout give up instead of waiting
+/// - Receivers can be cloned, so several consumers can share the work
+/// - select! waits on several receivers and takes from whichever is ready
+pub fn bounded_channels(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Bounded Channels ---")?;
+    
+    // Room for two: the producer gets two ahead, then waits for the consumer
+    let (tx, rx) = channel::bounded(2);
+    let log = SharedOutput::new();
+    let mut producer_log = log.clone();
+    let producer = thread::spawn(move || {
+        for i in 1..=4 {
+            tx.send(i).unwrap();
+            writeln!(producer_log, "  Sent {}", i).unwrap();
+        }
+    });
+    for value in rx.iter() {
+        writeln!(&log, "  Received {}", value).unwrap();
+        thread::sleep(Duration::from_millis(50));
+    }
+    producer.join().unwrap();
+    log.drain_into(out)?;
+    
+    // Giving up instead of waiting
+    let (tx, rx) = channel::bounded(1);
+    tx.try_send("first").unwrap();
+    writeln!(out, "try_send on a full channel: {:?}", tx.try_send("second"))?;
+    rx.recv().unwrap();
+    writeln!(out, "recv_timeout on an empty one: {:?}", rx.recv_timeout(Duration::from_millis(10)))?;
+    drop(tx);
+    writeln!(out, "recv once every sender is gone: {:?}", rx.recv())?;
+    
+    // select! takes from whichever receiver has something first
+    writeln!(out, "Selecting:")?;
+    let (numbers_tx, numbers) = channel::bounded(4);
+    let (words_tx, words) = channel::bounded(4);
+    let counter = thread::spawn(move || {
+        for n in 1..=3 {
+            thread::sleep(Duration::from_millis(30));
+            numbers_tx.send(n).unwrap();
+        }
+        numbers_tx
+    });
+    let speaker = thread::spawn(move || {
+        for word in ["ping", "pong"] {
+            thread::sleep(Duration::from_millis(45));
+            words_tx.send(word).unwrap();
+        }
+        words_tx
+    });
+    // The threads hand their senders back, so neither channel closes mid-loop
+    for _ in 0..5 {
+        crate::select! {
+            recv(numbers) -> n => writeln!(out, "  Number {:?}", n)?,
+            recv(words) -> w => writeln!(out, "  Word {:?}", w)?,
+        }
+    }
+    drop(counter.join().unwrap());
+    let _words_tx = speaker.join().unwrap();
+    
+    // A receiver whose senders are all gone is ready too, with an Err
+    crate::select! {
+        recv(words) -> w => writeln!(out, "  Word {:?}", w)?,
+        recv(numbers) -> n => writeln!(out, "  Numbers closed: {:?}", n)?,
+    }
+    
+    Ok(())
 }
 
 /// Demonstrates shared state with Mutex
@@ -152,18 +268,20 @@ pub fn channel_multiple_producers() {
 /// - Only one thread can access data at a time
 /// - lock() acquires the lock (blocks if held)
 /// - Automatically released when guard goes out of scope
-pub fn mutex_basics() {
-    println!("\n--- Mutex Basics ---");
+pub fn mutex_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Mutex Basics ---")?;
     
     let m = Mutex::new(5);
     
     {
         let mut num = m.lock().unwrap();
         *num = 6;
-        println!("Modified value: {}", *num);
+        writeln!(out, "Modified value: {}", *num)?;
     } // Lock released here
     
-    println!("Value after lock released: {:?}", m);
+    writeln!(out, "Value after lock released: {:?}", m)?;
+    
+    Ok(())
 }
 
 /// Demonstrates Arc with Mutex for shared state
@@ -172,18 +290,20 @@ pub fn mutex_basics() {
 /// - Atomic Reference Counted (thread-safe Rc)
 /// - Allows multiple ownership across threads
 /// - Combined with Mutex for shared mutable state
-pub fn arc_mutex() {
-    println!("\n--- Arc + Mutex ---");
+pub fn arc_mutex(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Arc + Mutex ---")?;
     
     let counter = Arc::new(Mutex::new(0));
+    let log = SharedOutput::new();
     let mut handles = vec![];
     
     for i in 0..10 {
         let counter = Arc::clone(&counter);
+        let mut log = log.clone();
         let handle = thread::spawn(move || {
             let mut num = counter.lock().unwrap();
             *num += 1;
-            println!("  Thread {} incremented counter", i);
+            writeln!(log, "  Thread {} incremented counter", i).unwrap();
         });
         handles.push(handle);
     }
@@ -192,8 +312,11 @@ pub fn arc_mutex() {
     for handle in handles {
         handle.join().unwrap();
     }
+    log.drain_into(out)?;
+    
+    writeln!(out, "Final counter value: {}", *counter.lock().unwrap())?;
     
-    println!("Final counter value: {}", *counter.lock().unwrap());
+    Ok(())
 }
 
 /// Demonstrates Send and Sync traits
@@ -205,41 +328,55 @@ pub fn arc_mutex() {
 /// SYNC:
 /// - Type can be referenced from multiple threads
 /// - &T is Send if T is Sync
-pub fn send_sync_traits() {
-    println!("\n--- Send and Sync ---");
+pub fn send_sync_traits(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Send and Sync ---")?;
     
-    println!("Send trait:");
-    println!("  - Allows transferring ownership between threads");
-    println!("  - Most types are Send (except Rc, raw pointers)");
+    writeln!(out, "Send trait:")?;
+    writeln!(out, "  - Allows transferring ownership between threads")?;
+    writeln!(out, "  - Most types are Send (except Rc, raw pointers)")?;
     
-    println!("\nSync trait:");
-    println!("  - Allows multiple threads to access via &T");
-    println!("  - Types like Mutex, Arc are Sync");
-    println!("  - RefCell is NOT Sync (not thread-safe)");
+    writeln!(out, "\nSync trait:")?;
+    writeln!(out, "  - Allows multiple threads to access via &T")?;
+    writeln!(out, "  - Types like Mutex, Arc are Sync")?;
+    writeln!(out, "  - RefCell is NOT Sync (not thread-safe)")?;
     
     // Example: i32 is both Send and Sync
     let num = 42;
+    let log = SharedOutput::new();
+    let mut thread_log = log.clone();
     let handle = thread::spawn(move || {
-        println!("  Thread has num: {}", num);
+        writeln!(thread_log, "  Thread has num: {}", num).unwrap();
     });
     handle.join().unwrap();
+    
+    log.drain_into(out)
 }
 
-/// Demonstrates thread pools (conceptual)
-pub fn thread_pool_concept() {
-    println!("\n--- Thread Pool Concept ---");
-    
-    println!("Thread pools reuse threads for multiple tasks");
-    println!("Benefits:");
-    println!("  - Reduced overhead (no thread creation per task)");
-    println!("  - Limited concurrency (control resource usage)");
-    println!("  - Better performance for many small tasks");
-    
-    // Simple example with fixed number of threads
-    let handles: Vec<_> = (0..4)
+/// Demonstrates thread pools
+/// 
+/// THREAD POOLS:
+/// - ThreadPool (concurrency::pool) keeps a fixed set of worker threads
+/// - execute queues a job; spawn also returns a handle to join its result
+/// - A panicking job is caught, so its worker lives on, and joining its
+///   handle returns the panic as an Err
+/// - Dropping the pool waits for queued jobs and joins the workers
+pub fn thread_pool_concept(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Thread Pool Concept ---")?;
+    
+    writeln!(out, "Thread pools reuse threads for multiple tasks")?;
+    writeln!(out, "Benefits:")?;
+    writeln!(out, "  - Reduced overhead (no thread creation per task)")?;
+    writeln!(out, "  - Limited concurrency (control resource usage)")?;
+    writeln!(out, "  - Better performance for many small tasks")?;
+    
+    // Four workers share the jobs; spawn hands back a handle for each result
+    let pool = ThreadPool::new(4);
+    let log = SharedOutput::new();
+    let handles: Vec<_> = (0..6)
         .map(|i| {
-            thread::spawn(move || {
-                println!("  Worker thread {} processing", i);
+            let mut log = log.clone();
+            pool.spawn(move || {
+                writeln!(log, "  Job {} processing", i).unwrap();
                 thread::sleep(Duration::from_millis(100));
                 i * 2
             })
@@ -250,24 +387,31 @@ pub fn thread_pool_concept() {
         .into_iter()
         .map(|h| h.join().unwrap())
         .collect();
+    log.drain_into(out)?;
+    writeln!(out, "Results: {:?} from {} workers", results, pool.workers())?;
+    
+    // Dropping the pool finishes queued jobs, then joins every worker
+    drop(pool);
+    writeln!(out, "Pool shut down")?;
     
-    println!("Results: {:?}", results);
+    Ok(())
 }
 
 /// Demonstrates deadlock prevention
-pub fn deadlock_prevention() {
-    println!("\n--- Deadlock Prevention ---");
+pub fn deadlock_prevention(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Deadlock Prevention ---")?;
     
-    println!("Deadlock occurs when:");
-    println!("  - Thread A holds lock 1, waits for lock 2");
-    println!("  - Thread B holds lock 2, waits for lock 1");
+    writeln!(out, "Deadlock occurs when:")?;
+    writeln!(out, "  - Thread A holds lock 1, waits for lock 2")?;
+    writeln!(out, "  - Thread B holds lock 2, waits for lock 1")?;
     
-    println!("\nPrevention strategies:");
-    println!("  1. Always acquire locks in same order");
-    println!("  2. Use try_lock() instead of lock()");
-    println!("  3. Use timeout with lock acquisition");
-    println!("  4. Minimize lock scope");
-    println!("  5. Avoid nested locks when possible");
+    writeln!(out, "\nPrevention strategies:")?;
+    writeln!(out, "  1. Always acquire locks in same order")?;
+    writeln!(out, "  2. Use try_lock() instead of lock()")?;
+    writeln!(out, "  3. Use timeout with lock acquisition")?;
+    writeln!(out, "  4. Minimize lock scope")?;
+    writeln!(out, "  5. Avoid nested locks when possible")?;
+    writeln!(out, "  6. Check the order at runtime (see Deadlock Detection)")?;
     
     // Example: proper lock ordering
     let lock1 = Arc::new(Mutex::new(1));
@@ -276,27 +420,88 @@ pub fn deadlock_prevention() {
     let lock1_clone = Arc::clone(&lock1);
     let lock2_clone = Arc::clone(&lock2);
     
+    let log = SharedOutput::new();
+    let mut log1 = log.clone();
+    let mut log2 = log.clone();
+    
     let handle1 = thread::spawn(move || {
         let _l1 = lock1_clone.lock().unwrap();
         thread::sleep(Duration::from_millis(10));
         let _l2 = lock2_clone.lock().unwrap();
-        println!("  Thread 1 acquired both locks");
+        writeln!(log1, "  Thread 1 acquired both locks").unwrap();
     });
     
     let handle2 = thread::spawn(move || {
         let _l1 = lock1.lock().unwrap(); // Same order!
         thread::sleep(Duration::from_millis(10));
         let _l2 = lock2.lock().unwrap();
-        println!("  Thread 2 acquired both locks");
+        writeln!(log2, "  Thread 2 acquired both locks").unwrap();
     });
     
     handle1.join().unwrap();
     handle2.join().unwrap();
+    
+    log.drain_into(out)
+}
+
+/// Demonstrates catching a lock order inversion at runtime
+/// 
+/// DEADLOCK DETECTION:
+/// - TrackedMutex (concurrency::deadlock) records which locks each thread
+///   holds while it takes another
+/// - Taking two locks in the opposite order to an earlier thread returns a
+///   LockOrderError instead of risking a hang
+/// - The report names the threads and the lines that took each lock
+pub fn deadlock_detection(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Deadlock Detection ---")?;
+    
+    let checking = Arc::new(TrackedMutex::new("checking", 100));
+    let savings = Arc::new(TrackedMutex::new("savings", 50));
+    let log = SharedOutput::new();
+    
+    // Two transfers take the same locks in opposite orders: with plain
+    // mutexes, each could end up holding one and waiting for the other
+    let transfers = [("transfer 1", &checking, &savings), ("transfer 2", &savings, &checking)];
+    let handles: Vec<_> = transfers
+        .into_iter()
+        .map(|(name, from, to)| {
+            let (from, to) = (Arc::clone(from), Arc::clone(to));
+            let mut log = log.clone();
+            thread::Builder::new()
+                .name(name.to_string())
+                .spawn(move || {
+                    let mut source = from.lock().unwrap();
+                    thread::sleep(Duration::from_millis(10));
+                    match to.lock() {
+                        Ok(mut target) => {
+                            *source -= 10;
+                            *target += 10;
+                            writeln!(log, "  {} moved 10 from {} to {}", name, from.name(), to.name()).unwrap();
+                        }
+                        // Returning drops `source`, so the other transfer can go on
+                        Err(error) => writeln!(log, "  {}", error).unwrap(),
+                    }
+                })
+                .unwrap()
+        })
+        .collect();
+    
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    log.drain_into(out)?;
+    
+    // One lock at a time, so these can't be out of order
+    let checking_balance = *checking.lock().unwrap();
+    let savings_balance = *savings.lock().unwrap();
+    writeln!(out, "Balances: checking {}, savings {}", checking_balance, savings_balance)?;
+    
+    Ok(())
 }
 
 /// Demonstrates parallel computation
-pub fn parallel_computation() {
-    println!("\n--- Parallel Computation ---");
+pub fn parallel_computation(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Parallel Computation ---")?;
     
     // Compute sum of squares in parallel
     let data: Vec<i32> = (1..=100).collect();
@@ -317,52 +522,244 @@ pub fn parallel_computation() {
         .map(|h| h.join().unwrap())
         .sum();
     
-    println!("Sum of squares (1-100): {}", total);
+    writeln!(out, "Sum of squares (1-100): {}", total)?;
+    
+    Ok(())
+}
+
+/// Below this many items, splitting further costs more than it saves
+const SEQUENTIAL_CUTOFF: usize = 16;
+
+/// Adds up `f(n)` for every number, splitting the slice in half until the
+/// pieces are small enough to sum directly
+fn parallel_sum_by<'env>(worker: &Worker<'_, 'env>, numbers: &'env [u64], f: fn(u64) -> u64) -> u64 {
+    if numbers.len() <= SEQUENTIAL_CUTOFF {
+        return numbers.iter().map(|&n| f(n)).sum();
+    }
+    let (left, right) = numbers.split_at(numbers.len() / 2);
+    let (a, b) = worker.join(
+        |w| parallel_sum_by(w, left, f),
+        move |w| parallel_sum_by(w, right, f),
+    );
+    a + b
+}
+
+/// Partitions around a pivot, then sorts both sides in parallel
+fn parallel_quicksort<'env, T: Ord + Send>(worker: &Worker<'_, 'env>, items: &'env mut [T]) {
+    if items.len() <= SEQUENTIAL_CUTOFF {
+        items.sort();
+        return;
+    }
+    let pivot = partition(items);
+    let (left, right) = items.split_at_mut(pivot);
+    worker.join(
+        |w| parallel_quicksort(w, left),
+        move |w| parallel_quicksort(w, &mut right[1..]),
+    );
+}
+
+/// Moves the middle item into its sorted position, with everything smaller
+/// before it and everything else after, and returns that position
+fn partition<T: Ord>(items: &mut [T]) -> usize {
+    let last = items.len() - 1;
+    items.swap(items.len() / 2, last);
+    let mut store = 0;
+    for i in 0..last {
+        if items[i] < items[last] {
+            items.swap(i, store);
+            store += 1;
+        }
+    }
+    items.swap(store, last);
+    store
+}
+
+/// A binary tree of numbers
+struct Tree {
+    value: u64,
+    left: Option<Box<Tree>>,
+    right: Option<Box<Tree>>,
+}
+
+impl Tree {
+    /// A balanced tree holding `values`
+    fn balanced(values: &[u64]) -> Option<Box<Tree>> {
+        if values.is_empty() {
+            return None;
+        }
+        let middle = values.len() / 2;
+        Some(Box::new(Tree {
+            value: values[middle],
+            left: Tree::balanced(&values[..middle]),
+            right: Tree::balanced(&values[middle + 1..]),
+        }))
+    }
+
+    /// Visits both subtrees of every node in parallel, returning how many
+    /// nodes there are and what their values add up to
+    fn parallel_count_and_sum<'env>(worker: &Worker<'_, 'env>, tree: &'env Option<Box<Tree>>) -> (usize, u64) {
+        let Some(node) = tree else {
+            return (0, 0);
+        };
+        let ((left_count, left_sum), (right_count, right_sum)) = worker.join(
+            |w| Tree::parallel_count_and_sum(w, &node.left),
+            move |w| Tree::parallel_count_and_sum(w, &node.right),
+        );
+        (left_count + right_count + 1, left_sum + right_sum + node.value)
+    }
+}
+
+/// Work that takes `n` steps, so later items cost more than earlier ones
+fn uneven_work(n: u64) -> u64 {
+    (0..n).fold(n, |acc, k| acc.wrapping_mul(31).wrapping_add(k)) % 1_000
+}
+
+/// Demonstrates work stealing with fork-join recursion
+///
+/// WORK STEALING:
+/// - Executor (concurrency::steal) gives each worker its own deque of jobs
+/// - worker.join(a, b) offers b to other workers, then runs a
+/// - Idle workers steal the oldest job from a peer's deque
+/// - Recursive divide-and-conquer splits work where it can, and the
+///   executor balances the pieces, however uneven they are
+pub fn work_stealing(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Work Stealing ---")?;
+
+    let executor = Executor::new(4);
+
+    let numbers: Vec<u64> = (1..=100).collect();
+    let total = executor.run(|worker| parallel_sum_by(worker, &numbers, |n| n * n));
+    writeln!(out, "Sum of squares (1-100): {}", total)?;
+
+    let mut items: Vec<u64> = (0..40).map(|i| (i * 37 + 11) % 100).collect();
+    executor.run(|worker| parallel_quicksort(worker, &mut items));
+    writeln!(out, "Quicksorted: {:?}", items)?;
+
+    let values: Vec<u64> = (1..=1_000).collect();
+    let tree = Tree::balanced(&values);
+    let (count, sum) = executor.run(|worker| Tree::parallel_count_and_sum(worker, &tree));
+    writeln!(out, "Tree of {} nodes adds up to {}", count, sum)?;
+
+    // Item n costs n steps, so the last chunks hold most of the work
+    let numbers: Vec<u64> = (1..=1_000).collect();
+    writeln!(out, "\nSumming uneven work over {} items:", numbers.len())?;
+    let mut bench = Bench::new();
+    bench.run("sequential", || numbers.iter().map(|&n| uneven_work(n)).sum::<u64>());
+    // OS threads, like the executor's, even under --deterministic
+    bench.run("thread per 25-item chunk", || {
+        let handles: Vec<_> = numbers
+            .chunks(25)
+            .map(|chunk| {
+                let chunk = chunk.to_vec();
+                std::thread::spawn(move || chunk.iter().map(|&n| uneven_work(n)).sum::<u64>())
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).sum::<u64>()
+    });
+    bench.run("work stealing, 4 workers", || {
+        executor.run(|worker| parallel_sum_by(worker, &numbers, uneven_work))
+    });
+    bench.write_table(out)
+}
+
+/// Demonstrates parallel iterators
+///
+/// PARALLEL ITERATORS:
+/// - ParallelSlice (concurrency::par_iter) adds par_iter() to slices and Vec
+/// - map, filter, reduce, sum, for_each and collect work like their
+///   sequential versions
+/// - Each thread takes one chunk of the slice, and results come back in
+///   the slice's order
+/// - for_each runs in whatever order the threads reach the items
+pub fn parallel_iterators(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Parallel Iterators ---")?;
+    
+    let numbers: Vec<u64> = (1..=20).collect();
+    
+    // The same chain as with iter(), spread over four threads
+    let even_squares: Vec<u64> = numbers
+        .par_iter()
+        .with_workers(4)
+        .filter(|&&n| n % 2 == 0)
+        .map(|n| n * n)
+        .collect();
+    writeln!(out, "Even squares: {:?}", even_squares)?;
+    
+    let sequential: Vec<u64> = numbers.iter().filter(|&&n| n % 2 == 0).map(|n| n * n).collect();
+    writeln!(out, "Same as the sequential chain: {}", even_squares == sequential)?;
+    
+    let total: u64 = numbers.par_iter().with_workers(4).sum();
+    writeln!(out, "Sum (1-20): {}", total)?;
+    
+    // reduce combines the chunks in order, so the operation needn't commute
+    let words = ["fearless", " ", "concurrency", " ", "with", " ", "iterators"];
+    let sentence = words
+        .par_iter()
+        .with_workers(3)
+        .map(|word| word.to_string())
+        .reduce(|a, b| a + &b);
+    writeln!(out, "Reduced: {:?}", sentence)?;
+    
+    // for_each has no order to keep: each thread reports its items as it goes
+    let log = SharedOutput::new();
+    numbers.par_iter().with_workers(4).filter(|&&n| n % 5 == 0).for_each(|n| {
+        writeln!(&log, "  Visited {}", n).unwrap();
+    });
+    log.drain_into(out)?;
+    
+    Ok(())
 }
 
 /// Demonstrates scoped threads (conceptual for older Rust)
-pub fn scoped_threads_concept() {
-    println!("\n--- Scoped Threads ---");
+pub fn scoped_threads_concept(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Scoped Threads ---")?;
     
-    println!("Scoped threads (std::thread::scope in Rust 1.63+):");
-    println!("  - Can borrow local variables");
-    println!("  - Guaranteed to finish before scope ends");
-    println!("  - No need for Arc or move in many cases");
+    writeln!(out, "Scoped threads (std::thread::scope in Rust 1.63+):")?;
+    writeln!(out, "  - Can borrow local variables")?;
+    writeln!(out, "  - Guaranteed to finish before scope ends")?;
+    writeln!(out, "  - No need for Arc or move in many cases")?;
     
     let mut data = vec![1, 2, 3, 4, 5];
+    // Scoped threads can borrow the log too, no clones needed
+    let log = SharedOutput::new();
     
     thread::scope(|s| {
         s.spawn(|| {
-            println!("  Thread can read data: {:?}", data);
+            writeln!(&log, "  Thread can read data: {:?}", data).unwrap();
         });
         
         s.spawn(|| {
-            println!("  Another thread reading: len = {}", data.len());
+            writeln!(&log, "  Another thread reading: len = {}", data.len()).unwrap();
         });
     }); // All threads guaranteed to finish here
+    log.drain_into(out)?;
     
     data.push(6);
-    println!("Data after threads: {:?}", data);
+    writeln!(out, "Data after threads: {:?}", data)?;
+    
+    Ok(())
 }
 
 /// Demonstrates practical concurrency patterns
-pub fn practical_patterns() {
-    println!("\n--- Practical Patterns ---");
+pub fn practical_patterns(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Practical Patterns ---")?;
     
     // Pattern 1: Worker pool
-    println!("\n1. Worker Pool Pattern:");
+    writeln!(out, "\n1. Worker Pool Pattern:")?;
     let (tx, rx) = mpsc::channel();
     let rx = Arc::new(Mutex::new(rx));
     
+    let log = SharedOutput::new();
     let mut workers = vec![];
     for id in 0..3 {
         let rx = Arc::clone(&rx);
+        let mut log = log.clone();
         let worker = thread::spawn(move || {
             loop {
                 let job = rx.lock().unwrap().recv();
                 match job {
                     Ok(num) => {
-                        println!("  Worker {} processing: {}", id, num);
+                        writeln!(log, "  Worker {} processing: {}", id, num).unwrap();
                         thread::sleep(Duration::from_millis(50));
                     }
                     Err(_) => break,
@@ -382,9 +779,10 @@ pub fn practical_patterns() {
     for worker in workers {
         worker.join().unwrap();
     }
+    log.drain_into(out)?;
     
     // Pattern 2: Fan-out, Fan-in
-    println!("\n2. Fan-out, Fan-in Pattern:");
+    writeln!(out, "\n2. Fan-out, Fan-in Pattern:")?;
     let (tx, rx) = mpsc::channel();
     
     // Fan-out: multiple workers
@@ -399,47 +797,98 @@ pub fn practical_patterns() {
     
     // Fan-in: collect results
     let results: Vec<_> = rx.iter().collect();
-    println!("  Results: {:?}", results);
+    writeln!(out, "  Results: {:?}", results)?;
+    
+    Ok(())
 }
 
 /// Demonstrates thread safety guarantees
-pub fn thread_safety() {
-    println!("\n--- Thread Safety ---");
-    
-    println!("Rust's thread safety guarantees:");
-    println!("  1. Data races impossible at compile time");
-    println!("  2. Send trait prevents unsafe transfers");
-    println!("  3. Sync trait prevents unsafe sharing");
-    println!("  4. Mutex ensures exclusive access");
-    println!("  5. Arc provides thread-safe reference counting");
-    
-    println!("\nCommon thread-safe types:");
-    println!("  - Arc<T> (atomic reference counting)");
-    println!("  - Mutex<T> (mutual exclusion)");
-    println!("  - RwLock<T> (reader-writer lock)");
-    println!("  - Atomic types (AtomicBool, AtomicI32, etc.)");
+pub fn thread_safety(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Thread Safety ---")?;
+    
+    writeln!(out, "Rust's thread safety guarantees:")?;
+    writeln!(out, "  1. Data races impossible at compile time")?;
+    writeln!(out, "  2. Send trait prevents unsafe transfers")?;
+    writeln!(out, "  3. Sync trait prevents unsafe sharing")?;
+    writeln!(out, "  4. Mutex ensures exclusive access")?;
+    writeln!(out, "  5. Arc provides thread-safe reference counting")?;
+    
+    writeln!(out, "\nCommon thread-safe types:")?;
+    writeln!(out, "  - Arc<T> (atomic reference counting)")?;
+    writeln!(out, "  - Mutex<T> (mutual exclusion)")?;
+    writeln!(out, "  - RwLock<T> (reader-writer lock)")?;
+    writeln!(out, "  - Atomic types (AtomicBool, AtomicI32, etc.)")?;
+    
+    Ok(())
 }
 
 // ============================================================================
 // PUBLIC INTERFACE
 // ============================================================================
 
-pub fn run_all_examples() {
-    thread_basics();
-    thread_move();
-    channel_basics();
-    channel_multiple_messages();
-    channel_multiple_producers();
-    mutex_basics();
-    arc_mutex();
-    send_sync_traits();
-    thread_pool_concept();
-    deadlock_prevention();
-    parallel_computation();
-    scoped_threads_concept();
-    practical_patterns();
-    thread_safety();
-}
+/// This module's lessons, in teaching order
+pub static SECTION: Section = Section {
+    id: "concurrency",
+    number: 9,
+    title: "CONCURRENCY",
+    icon: "⚡",
+    lessons: &[
+        &lesson!(thread_basics, "Thread Basics")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["threads"]),
+        &lesson!(thread_move, "Thread Move")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["threads", "closures", "ownership"]),
+        &lesson!(channel_basics, "Channel Basics")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["channels"]),
+        &lesson!(channel_multiple_messages, "Multiple Messages")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["channels", "threads"]),
+        &lesson!(channel_multiple_producers, "Multiple Producers")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["channels", "threads"]),
+        &lesson!(bounded_channels, "Bounded Channels")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["channels", "sync"]),
+        &lesson!(mutex_basics, "Mutex Basics")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["sync", "mutex"]),
+        &lesson!(arc_mutex, "Arc + Mutex")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["sync", "mutex", "threads"]),
+        &lesson!(send_sync_traits, "Send and Sync")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["sync", "traits"]),
+        &lesson!(thread_pool_concept, "Thread Pool Concept")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["threads", "channels"]),
+        &lesson!(deadlock_prevention, "Deadlock Prevention")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["sync", "mutex", "deadlocks"]),
+        &lesson!(deadlock_detection, "Deadlock Detection")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["sync", "mutex", "deadlocks"]),
+        &lesson!(parallel_computation, "Parallel Computation")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["threads", "performance"]),
+        &lesson!(work_stealing, "Work Stealing")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["threads", "performance"]),
+        &lesson!(parallel_iterators, "Parallel Iterators")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["iterators", "threads"]),
+        &lesson!(scoped_threads_concept, "Scoped Threads")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["threads", "lifetimes"]),
+        &lesson!(practical_patterns, "Practical Patterns")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["threads", "channels", "sync"]),
+        &lesson!(thread_safety, "Thread Safety")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["sync", "threads"]),
+    ],
+};
 
 // ============================================================================
 // UNIT TESTS
@@ -494,4 +943,22 @@ mod tests {
         
         assert_eq!(*counter.lock().unwrap(), 10);
     }
+    
+    #[test]
+    fn test_parallel_quicksort() {
+        let mut items: Vec<i32> = (0..500).map(|i| (i * 7919) % 503 - 250).collect();
+        let mut expected = items.clone();
+        expected.sort();
+        Executor::new(3).run(|worker| parallel_quicksort(worker, &mut items));
+        assert_eq!(items, expected);
+    }
+    
+    #[test]
+    fn test_parallel_tree_traversal() {
+        let values: Vec<u64> = (1..=100).collect();
+        let tree = Tree::balanced(&values);
+        let counted = Executor::new(2).run(|worker| Tree::parallel_count_and_sum(worker, &tree));
+        assert_eq!(counted, (100, 5050));
+        assert_eq!(Executor::new(2).run(|worker| Tree::parallel_count_and_sum(worker, &None)), (0, 0));
+    }
 }
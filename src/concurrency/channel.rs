@@ -0,0 +1,448 @@
+// ============================================================================
+// BOUNDED CHANNELS
+// ============================================================================
+// A channel built by hand from a `Mutex` and two `Condvar`s, to show what
+// `std::sync::mpsc` does underneath, plus a few things it doesn't:
+//
+//     let (tx, rx) = channel::bounded(2);
+//     tx.send(1)?;                    // waits while the channel is full
+//     tx.try_send(2)?;                // or fails straight away
+//     let rx2 = rx.clone();           // any number of receivers
+//     rx2.recv_timeout(Duration::from_millis(10))?;
+//
+// The queue and the count of senders and receivers live behind the mutex.
+// A sender waits on `not_full` while the queue is at capacity, which is the
+// backpressure that stops a fast producer running away from a slow
+// consumer; a receiver waits on `not_empty`. Each side notifies the other
+// when it changes the queue. Once the last sender is dropped, receivers
+// drain what's left and then get `RecvError`; once the last receiver is
+// dropped, sends fail and hand the value back.
+//
+// `select!` waits on several receivers at once:
+//
+//     select! {
+//         recv(jobs) -> job => println!("job: {:?}", job),
+//         recv(quit) -> msg => println!("quit: {:?}", msg),
+//     }
+//
+// It registers a `Waiter` with each receiver, whose senders wake it, and
+// takes a message from the first receiver in the list that has one.
+//
+// The locks come from `crate::sched`, so under `--deterministic` waiting
+// threads step aside, and timeouts use logical time.
+// ============================================================================
+
+use crate::sched::{Condvar, Mutex, MutexGuard};
+use std::collections::VecDeque;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+pub use std::sync::mpsc::{RecvError, RecvTimeoutError, SendError, TryRecvError, TrySendError};
+
+/// Creates a channel that holds at most `capacity` values
+///
+/// Panics if `capacity` is zero.
+pub fn bounded<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    assert!(capacity > 0, "a bounded channel needs room for at least one value");
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State {
+            queue: VecDeque::with_capacity(capacity),
+            capacity,
+            senders: 1,
+            receivers: 1,
+            waiters: Vec::new(),
+        }),
+        not_full: Condvar::new(),
+        not_empty: Condvar::new(),
+    });
+    (
+        Sender {
+            shared: Arc::clone(&shared),
+        },
+        Receiver { shared },
+    )
+}
+
+struct Shared<T> {
+    state: Mutex<State<T>>,
+    /// Signalled when a value is taken, or the last receiver goes
+    not_full: Condvar,
+    /// Signalled when a value arrives, or the last sender goes
+    not_empty: Condvar,
+}
+
+struct State<T> {
+    queue: VecDeque<T>,
+    capacity: usize,
+    senders: usize,
+    receivers: usize,
+    /// The `select!`s watching this channel; finished ones are cleared out
+    /// whenever the list is next used
+    waiters: Vec<Weak<Waiter>>,
+}
+
+impl<T> Shared<T> {
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        // Nothing panics while holding the lock
+        self.state.lock().unwrap()
+    }
+}
+
+impl<T> State<T> {
+    /// Tells every `select!` watching the channel that a receiver may have
+    /// something to take
+    fn wake_waiters(&mut self) {
+        self.waiters.retain(|waiter| match waiter.upgrade() {
+            Some(waiter) => {
+                waiter.notify();
+                true
+            }
+            None => false,
+        });
+    }
+}
+
+/// The sending half of a bounded channel
+pub struct Sender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+/// The receiving half of a bounded channel; clone it for more consumers
+pub struct Receiver<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Sender<T> {
+    /// Waits for room, then sends `value`, or hands it back if every
+    /// receiver is gone
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        let state = self.shared.lock();
+        let mut state = self
+            .shared
+            .not_full
+            .wait_while(state, |state| state.receivers > 0 && state.queue.len() == state.capacity)
+            .unwrap();
+        if state.receivers == 0 {
+            return Err(SendError(value));
+        }
+        self.push(&mut state, value);
+        Ok(())
+    }
+
+    /// Sends `value` if there's room right now
+    pub fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        let mut state = self.shared.lock();
+        if state.receivers == 0 {
+            Err(TrySendError::Disconnected(value))
+        } else if state.queue.len() == state.capacity {
+            Err(TrySendError::Full(value))
+        } else {
+            self.push(&mut state, value);
+            Ok(())
+        }
+    }
+
+    fn push(&self, state: &mut State<T>, value: T) {
+        state.queue.push_back(value);
+        state.wake_waiters();
+        self.shared.not_empty.notify_one();
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Waits for a value, or fails once the channel is empty and every
+    /// sender is gone
+    pub fn recv(&self) -> Result<T, RecvError> {
+        let state = self.shared.lock();
+        let state = self
+            .shared
+            .not_empty
+            .wait_while(state, |state| state.senders > 0 && state.queue.is_empty())
+            .unwrap();
+        self.pop(state).ok_or(RecvError)
+    }
+
+    /// Takes a value if there is one right now
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        let state = self.shared.lock();
+        let disconnected = state.senders == 0;
+        match self.pop(state) {
+            Some(value) => Ok(value),
+            None if disconnected => Err(TryRecvError::Disconnected),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+
+    /// Like `recv`, but gives up after `timeout`
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        let state = self.shared.lock();
+        let (state, _) = self
+            .shared
+            .not_empty
+            .wait_timeout_while(state, timeout, |state| state.senders > 0 && state.queue.is_empty())
+            .unwrap();
+        let disconnected = state.senders == 0;
+        match self.pop(state) {
+            Some(value) => Ok(value),
+            None if disconnected => Err(RecvTimeoutError::Disconnected),
+            None => Err(RecvTimeoutError::Timeout),
+        }
+    }
+
+    /// Receives until the channel is empty and every sender is gone
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        std::iter::from_fn(|| self.recv().ok())
+    }
+
+    /// Has `waiter` woken whenever a value arrives or the last sender goes
+    pub fn watch(&self, waiter: &Arc<Waiter>) {
+        let mut state = self.shared.lock();
+        state.waiters.retain(|waiter| waiter.strong_count() > 0);
+        state.waiters.push(Arc::downgrade(waiter));
+    }
+
+    /// What `select!` takes from this receiver: a value, `Err` if it's
+    /// disconnected, or `None` if it would have to wait
+    pub fn try_select(&self) -> Option<Result<T, RecvError>> {
+        match self.try_recv() {
+            Ok(value) => Some(Ok(value)),
+            Err(TryRecvError::Disconnected) => Some(Err(RecvError)),
+            Err(TryRecvError::Empty) => None,
+        }
+    }
+
+    fn pop(&self, mut state: MutexGuard<'_, State<T>>) -> Option<T> {
+        let value = state.queue.pop_front()?;
+        drop(state);
+        self.shared.not_full.notify_one();
+        Some(value)
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Sender<T> {
+        self.shared.lock().senders += 1;
+        Sender {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl<T> Clone for Receiver<T> {
+    fn clone(&self) -> Receiver<T> {
+        self.shared.lock().receivers += 1;
+        Receiver {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+/// The last sender going wakes every receiver, so they can see it's over
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.lock();
+        state.senders -= 1;
+        if state.senders == 0 {
+            state.wake_waiters();
+            self.shared.not_empty.notify_all();
+        }
+    }
+}
+
+/// The last receiver going wakes every sender, so their sends can fail
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.lock();
+        state.receivers -= 1;
+        if state.receivers == 0 {
+            self.shared.not_full.notify_all();
+        }
+    }
+}
+
+/// Wakes a `select!` when any of the receivers it watches may be ready
+pub struct Waiter {
+    /// Counts wake-ups, so `wait` can tell if one came since it looked
+    version: Mutex<u64>,
+    changed: Condvar,
+}
+
+impl Waiter {
+    pub fn new() -> Arc<Waiter> {
+        Arc::new(Waiter {
+            version: Mutex::new(0),
+            changed: Condvar::new(),
+        })
+    }
+
+    pub fn version(&self) -> u64 {
+        *self.version.lock().unwrap()
+    }
+
+    fn notify(&self) {
+        *self.version.lock().unwrap() += 1;
+        self.changed.notify_all();
+    }
+
+    /// Sleeps until woken, unless that already happened after `seen`
+    pub fn wait(&self, seen: u64) {
+        let version = self.version.lock().unwrap();
+        drop(self.changed.wait_while(version, |version| *version == seen).unwrap());
+    }
+}
+
+/// Waits until one of several bounded receivers has a message or is
+/// disconnected, then runs that arm with `Ok(message)` or `Err(RecvError)`:
+///
+/// ```ignore
+/// select! {
+///     recv(numbers) -> n => println!("number {:?}", n),
+///     recv(words) -> w => println!("word {:?}", w),
+/// }
+/// ```
+///
+/// When several are ready, the first in the list wins. Each receiver
+/// expression is evaluated more than once, so pass a variable.
+#[macro_export]
+macro_rules! select {
+    // Gives each arm a `slot` variable: made in a separate expansion, each
+    // one is a different variable even though they share a name
+    (@arms [$($arms:tt)*] recv($rx:expr) -> $msg:ident => $body:expr $(, $($rest:tt)*)?) => {
+        $crate::select!(@arms [$($arms)* ($rx, slot, $msg, $body)] $($($rest)*)?)
+    };
+    (@arms [$(($rx:expr, $slot:ident, $msg:ident, $body:expr))+]) => {{
+        let waiter = $crate::concurrency::channel::Waiter::new();
+        $( $rx.watch(&waiter); )+
+        $( let mut $slot = None; )+
+        loop {
+            let seen = waiter.version();
+            $(
+                if let Some(message) = $rx.try_select() {
+                    $slot = Some(message);
+                    break;
+                }
+            )+
+            waiter.wait(seen);
+        }
+        // The arms run outside the loop, so a `break` in one means the
+        // caller's loop
+        $( if let Some($msg) = $slot { $body } else )+ {
+            unreachable!("the loop only ends once a slot is filled")
+        }
+    }};
+    ($($arms:tt)+) => {
+        $crate::select!(@arms [] $($arms)+)
+    };
+}
+
+// ============================================================================
+// UNIT TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sched::{self, thread};
+
+    #[test]
+    fn test_backpressure_and_try_send() {
+        let (tx, rx) = bounded(2);
+        tx.send(1).unwrap();
+        tx.try_send(2).unwrap();
+        assert_eq!(tx.try_send(3), Err(TrySendError::Full(3)));
+        assert_eq!(rx.recv(), Ok(1));
+        tx.try_send(3).unwrap();
+        assert_eq!(rx.try_recv(), Ok(2));
+        assert_eq!(rx.try_recv(), Ok(3));
+        assert_eq!(rx.try_recv(), Err(TryRecvError::Empty));
+    }
+
+    #[test]
+    fn test_disconnects_on_both_sides() {
+        let (tx, rx) = bounded(4);
+        tx.send("last").unwrap();
+        drop(tx);
+        // What's queued is still delivered
+        assert_eq!(rx.recv(), Ok("last"));
+        assert_eq!(rx.recv(), Err(RecvError));
+        assert_eq!(rx.recv_timeout(Duration::from_secs(60)), Err(RecvTimeoutError::Disconnected));
+
+        let (tx, rx) = bounded(1);
+        drop(rx);
+        assert_eq!(tx.send(5), Err(SendError(5)));
+        assert_eq!(tx.try_send(6), Err(TrySendError::Disconnected(6)));
+    }
+
+    #[test]
+    fn test_a_full_channel_blocks_the_sender() {
+        // Seeded, so the receiver's sleep takes no real time
+        let log = sched::run_seeded(sched::DEFAULT_SEED, || {
+            let (tx, rx) = bounded(1);
+            let log = Arc::new(Mutex::new(Vec::new()));
+            let sender_log = Arc::clone(&log);
+            let sender = thread::spawn(move || {
+                for i in 1..=3 {
+                    tx.send(i).unwrap();
+                    sender_log.lock().unwrap().push(format!("sent {}", i));
+                }
+            });
+            thread::sleep(Duration::from_secs(1));
+            // Only the first value fit while nobody was receiving
+            log.lock().unwrap().push("woke".to_string());
+            let received: Vec<i32> = rx.iter().collect();
+            sender.join().unwrap();
+            assert_eq!(received, [1, 2, 3]);
+            let log = log.lock().unwrap().clone();
+            log
+        });
+        assert_eq!(log[..2], ["sent 1", "woke"]);
+    }
+
+    #[test]
+    fn test_recv_timeout_and_many_consumers() {
+        let (tx, rx) = bounded::<u32>(8);
+        assert_eq!(rx.recv_timeout(Duration::from_millis(5)), Err(RecvTimeoutError::Timeout));
+
+        let consumers: Vec<_> = (0..3)
+            .map(|_| {
+                let rx = rx.clone();
+                thread::spawn(move || rx.iter().sum::<u32>())
+            })
+            .collect();
+        drop(rx);
+        for n in 1..=100 {
+            tx.send(n).unwrap();
+        }
+        drop(tx);
+        let total: u32 = consumers.into_iter().map(|consumer| consumer.join().unwrap()).sum();
+        assert_eq!(total, 5050);
+    }
+
+    #[test]
+    fn test_select_takes_whichever_is_ready() {
+        let (numbers_tx, numbers) = bounded::<i32>(1);
+        let (words_tx, words) = bounded::<&str>(1);
+        words_tx.send("hello").unwrap();
+        let picked = crate::select! {
+            recv(numbers) -> n => format!("number {:?}", n),
+            recv(words) -> w => format!("word {:?}", w),
+        };
+        assert_eq!(picked, "word Ok(\"hello\")");
+
+        let sender = thread::spawn(move || numbers_tx.send(7).unwrap());
+        let picked = crate::select! {
+            recv(numbers) -> n => n.map(|n| n * 6),
+            recv(words) -> w => Err(w.unwrap_err()),
+        };
+        assert_eq!(picked, Ok(42));
+        sender.join().unwrap();
+
+        // Disconnected receivers are ready too
+        drop(words_tx);
+        let picked = crate::select! {
+            recv(numbers) -> n => n.is_err(),
+            recv(words) -> w => w.is_err(),
+        };
+        assert!(picked);
+    }
+}
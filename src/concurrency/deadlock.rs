@@ -0,0 +1,396 @@
+// ============================================================================
+// DEADLOCK DETECTION
+// ============================================================================
+// "Always acquire locks in the same order" is easy to say and hard to check
+// by reading code. `TrackedMutex` checks it while the program runs:
+//
+//     let checking = TrackedMutex::new("checking", 100);
+//     let savings = TrackedMutex::new("savings", 50);
+//
+//     let a = checking.lock()?;     // thread 1: checking, then savings
+//     let b = savings.lock()?;
+//     ...
+//     let b = savings.lock()?;      // thread 2: savings, then checking
+//     let a = checking.lock()?;     // Err(LockOrderError), not a hang
+//
+// Every thread keeps a list of the tracked locks it holds. Whenever one is
+// locked while others are held, the pair is added to a graph of lock
+// orders shared by the whole program: an edge from `checking` to `savings`
+// means some thread has held `checking` while locking `savings`. Before a
+// thread waits for a lock, the graph is searched for a path back from that
+// lock to one the thread already holds. Finding one means two orders
+// disagree, and with unlucky timing the threads involved would wait for
+// each other forever, so `lock` returns an error describing the cycle
+// instead, with the threads and the lines of code that took each lock.
+// Edges are added before waiting, so that two threads locking in opposite
+// orders at the same moment still see each other's; the report says which
+// of those locks were taken and which were still being waited for.
+//
+// Like a linter, this flags orders that *could* deadlock, even when this
+// particular run would have got away with it.
+// ============================================================================
+
+use crate::sched::{Mutex, MutexGuard};
+use std::cell::RefCell;
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::panic::Location;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::PoisonError;
+
+/// A mutex that checks it's always locked in a consistent order with the
+/// other tracked mutexes
+pub struct TrackedMutex<T> {
+    inner: Mutex<T>,
+    id: usize,
+    name: &'static str,
+}
+
+/// Unlocks a `TrackedMutex` when dropped
+pub struct TrackedGuard<'a, T> {
+    inner: MutexGuard<'a, T>,
+    id: usize,
+}
+
+/// Where a thread locked, or tried to lock, a tracked mutex
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Site {
+    pub lock: &'static str,
+    pub location: &'static Location<'static>,
+}
+
+/// One thread locking `then` while holding `first`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Acquisition {
+    pub thread: String,
+    pub first: Site,
+    pub then: Site,
+    /// Whether the thread got `then`, rather than waiting for it or being
+    /// refused it
+    pub locked: bool,
+}
+
+/// A lock that would break the lock order seen so far
+#[derive(Debug, Clone, PartialEq)]
+pub struct LockOrderError {
+    /// The lock that was refused
+    pub attempt: Acquisition,
+    /// Earlier acquisitions leading from the lock that was wanted back to
+    /// the one already held, in order; empty if the thread tried to lock a
+    /// mutex it already holds
+    pub cycle: Vec<Acquisition>,
+}
+
+/// A tracked lock the current thread holds
+struct Held {
+    id: usize,
+    site: Site,
+}
+
+thread_local! {
+    static HELD: RefCell<Vec<Held>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Every order seen so far, keyed by the ids of the lock held and the one
+/// locked after it; only the first acquisition of each pair is kept
+static ORDERS: std::sync::Mutex<BTreeMap<(usize, usize), Acquisition>> = std::sync::Mutex::new(BTreeMap::new());
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+fn orders() -> std::sync::MutexGuard<'static, BTreeMap<(usize, usize), Acquisition>> {
+    // Nothing panics while holding it
+    ORDERS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// The earlier acquisitions leading from lock `from` to lock `to`, if any
+fn path(orders: &BTreeMap<(usize, usize), Acquisition>, from: usize, to: usize) -> Option<Vec<Acquisition>> {
+    // Breadth first, remembering the edge each lock was reached by
+    let mut reached_by: BTreeMap<usize, (usize, usize)> = BTreeMap::new();
+    let mut queue = VecDeque::from([from]);
+    while let Some(lock) = queue.pop_front() {
+        if lock == to {
+            let mut edges = Vec::new();
+            let mut at = to;
+            while at != from {
+                let edge = reached_by[&at];
+                edges.push(orders[&edge].clone());
+                at = edge.0;
+            }
+            edges.reverse();
+            return Some(edges);
+        }
+        for &(first, then) in orders.keys().filter(|&&(first, _)| first == lock) {
+            if then != from && !reached_by.contains_key(&then) {
+                reached_by.insert(then, (first, then));
+                queue.push_back(then);
+            }
+        }
+    }
+    None
+}
+
+fn thread_name() -> String {
+    let thread = std::thread::current();
+    match thread.name() {
+        Some(name) => name.to_string(),
+        None => format!("{:?}", thread.id()),
+    }
+}
+
+impl<T> TrackedMutex<T> {
+    /// `name` identifies the mutex in error reports
+    pub fn new(name: &'static str, value: T) -> TrackedMutex<T> {
+        TrackedMutex {
+            inner: Mutex::new(value),
+            id: NEXT_ID.fetch_add(1, Ordering::SeqCst),
+            name,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Waits for the lock, unless taking it now could deadlock
+    ///
+    /// A panic while holding the lock doesn't poison it, since the errors
+    /// here are about lock order.
+    #[track_caller]
+    pub fn lock(&self) -> Result<TrackedGuard<'_, T>, LockOrderError> {
+        let site = Site {
+            lock: self.name,
+            location: Location::caller(),
+        };
+        let recorded = HELD.with(|held| self.check(&held.borrow(), site))?;
+        let inner = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut orders = orders();
+        for key in recorded {
+            // Gone if the other mutex has been dropped meanwhile
+            if let Some(acquisition) = orders.get_mut(&key) {
+                acquisition.locked = true;
+            }
+        }
+        drop(orders);
+        HELD.with(|held| held.borrow_mut().push(Held { id: self.id, site }));
+        Ok(TrackedGuard { inner, id: self.id })
+    }
+
+    /// Records locking this mutex while holding `held` and returns the
+    /// orders that were new, or returns the error if that contradicts an
+    /// order seen before
+    fn check(&self, held: &[Held], site: Site) -> Result<Vec<(usize, usize)>, LockOrderError> {
+        let attempt = |first: Site| Acquisition {
+            thread: thread_name(),
+            first,
+            then: site,
+            locked: false,
+        };
+        if let Some(same) = held.iter().find(|lock| lock.id == self.id) {
+            return Err(LockOrderError {
+                attempt: attempt(same.site),
+                cycle: Vec::new(),
+            });
+        }
+
+        let mut orders = orders();
+        for lock in held {
+            if let Some(cycle) = path(&orders, self.id, lock.id) {
+                return Err(LockOrderError {
+                    attempt: attempt(lock.site),
+                    cycle,
+                });
+            }
+        }
+        let mut recorded = Vec::new();
+        for lock in held {
+            if let Entry::Vacant(entry) = orders.entry((lock.id, self.id)) {
+                entry.insert(attempt(lock.site));
+                recorded.push((lock.id, self.id));
+            }
+        }
+        Ok(recorded)
+    }
+}
+
+/// Forgets the orders this mutex took part in
+impl<T> Drop for TrackedMutex<T> {
+    fn drop(&mut self) {
+        orders().retain(|&(first, then), _| first != self.id && then != self.id);
+    }
+}
+
+impl<T> Deref for TrackedGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T> DerefMut for TrackedGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+}
+
+/// Prints the value, like std's guard
+impl<T: fmt::Debug> fmt::Debug for TrackedGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+/// Locks needn't be released in the reverse of the order they were taken
+impl<T> Drop for TrackedGuard<'_, T> {
+    fn drop(&mut self) {
+        HELD.with(|held| {
+            let mut held = held.borrow_mut();
+            if let Some(index) = held.iter().rposition(|lock| lock.id == self.id) {
+                held.remove(index);
+            }
+        });
+    }
+}
+
+impl fmt::Display for Site {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "`{}` at {}", self.lock, self.location)
+    }
+}
+
+impl fmt::Display for Acquisition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let then = if self.locked { "then" } else { "then tried to lock" };
+        write!(f, "thread '{}' locked {}, {} {}", self.thread, self.first, then, self.then)
+    }
+}
+
+/// e.g.
+///
+/// ```text
+/// lock order inversion: thread 'transfer 2' locked `savings` at src/concurrency.rs:40:30, then tried to lock `checking` at src/concurrency.rs:42:30
+///   but before that, thread 'transfer 1' locked `checking` at src/concurrency.rs:30:30, then `savings` at src/concurrency.rs:32:30
+/// ```
+impl fmt::Display for LockOrderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let kind = match self.cycle.len() {
+            0 => "relocking a held mutex",
+            1 => "lock order inversion",
+            _ => "lock order cycle",
+        };
+        write!(f, "{}: {}", kind, self.attempt)?;
+        for (i, acquisition) in self.cycle.iter().enumerate() {
+            let lead = if i == 0 { "but before that" } else { "and" };
+            write!(f, "\n  {}, {}", lead, acquisition)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for LockOrderError {}
+
+// ============================================================================
+// UNIT TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sched::{self, thread};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_a_consistent_order_is_fine() {
+        let a = TrackedMutex::new("a", 1);
+        let b = TrackedMutex::new("b", 2);
+        for _ in 0..2 {
+            let x = a.lock().unwrap();
+            let y = b.lock().unwrap();
+            assert_eq!(*x + *y, 3);
+        }
+        // Taking one alone says nothing about order
+        drop(b.lock().unwrap());
+        let mut x = a.lock().unwrap();
+        *x += 1;
+        assert_eq!(*x, 2);
+    }
+
+    #[test]
+    fn test_an_inversion_is_reported_with_its_sites() {
+        let a = TrackedMutex::new("a", ());
+        let b = TrackedMutex::new("b", ());
+        {
+            let _a = a.lock().unwrap();
+            let _b = b.lock().unwrap();
+        }
+        let _b = b.lock().unwrap();
+        let error = a.lock().unwrap_err();
+
+        assert_eq!(error.attempt.first.lock, "b");
+        assert_eq!(error.attempt.then.lock, "a");
+        assert_eq!(error.cycle.len(), 1);
+        assert_eq!((error.cycle[0].first.lock, error.cycle[0].then.lock), ("a", "b"));
+        assert_eq!(error.cycle[0].first.location.file(), file!());
+        assert!(error.cycle[0].first.location.line() < error.attempt.then.location.line());
+        assert!(error.cycle[0].locked && !error.attempt.locked);
+
+        let report = error.to_string();
+        assert!(report.starts_with("lock order inversion: thread '"), "{}", report);
+        assert!(report.contains(&format!("then tried to lock `a` at {}:", file!())), "{}", report);
+        assert!(report.contains("\n  but before that, thread '"), "{}", report);
+    }
+
+    #[test]
+    fn test_finds_longer_cycles_and_relocking() {
+        let locks: Vec<TrackedMutex<()>> = ["a", "b", "c"].into_iter().map(|name| TrackedMutex::new(name, ())).collect();
+        for pair in locks.windows(2) {
+            let _first = pair[0].lock().unwrap();
+            let _then = pair[1].lock().unwrap();
+        }
+        let _c = locks[2].lock().unwrap();
+        let error = locks[0].lock().unwrap_err();
+        let names: Vec<_> = error.cycle.iter().map(|step| (step.first.lock, step.then.lock)).collect();
+        assert_eq!(names, [("a", "b"), ("b", "c")]);
+        assert!(error.to_string().starts_with("lock order cycle:"));
+
+        let error = locks[2].lock().unwrap_err();
+        assert!(error.cycle.is_empty());
+        assert!(error.to_string().starts_with("relocking a held mutex:"));
+    }
+
+    #[test]
+    fn test_opposite_orders_on_two_threads_report_instead_of_hanging() {
+        for seed in 0..10 {
+            let errors = sched::run_seeded(seed, || {
+                let a = Arc::new(TrackedMutex::new("a", 0));
+                let b = Arc::new(TrackedMutex::new("b", 0));
+                let handles: Vec<_> = [("forward", true), ("backward", false)]
+                    .into_iter()
+                    .map(|(name, forward)| {
+                        let (first, then) = if forward {
+                            (Arc::clone(&a), Arc::clone(&b))
+                        } else {
+                            (Arc::clone(&b), Arc::clone(&a))
+                        };
+                        thread::Builder::new()
+                            .name(name.to_string())
+                            .spawn(move || {
+                                let _first = first.lock().unwrap();
+                                thread::sleep(std::time::Duration::from_millis(10));
+                                then.lock().map(|_| ()).err()
+                            })
+                            .unwrap()
+                    })
+                    .collect();
+                let errors: Vec<LockOrderError> =
+                    handles.into_iter().filter_map(|handle| handle.join().unwrap()).collect();
+                errors
+            });
+            assert_eq!(errors.len(), 1, "seed {}", seed);
+            let threads = [errors[0].attempt.thread.as_str(), errors[0].cycle[0].thread.as_str()];
+            assert!(threads == ["forward", "backward"] || threads == ["backward", "forward"], "{:?}", threads);
+        }
+    }
+}
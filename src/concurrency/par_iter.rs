@@ -0,0 +1,215 @@
+// ============================================================================
+// PARALLEL ITERATORS
+// ============================================================================
+// The iterator chains from the functional lessons, spread across threads by
+// swapping `iter()` for `par_iter()`:
+//
+//     use happyr::concurrency::ParallelSlice;
+//
+//     let evens: Vec<u64> = numbers.par_iter().filter(|n| *n % 2 == 0).map(|n| n * n).collect();
+//     let total: u64 = numbers.par_iter().map(|n| n * n).sum();
+//
+// The slice is cut into one contiguous chunk per worker, and each chunk runs
+// the whole chain in its own scoped thread, so the closures can borrow local
+// variables. Every thread sends its chunk's result back over a channel with
+// the chunk's position, and the results are put back in slice order: so
+// `collect` keeps the original order, and `reduce` only needs an operation
+// that is associative, not one that is also commutative. `for_each` is the
+// exception, since it runs as each thread gets to each item.
+//
+// The threads and the channel come from `crate::sched`, so the chunks
+// interleave reproducibly under `--deterministic`.
+// ============================================================================
+
+use crate::sched::{mpsc, thread};
+use std::iter::{FilterMap, Sum};
+use std::slice;
+
+/// Adds `par_iter` to slices, and so to `Vec` and arrays too
+pub trait ParallelSlice<T: Sync> {
+    /// Like `iter`, but the chain built on it runs across several threads
+    fn par_iter<'a>(&'a self) -> ParIter<'a, T, fn(&'a T) -> Option<&'a T>>;
+}
+
+impl<T: Sync> ParallelSlice<T> for [T] {
+    fn par_iter<'a>(&'a self) -> ParIter<'a, T, fn(&'a T) -> Option<&'a T>> {
+        ParIter {
+            items: self,
+            workers: thread_count(),
+            stages: Some,
+        }
+    }
+}
+
+/// A parallel iterator chain over a slice
+///
+/// `stages` is every `map` and `filter` so far combined into one function,
+/// which returns `None` for an item a filter dropped.
+pub struct ParIter<'a, T, F> {
+    items: &'a [T],
+    workers: usize,
+    stages: F,
+}
+
+/// A thread for each CPU the OS lets this process use
+fn thread_count() -> usize {
+    std::thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+impl<'a, T, U, F> ParIter<'a, T, F>
+where
+    T: Sync,
+    U: Send,
+    F: Fn(&'a T) -> Option<U> + Sync,
+{
+    /// Spreads the chain over `workers` threads instead of one per CPU
+    ///
+    /// Panics if `workers` is zero.
+    pub fn with_workers(self, workers: usize) -> Self {
+        assert!(workers > 0, "a parallel iterator needs at least one worker");
+        ParIter { workers, ..self }
+    }
+
+    pub fn map<V, G>(self, g: G) -> ParIter<'a, T, impl Fn(&'a T) -> Option<V> + Sync>
+    where
+        V: Send,
+        G: Fn(U) -> V + Sync,
+    {
+        let stages = self.stages;
+        ParIter {
+            items: self.items,
+            workers: self.workers,
+            stages: move |item| stages(item).map(&g),
+        }
+    }
+
+    pub fn filter<P>(self, predicate: P) -> ParIter<'a, T, impl Fn(&'a T) -> Option<U> + Sync>
+    where
+        P: Fn(&U) -> bool + Sync,
+    {
+        let stages = self.stages;
+        ParIter {
+            items: self.items,
+            workers: self.workers,
+            stages: move |item| stages(item).filter(&predicate),
+        }
+    }
+
+    /// Collects the items in their original order
+    pub fn collect<C: FromIterator<U>>(self) -> C {
+        self.run(|items| items.collect::<Vec<U>>()).into_iter().flatten().collect()
+    }
+
+    /// Combines the items with `op`, or returns `None` if there are none
+    ///
+    /// Each chunk is reduced on its own, then the chunks' results in order,
+    /// so `op` must be associative, e.g. `+` or string concatenation.
+    pub fn reduce<O>(self, op: O) -> Option<U>
+    where
+        O: Fn(U, U) -> U + Sync,
+    {
+        self.run(|items| items.reduce(&op)).into_iter().flatten().reduce(&op)
+    }
+
+    pub fn sum<S>(self) -> S
+    where
+        S: Sum<U> + Sum<S> + Send,
+    {
+        self.run(|items| items.sum::<S>()).into_iter().sum()
+    }
+
+    /// Calls `f` on every item, in whatever order the threads get to them
+    pub fn for_each<G>(self, f: G)
+    where
+        G: Fn(U) + Sync,
+    {
+        self.run(|items| items.for_each(&f));
+    }
+
+    /// Runs `per_chunk` over the chain for each chunk of the slice, each in
+    /// its own thread, and returns the results in the chunks' order
+    fn run<R, C>(self, per_chunk: C) -> Vec<R>
+    where
+        R: Send,
+        C: Fn(FilterMap<slice::Iter<'a, T>, &F>) -> R + Sync,
+    {
+        if self.items.is_empty() {
+            return Vec::new();
+        }
+        let size = self.items.len().div_ceil(self.workers);
+        let (tx, rx) = mpsc::channel();
+        thread::scope(|scope| {
+            for (index, chunk) in self.items.chunks(size).enumerate() {
+                let tx = tx.clone();
+                let per_chunk = &per_chunk;
+                let stages = &self.stages;
+                scope.spawn(move || {
+                    // The receiver outlives the scope, so this can't fail
+                    let _ = tx.send((index, per_chunk(chunk.iter().filter_map(stages))));
+                });
+            }
+        });
+        drop(tx);
+
+        let mut results: Vec<(usize, R)> = rx.iter().collect();
+        results.sort_by_key(|&(index, _)| index);
+        results.into_iter().map(|(_, result)| result).collect()
+    }
+}
+
+// ============================================================================
+// UNIT TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    #[test]
+    fn test_collect_keeps_the_original_order() {
+        let numbers: Vec<u64> = (1..=1_000).collect();
+        let expected: Vec<u64> = numbers.iter().filter(|&&n| n % 3 == 0).map(|n| n * 2).collect();
+        for workers in [1, 3, 8, 2_000] {
+            let doubled: Vec<u64> = numbers
+                .par_iter()
+                .with_workers(workers)
+                .filter(|&&n| n % 3 == 0)
+                .map(|n| n * 2)
+                .collect();
+            assert_eq!(doubled, expected, "with {} workers", workers);
+        }
+    }
+
+    #[test]
+    fn test_reduce_combines_chunks_in_order() {
+        let words = ["par", "allel", " ", "it", "er", "ators"];
+        let joined = words.par_iter().with_workers(4).map(|word| word.to_string()).reduce(|a, b| a + &b);
+        assert_eq!(joined.as_deref(), Some("parallel iterators"));
+        let empty: [u8; 0] = [];
+        assert_eq!(empty.par_iter().map(|&n| n).reduce(|a, b| a + b), None);
+    }
+
+    #[test]
+    fn test_sum() {
+        let numbers: Vec<u64> = (1..=100).collect();
+        assert_eq!(numbers.par_iter().with_workers(7).map(|n| n * n).sum::<u64>(), 338_350);
+        assert_eq!(numbers.par_iter().filter(|&&n| n > 100).sum::<u64>(), 0);
+    }
+
+    #[test]
+    fn test_for_each_visits_every_item_once() {
+        let total = AtomicU64::new(0);
+        let numbers: Vec<u64> = (1..=500).collect();
+        numbers.par_iter().with_workers(6).for_each(|&n| {
+            total.fetch_add(n, Ordering::SeqCst);
+        });
+        assert_eq!(total.load(Ordering::SeqCst), 125_250);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one worker")]
+    fn test_needs_a_worker() {
+        [1, 2, 3].par_iter().with_workers(0);
+    }
+}
@@ -0,0 +1,220 @@
+// ============================================================================
+// THREAD POOL
+// ============================================================================
+// A fixed set of worker threads taking jobs from one shared queue, the
+// pattern `practical_patterns` builds by hand, packaged for reuse:
+//
+//     let pool = ThreadPool::new(4);
+//     pool.execute(|| println!("fire and forget"));
+//     let answer = pool.spawn(|| 6 * 7);
+//     assert_eq!(answer.join().unwrap(), 42);
+//
+// The queue is a channel whose receiver the workers share behind a mutex;
+// whichever worker is idle takes the next job. A job that panics is caught
+// on its worker, which goes on to the next job, and `spawn`'s handle hands
+// the panic to whoever joins it. Dropping the pool closes the queue, lets
+// the workers finish every job already queued, and joins them.
+//
+// It's built on `crate::sched`, so its threads follow `--deterministic`
+// like the rest of the concurrency lessons.
+// ============================================================================
+
+use crate::sched::{mpsc, thread, Mutex};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+/// Runs jobs on a fixed number of worker threads
+pub struct ThreadPool {
+    workers: Vec<thread::JoinHandle<()>>,
+    /// `None` once the pool starts shutting down
+    queue: Option<mpsc::Sender<Job>>,
+    /// How many jobs have panicked so far
+    panics: Arc<AtomicUsize>,
+}
+
+/// Waits for the result of a job started with `ThreadPool::spawn`
+pub struct JobHandle<T> {
+    result: mpsc::Receiver<std::thread::Result<T>>,
+}
+
+impl ThreadPool {
+    /// Starts `workers` threads
+    ///
+    /// Panics if `workers` is zero.
+    pub fn new(workers: usize) -> ThreadPool {
+        assert!(workers > 0, "a thread pool needs at least one worker");
+        let (queue, jobs) = mpsc::channel::<Job>();
+        let jobs = Arc::new(Mutex::new(jobs));
+        let panics = Arc::new(AtomicUsize::new(0));
+
+        let workers = (0..workers)
+            .map(|_| {
+                let jobs = Arc::clone(&jobs);
+                let panics = Arc::clone(&panics);
+                thread::spawn(move || loop {
+                    // The guard is dropped at the end of the statement, so
+                    // other workers can take jobs while this one runs
+                    let job = jobs.lock().unwrap().recv();
+                    match job {
+                        Ok(job) => {
+                            if panic::catch_unwind(AssertUnwindSafe(job)).is_err() {
+                                panics.fetch_add(1, Ordering::SeqCst);
+                            }
+                        }
+                        // The pool was dropped and the queue is empty
+                        Err(_) => break,
+                    }
+                })
+            })
+            .collect();
+
+        ThreadPool {
+            workers,
+            queue: Some(queue),
+            panics,
+        }
+    }
+
+    /// A pool with a worker for each CPU the OS lets this process use
+    pub fn with_available_parallelism() -> ThreadPool {
+        ThreadPool::new(std::thread::available_parallelism().map_or(1, |n| n.get()))
+    }
+
+    pub fn workers(&self) -> usize {
+        self.workers.len()
+    }
+
+    /// How many jobs have panicked, each caught without ending its worker
+    pub fn panicked_jobs(&self) -> usize {
+        self.panics.load(Ordering::SeqCst)
+    }
+
+    /// Queues `job` to run on the next idle worker
+    pub fn execute<F>(&self, job: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.queue
+            .as_ref()
+            .expect("the queue only closes when the pool is dropped")
+            .send(Box::new(job))
+            .expect("workers only stop once the queue closes");
+    }
+
+    /// Queues `job` and returns a handle to wait for what it returns
+    pub fn spawn<F, T>(&self, job: F) -> JobHandle<T>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let (sender, result) = mpsc::channel();
+        let panics = Arc::clone(&self.panics);
+        self.execute(move || {
+            let outcome = panic::catch_unwind(AssertUnwindSafe(job));
+            if outcome.is_err() {
+                // Counted before the handle can see it
+                panics.fetch_add(1, Ordering::SeqCst);
+            }
+            // Nobody may be waiting for the result any more
+            let _ = sender.send(outcome);
+        });
+        JobHandle { result }
+    }
+}
+
+impl<T> JobHandle<T> {
+    /// Waits for the job to finish: `Err` holds its panic, like
+    /// `std::thread::JoinHandle::join`
+    pub fn join(self) -> std::thread::Result<T> {
+        self.result
+            .recv()
+            .expect("every queued job runs before the pool shuts down")
+    }
+}
+
+/// Finishes every queued job, then stops the workers
+impl Drop for ThreadPool {
+    fn drop(&mut self) {
+        drop(self.queue.take());
+        for worker in self.workers.drain(..) {
+            // Jobs' panics are caught, so a worker can't have panicked
+            let _ = worker.join();
+        }
+    }
+}
+
+// ============================================================================
+// UNIT TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_spawn_returns_results() {
+        let pool = ThreadPool::new(3);
+        assert_eq!(pool.workers(), 3);
+        let handles: Vec<_> = (0..10).map(|i| pool.spawn(move || i * i)).collect();
+        let squares: Vec<i32> = handles.into_iter().map(|handle| handle.join().unwrap()).collect();
+        assert_eq!(squares, [0, 1, 4, 9, 16, 25, 36, 49, 64, 81]);
+    }
+
+    #[test]
+    fn test_drop_finishes_queued_jobs() {
+        let done = Arc::new(AtomicUsize::new(0));
+        let pool = ThreadPool::new(2);
+        for _ in 0..8 {
+            let done = Arc::clone(&done);
+            pool.execute(move || {
+                std::thread::sleep(Duration::from_millis(5));
+                done.fetch_add(1, Ordering::SeqCst);
+            });
+        }
+        drop(pool);
+        assert_eq!(done.load(Ordering::SeqCst), 8);
+    }
+
+    #[test]
+    fn test_a_panicking_job_leaves_its_worker_running() {
+        // One worker, so the jobs after the panic can only run on it
+        let pool = ThreadPool::new(1);
+        pool.execute(|| panic!("first job fails"));
+        let failed = pool.spawn(|| -> i32 { panic!("second job fails") });
+        let after = pool.spawn(|| "still working");
+
+        let payload = failed.join().unwrap_err();
+        assert_eq!(payload.downcast_ref::<&str>(), Some(&"second job fails"));
+        assert_eq!(after.join().unwrap(), "still working");
+        assert_eq!(pool.panicked_jobs(), 2);
+    }
+
+    #[test]
+    fn test_runs_under_the_seeded_scheduler() {
+        let order = |seed| {
+            crate::sched::run_seeded(seed, || {
+                let pool = ThreadPool::new(2);
+                let log = Arc::new(Mutex::new(Vec::new()));
+                for job in 0..6 {
+                    let log = Arc::clone(&log);
+                    pool.execute(move || log.lock().unwrap().push(job));
+                }
+                drop(pool);
+                let order = log.lock().unwrap().clone();
+                order
+            })
+        };
+        assert_eq!(order(3), order(3));
+        assert_eq!(order(3).len(), 6);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one worker")]
+    fn test_needs_a_worker() {
+        ThreadPool::new(0);
+    }
+}
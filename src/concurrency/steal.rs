@@ -0,0 +1,286 @@
+// ============================================================================
+// WORK STEALING
+// ============================================================================
+// Splitting work into fixed chunks up front, as `parallel_computation` does,
+// goes badly when some chunks cost far more than others: the threads with
+// cheap chunks finish early and sit idle. A work-stealing executor lets
+// divide-and-conquer code say where it *could* split instead, and balances
+// the pieces while they run:
+//
+//     let executor = Executor::new(4);
+//     let total = executor.run(|worker| sum(worker, &numbers));
+//
+//     fn sum<'env>(worker: &Worker<'_, 'env>, numbers: &'env [u64]) -> u64 {
+//         if numbers.len() <= 1_000 {
+//             return numbers.iter().sum();
+//         }
+//         let (left, right) = numbers.split_at(numbers.len() / 2);
+//         let (a, b) = worker.join(|w| sum(w, left), |w| sum(w, right));
+//         a + b
+//     }
+//
+// Each worker has its own deque of jobs. `join(a, b)` pushes `b` onto the
+// back of its worker's deque and runs `a` straight away. A worker that runs
+// out of jobs steals from the front of a peer's deque, where the oldest and
+// so usually the biggest pieces of work are. Once `a` is done, its worker
+// pops `b` back off and runs it, unless a thief got to it first; then it
+// runs or steals other jobs until `b` is finished.
+//
+// `run` starts the workers inside `std::thread::scope`, so jobs can borrow
+// anything that outlives the call, and stops them before it returns. Which
+// worker steals what depends on timing, so these are plain OS threads even
+// under `--deterministic`; the results don't depend on who ran what.
+// ============================================================================
+
+use std::collections::VecDeque;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+
+type Job<'env> = Box<dyn FnOnce(&Worker<'_, 'env>) + Send + 'env>;
+
+/// Runs fork-join computations on a fixed number of workers
+#[derive(Debug, Clone, Copy)]
+pub struct Executor {
+    workers: usize,
+}
+
+/// One of an executor's workers, handed to every job it runs
+pub struct Worker<'s, 'env> {
+    shared: &'s Shared<'env>,
+    index: usize,
+}
+
+/// What the workers of one `run` share
+struct Shared<'env> {
+    /// One per worker: its owner uses the back, thieves the front
+    deques: Vec<Mutex<VecDeque<Job<'env>>>>,
+    /// Counts pushes and finished jobs, so an idle worker can tell whether
+    /// anything happened since it last looked for work
+    events: Mutex<u64>,
+    changed: Condvar,
+    /// Set once the computation has returned
+    done: AtomicBool,
+}
+
+impl Executor {
+    /// An executor with `workers` workers, counting the thread that calls
+    /// `run`
+    ///
+    /// Panics if `workers` is zero.
+    pub fn new(workers: usize) -> Executor {
+        assert!(workers > 0, "an executor needs at least one worker");
+        Executor { workers }
+    }
+
+    /// An executor with a worker for each CPU the OS lets this process use
+    pub fn with_available_parallelism() -> Executor {
+        Executor::new(thread::available_parallelism().map_or(1, |n| n.get()))
+    }
+
+    pub fn workers(&self) -> usize {
+        self.workers
+    }
+
+    /// Runs `f` on the calling thread as the first worker, with the others
+    /// stealing whatever it and its `join`s offer, and returns its result
+    /// once every job has finished
+    pub fn run<'env, T>(&self, f: impl FnOnce(&Worker<'_, 'env>) -> T) -> T {
+        let shared = Shared {
+            deques: (0..self.workers).map(|_| Mutex::new(VecDeque::new())).collect(),
+            events: Mutex::new(0),
+            changed: Condvar::new(),
+            done: AtomicBool::new(false),
+        };
+
+        /// Stops the other workers when `f` returns, or panics
+        struct Done<'a, 'env>(&'a Shared<'env>);
+
+        impl Drop for Done<'_, '_> {
+            fn drop(&mut self) {
+                self.0.done.store(true, Ordering::SeqCst);
+                self.0.signal();
+            }
+        }
+
+        thread::scope(|scope| {
+            for index in 1..self.workers {
+                let shared = &shared;
+                scope.spawn(move || Worker { shared, index }.work());
+            }
+            let _done = Done(&shared);
+            f(&Worker {
+                shared: &shared,
+                index: 0,
+            })
+        })
+    }
+}
+
+impl<'env> Worker<'_, 'env> {
+    /// Which worker this is, from 0 for the thread that called `run`
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Runs `a` and `b`, in parallel if another worker is free to steal
+    /// `b`, and returns both results
+    ///
+    /// If either panics, the panic is passed on once both have finished.
+    pub fn join<A, B, RA, RB>(&self, a: A, b: B) -> (RA, RB)
+    where
+        A: FnOnce(&Self) -> RA,
+        B: FnOnce(&Worker<'_, 'env>) -> RB + Send + 'env,
+        RB: Send + 'env,
+    {
+        let slot = Arc::new(Mutex::new(None));
+        let result = Arc::clone(&slot);
+        self.push(Box::new(move |worker| {
+            let outcome = panic::catch_unwind(AssertUnwindSafe(|| b(worker)));
+            *result.lock().unwrap() = Some(outcome);
+            worker.shared.signal();
+        }));
+
+        let a = panic::catch_unwind(AssertUnwindSafe(|| a(self)));
+        // `b` is still at the back of this worker's deque unless it was
+        // stolen: either way, keep busy until it has finished
+        let b = loop {
+            let seen = self.shared.events();
+            if let Some(outcome) = slot.lock().unwrap().take() {
+                break outcome;
+            }
+            match self.find_job() {
+                Some(job) => job(self),
+                None => self.shared.wait(seen),
+            }
+        };
+
+        match (a, b) {
+            (Ok(a), Ok(b)) => (a, b),
+            (Err(payload), _) | (_, Err(payload)) => panic::resume_unwind(payload),
+        }
+    }
+
+    fn push(&self, job: Job<'env>) {
+        self.shared.deques[self.index].lock().unwrap().push_back(job);
+        self.shared.signal();
+    }
+
+    /// The newest job on this worker's deque, or else the oldest one on a
+    /// peer's, trying each peer in turn
+    fn find_job(&self) -> Option<Job<'env>> {
+        let deques = &self.shared.deques;
+        if let Some(job) = deques[self.index].lock().unwrap().pop_back() {
+            return Some(job);
+        }
+        (1..deques.len())
+            .map(|offset| (self.index + offset) % deques.len())
+            .find_map(|peer| deques[peer].lock().unwrap().pop_front())
+    }
+
+    /// The loop every worker but the first runs until `run` is finished
+    fn work(&self) {
+        loop {
+            let seen = self.shared.events();
+            if let Some(job) = self.find_job() {
+                job(self);
+            } else if self.shared.done.load(Ordering::SeqCst) {
+                return;
+            } else {
+                self.shared.wait(seen);
+            }
+        }
+    }
+}
+
+impl Shared<'_> {
+    fn events(&self) -> u64 {
+        *self.events.lock().unwrap()
+    }
+
+    /// Wakes the workers waiting for something to happen
+    fn signal(&self) {
+        *self.events.lock().unwrap() += 1;
+        self.changed.notify_all();
+    }
+
+    /// Sleeps until something has happened since `seen` was read
+    fn wait(&self, seen: u64) {
+        let events = self.events.lock().unwrap();
+        drop(self.changed.wait_while(events, |events| *events == seen).unwrap());
+    }
+}
+
+// ============================================================================
+// UNIT TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    fn sum<'env>(worker: &Worker<'_, 'env>, numbers: &'env [u64]) -> u64 {
+        if numbers.len() <= 8 {
+            return numbers.iter().sum();
+        }
+        let (left, right) = numbers.split_at(numbers.len() / 2);
+        let (a, b) = worker.join(|w| sum(w, left), |w| sum(w, right));
+        a + b
+    }
+
+    #[test]
+    fn test_nested_joins_add_up() {
+        let numbers: Vec<u64> = (1..=10_000).collect();
+        for workers in [1, 2, 4] {
+            let total = Executor::new(workers).run(|worker| sum(worker, &numbers));
+            assert_eq!(total, 50_005_000, "with {} workers", workers);
+        }
+    }
+
+    #[test]
+    fn test_an_idle_worker_steals() {
+        // `a` waits until `b` has run, which only a thief can do
+        let started = AtomicBool::new(false);
+        let (a, b) = Executor::new(2).run(|worker| {
+            worker.join(
+                |w| {
+                    let deadline = Instant::now() + Duration::from_secs(10);
+                    while !started.load(Ordering::SeqCst) {
+                        assert!(Instant::now() < deadline, "b was never stolen");
+                        thread::yield_now();
+                    }
+                    w.index()
+                },
+                |w| {
+                    started.store(true, Ordering::SeqCst);
+                    w.index()
+                },
+            )
+        });
+        assert_eq!((a, b), (0, 1));
+    }
+
+    #[test]
+    fn test_a_panic_is_passed_on_after_both_finish() {
+        let finished = AtomicBool::new(false);
+        let outcome = panic::catch_unwind(AssertUnwindSafe(|| {
+            Executor::new(2).run(|worker| {
+                worker.join(
+                    |_| -> u8 { panic!("left side fails") },
+                    |_| finished.store(true, Ordering::SeqCst),
+                )
+            })
+        }));
+        let payload = outcome.unwrap_err();
+        assert_eq!(payload.downcast_ref::<&str>(), Some(&"left side fails"));
+        assert!(finished.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one worker")]
+    fn test_needs_a_worker() {
+        Executor::new(0);
+    }
+}
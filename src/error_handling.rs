@@ -9,8 +9,20 @@
 // - Custom error types
 // ============================================================================
 
+// The long-hand error handling is shown on purpose, before ? and the
+// Option/Result combinators that replace it.
+#![allow(
+    dead_code,
+    clippy::bind_instead_of_map,
+    clippy::question_mark,
+    clippy::unnecessary_lazy_evaluations,
+    clippy::unnecessary_literal_unwrap,
+)]
+
+use crate::lesson::{lesson, Difficulty, Section};
+use std::io::{self, Write};
 use std::fs::File;
-use std::io::{self, Read, ErrorKind};
+use std::io::{Read, ErrorKind};
 use std::fmt;
 
 /// Demonstrates panic! for unrecoverable errors
@@ -19,18 +31,20 @@ use std::fmt;
 /// - Used for unrecoverable errors
 /// - Unwinds the stack and cleans up
 /// - Can set RUST_BACKTRACE=1 for backtrace
-pub fn panic_basics() {
-    println!("\n--- Panic Basics ---");
+pub fn panic_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Panic Basics ---")?;
     
     // Explicit panic
     // panic!("crash and burn"); // Uncomment to see panic
     
     // Panic from out of bounds access
-    let v = vec![1, 2, 3];
-    // v[99]; // Uncomment to see panic
+    let _v = [1, 2, 3];
+    // _v[99]; // Uncomment to see panic
+    
+    writeln!(out, "Panic examples are commented out to avoid crashing!")?;
+    writeln!(out, "Uncomment them in the source to see how panics work.")?;
     
-    println!("Panic examples are commented out to avoid crashing!");
-    println!("Uncomment them in the source to see how panics work.");
+    Ok(())
 }
 
 /// Demonstrates Result<T, E> for recoverable errors
@@ -39,15 +53,15 @@ pub fn panic_basics() {
 /// - Enum with Ok(T) and Err(E) variants
 /// - Used for operations that might fail
 /// - Forces explicit error handling
-pub fn result_basics() {
-    println!("\n--- Result Basics ---");
+pub fn result_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Result Basics ---")?;
     
     // File operations return Result
     let file_result = File::open("hello.txt");
     
     match file_result {
-        Ok(file) => println!("File opened successfully: {:?}", file),
-        Err(error) => println!("Failed to open file: {:?}", error),
+        Ok(file) => writeln!(out, "File opened successfully: {:?}", file)?,
+        Err(error) => writeln!(out, "Failed to open file: {:?}", error)?,
     }
     
     // Handling different error kinds
@@ -57,15 +71,17 @@ pub fn result_basics() {
         Ok(file) => file,
         Err(error) => match error.kind() {
             ErrorKind::NotFound => {
-                println!("File not found, would create it here");
-                return; // Skip file creation for this example
+                writeln!(out, "File not found, would create it here")?;
+                return Ok(()); // Skip file creation for this example
             }
             other_error => {
-                println!("Problem opening file: {:?}", other_error);
-                return;
+                writeln!(out, "Problem opening file: {:?}", other_error)?;
+                return Ok(());
             }
         },
     };
+    
+    Ok(())
 }
 
 /// Demonstrates unwrap and expect
@@ -73,8 +89,8 @@ pub fn result_basics() {
 /// SHORTCUTS:
 /// - unwrap(): Returns value or panics
 /// - expect(): Like unwrap but with custom message
-pub fn unwrap_and_expect() {
-    println!("\n--- Unwrap and Expect ---");
+pub fn unwrap_and_expect(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Unwrap and Expect ---")?;
     
     // unwrap: panics on error
     // let file = File::open("hello.txt").unwrap(); // Panics if file doesn't exist
@@ -83,15 +99,17 @@ pub fn unwrap_and_expect() {
     // let file = File::open("hello.txt")
     //     .expect("Failed to open hello.txt"); // Better error message
     
-    println!("Unwrap/expect examples commented out to avoid panics");
+    writeln!(out, "Unwrap/expect examples commented out to avoid panics")?;
     
     // Safe alternative: unwrap_or
     let default_value = Some(5).unwrap_or(0);
-    println!("With default: {}", default_value);
+    writeln!(out, "With default: {}", default_value)?;
     
     let none_value: Option<i32> = None;
     let with_default = none_value.unwrap_or(10);
-    println!("None with default: {}", with_default);
+    writeln!(out, "None with default: {}", with_default)?;
+    
+    Ok(())
 }
 
 /// Demonstrates error propagation
@@ -99,18 +117,20 @@ pub fn unwrap_and_expect() {
 /// ERROR PROPAGATION:
 /// - Return errors to calling code
 /// - Use ? operator for concise propagation
-pub fn error_propagation() {
-    println!("\n--- Error Propagation ---");
+pub fn error_propagation(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Error Propagation ---")?;
     
     match read_username_from_file() {
-        Ok(username) => println!("Username: {}", username),
-        Err(e) => println!("Error reading username: {}", e),
+        Ok(username) => writeln!(out, "Username: {}", username)?,
+        Err(e) => writeln!(out, "Error reading username: {}", e)?,
     }
     
     match read_username_short() {
-        Ok(username) => println!("Username (short): {}", username),
-        Err(e) => println!("Error reading username: {}", e),
+        Ok(username) => writeln!(out, "Username (short): {}", username)?,
+        Err(e) => writeln!(out, "Error reading username: {}", e)?,
     }
+    
+    Ok(())
 }
 
 // Verbose error propagation
@@ -151,8 +171,8 @@ fn read_username_shortest() -> Result<String, io::Error> {
 /// - Returns error if Result is Err
 /// - Unwraps value if Result is Ok
 /// - Automatically converts error types (with From trait)
-pub fn question_mark_operator() {
-    println!("\n--- ? Operator ---");
+pub fn question_mark_operator(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- ? Operator ---")?;
     
     // ? can only be used in functions that return Result or Option
     fn divide(a: i32, b: i32) -> Result<i32, String> {
@@ -170,9 +190,11 @@ pub fn question_mark_operator() {
     }
     
     match calculate() {
-        Ok(result) => println!("Calculation result: {}", result),
-        Err(e) => println!("Calculation error: {}", e),
+        Ok(result) => writeln!(out, "Calculation result: {}", result)?,
+        Err(e) => writeln!(out, "Calculation error: {}", e)?,
     }
+    
+    Ok(())
 }
 
 /// Demonstrates Option<T> for optional values
@@ -181,36 +203,38 @@ pub fn question_mark_operator() {
 /// - Represents a value that might be absent
 /// - Variants: Some(T) or None
 /// - No null in Rust!
-pub fn option_handling() {
-    println!("\n--- Option Handling ---");
+pub fn option_handling(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Option Handling ---")?;
     
     let some_number = Some(5);
     let no_number: Option<i32> = None;
     
     // Pattern matching
     match some_number {
-        Some(n) => println!("Got number: {}", n),
-        None => println!("No number"),
+        Some(n) => writeln!(out, "Got number: {}", n)?,
+        None => writeln!(out, "No number")?,
     }
     
     // if let
     if let Some(n) = some_number {
-        println!("Number via if let: {}", n);
+        writeln!(out, "Number via if let: {}", n)?;
     }
     
     // Combinators
     let doubled = some_number.map(|n| n * 2);
-    println!("Doubled: {:?}", doubled);
+    writeln!(out, "Doubled: {:?}", doubled)?;
     
     let or_else = no_number.or(Some(10));
-    println!("With or: {:?}", or_else);
+    writeln!(out, "With or: {:?}", or_else)?;
     
     let and_then = some_number.and_then(|n| Some(n + 5));
-    println!("And then: {:?}", and_then);
+    writeln!(out, "And then: {:?}", and_then)?;
     
     // Converting Option to Result
     let result: Result<i32, &str> = some_number.ok_or("No value");
-    println!("Option to Result: {:?}", result);
+    writeln!(out, "Option to Result: {:?}", result)?;
+    
+    Ok(())
 }
 
 /// Demonstrates custom error types
@@ -219,27 +243,30 @@ pub fn option_handling() {
 /// - Create your own error types
 /// - Implement Error trait
 /// - Provide better error information
-pub fn custom_errors() {
-    println!("\n--- Custom Errors ---");
+pub fn custom_errors(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Custom Errors ---")?;
     
     match parse_age("25") {
-        Ok(age) => println!("Parsed age: {}", age),
-        Err(e) => println!("Error: {}", e),
+        Ok(age) => writeln!(out, "Parsed age: {}", age)?,
+        Err(e) => writeln!(out, "Error: {}", e)?,
     }
     
     match parse_age("-5") {
-        Ok(age) => println!("Parsed age: {}", age),
-        Err(e) => println!("Error: {}", e),
+        Ok(age) => writeln!(out, "Parsed age: {}", age)?,
+        Err(e) => writeln!(out, "Error: {}", e)?,
     }
     
     match parse_age("abc") {
-        Ok(age) => println!("Parsed age: {}", age),
-        Err(e) => println!("Error: {}", e),
+        Ok(age) => writeln!(out, "Parsed age: {}", age)?,
+        Err(e) => writeln!(out, "Error: {}", e)?,
     }
+    
+    Ok(())
 }
 
-#[derive(Debug)]
-enum AgeError {
+/// Why `parse_age` rejected its input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AgeError {
     InvalidFormat,
     NegativeAge,
     TooOld,
@@ -257,7 +284,9 @@ impl fmt::Display for AgeError {
 
 impl std::error::Error for AgeError {}
 
-fn parse_age(input: &str) -> Result<u32, AgeError> {
+/// Parses a person's age, rejecting anything that isn't a whole number
+/// from 0 to 150
+pub fn parse_age(input: &str) -> Result<u32, AgeError> {
     let age: u32 = input.parse().map_err(|_| AgeError::InvalidFormat)?;
     
     if age > 150 {
@@ -268,13 +297,15 @@ fn parse_age(input: &str) -> Result<u32, AgeError> {
 }
 
 /// Demonstrates combining different error types
-pub fn combining_errors() {
-    println!("\n--- Combining Errors ---");
+pub fn combining_errors(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Combining Errors ---")?;
     
     match read_and_parse() {
-        Ok(number) => println!("Parsed number: {}", number),
-        Err(e) => println!("Error: {}", e),
+        Ok(number) => writeln!(out, "Parsed number: {}", number)?,
+        Err(e) => writeln!(out, "Error: {}", e)?,
     }
+    
+    Ok(())
 }
 
 // Custom error that can hold different error types
@@ -316,40 +347,42 @@ fn read_and_parse() -> Result<i32, MyError> {
 }
 
 /// Demonstrates Result methods and combinators
-pub fn result_methods() {
-    println!("\n--- Result Methods ---");
+pub fn result_methods(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Result Methods ---")?;
     
     let ok_result: Result<i32, &str> = Ok(10);
     let err_result: Result<i32, &str> = Err("error");
     
     // is_ok and is_err
-    println!("Is ok: {}", ok_result.is_ok());
-    println!("Is err: {}", err_result.is_err());
+    writeln!(out, "Is ok: {}", ok_result.is_ok())?;
+    writeln!(out, "Is err: {}", err_result.is_err())?;
     
     // map: transform Ok value
     let doubled = ok_result.map(|x| x * 2);
-    println!("Mapped: {:?}", doubled);
+    writeln!(out, "Mapped: {:?}", doubled)?;
     
     // map_err: transform Err value
     let mapped_err = err_result.map_err(|e| format!("Error: {}", e));
-    println!("Mapped error: {:?}", mapped_err);
+    writeln!(out, "Mapped error: {:?}", mapped_err)?;
     
     // and_then: chain operations
     let result = ok_result.and_then(|x| Ok(x + 5));
-    println!("And then: {:?}", result);
+    writeln!(out, "And then: {:?}", result)?;
     
     // or_else: provide alternative
-    let result = err_result.or_else(|_| Ok(0));
-    println!("Or else: {:?}", result);
+    let result: Result<i32, &str> = err_result.or_else(|_| Ok(0));
+    writeln!(out, "Or else: {:?}", result)?;
     
     // unwrap_or_else: compute default
     let value = err_result.unwrap_or_else(|_| 42);
-    println!("Unwrap or else: {}", value);
+    writeln!(out, "Unwrap or else: {}", value)?;
+    
+    Ok(())
 }
 
 /// Demonstrates early returns with ?
-pub fn early_returns() {
-    println!("\n--- Early Returns ---");
+pub fn early_returns(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Early Returns ---")?;
     
     fn process_data() -> Result<String, String> {
         let step1 = validate_input("valid")?;
@@ -375,45 +408,72 @@ pub fn early_returns() {
     }
     
     match process_data() {
-        Ok(result) => println!("Success: {}", result),
-        Err(e) => println!("Failed: {}", e),
+        Ok(result) => writeln!(out, "Success: {}", result)?,
+        Err(e) => writeln!(out, "Failed: {}", e)?,
     }
+    
+    Ok(())
 }
 
 /// Demonstrates when to use panic vs Result
-pub fn panic_vs_result() {
-    println!("\n--- Panic vs Result ---");
-    
-    println!("Use panic! when:");
-    println!("  - Unrecoverable errors (programmer mistakes)");
-    println!("  - Prototyping (unwrap/expect)");
-    println!("  - Tests (should panic)");
-    println!("  - Impossible situations");
-    
-    println!("\nUse Result when:");
-    println!("  - Recoverable errors");
-    println!("  - Expected failures (file not found, network error)");
-    println!("  - Library code (let caller decide)");
-    println!("  - User input validation");
+pub fn panic_vs_result(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Panic vs Result ---")?;
+    
+    writeln!(out, "Use panic! when:")?;
+    writeln!(out, "  - Unrecoverable errors (programmer mistakes)")?;
+    writeln!(out, "  - Prototyping (unwrap/expect)")?;
+    writeln!(out, "  - Tests (should panic)")?;
+    writeln!(out, "  - Impossible situations")?;
+    
+    writeln!(out, "\nUse Result when:")?;
+    writeln!(out, "  - Recoverable errors")?;
+    writeln!(out, "  - Expected failures (file not found, network error)")?;
+    writeln!(out, "  - Library code (let caller decide)")?;
+    writeln!(out, "  - User input validation")?;
+    
+    Ok(())
 }
 
 // ============================================================================
 // PUBLIC INTERFACE
 // ============================================================================
 
-pub fn run_all_examples() {
-    panic_basics();
-    result_basics();
-    unwrap_and_expect();
-    error_propagation();
-    question_mark_operator();
-    option_handling();
-    custom_errors();
-    combining_errors();
-    result_methods();
-    early_returns();
-    panic_vs_result();
-}
+/// This module's lessons, in teaching order
+pub static SECTION: Section = Section {
+    id: "error_handling",
+    number: 6,
+    title: "ERROR HANDLING",
+    icon: "⚠️",
+    lessons: &[
+        &lesson!(panic_basics, "Panic Basics").with_tags(&["error-handling", "panic"]),
+        &lesson!(result_basics, "Result Basics").with_tags(&["error-handling", "result"]),
+        &lesson!(unwrap_and_expect, "Unwrap and Expect")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["error-handling", "result", "option"]),
+        &lesson!(error_propagation, "Error Propagation")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["error-handling", "result"]),
+        &lesson!(question_mark_operator, "? Operator")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["error-handling", "result", "operators"]),
+        &lesson!(option_handling, "Option Handling").with_tags(&["option"]),
+        &lesson!(custom_errors, "Custom Errors")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["error-handling", "traits"]),
+        &lesson!(combining_errors, "Combining Errors")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["error-handling", "trait-objects"]),
+        &lesson!(result_methods, "Result Methods")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["result", "closures"]),
+        &lesson!(early_returns, "Early Returns")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["result", "control-flow"]),
+        &lesson!(panic_vs_result, "Panic vs Result")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["error-handling", "panic", "result"]),
+    ],
+};
 
 // ============================================================================
 // UNIT TESTS
@@ -0,0 +1,317 @@
+// ============================================================================
+// EXERCISES
+// ============================================================================
+// Each exercise is a stub in `exercises/<section>/<name>.rs` with a `todo!()`
+// for the learner to replace, plus hidden tests in `exercises/.hidden/`.
+// `happyr exercise check ownership/first_word` compiles the learner's file
+// with the hidden tests appended, using `rustc --test`, runs the resulting
+// binary and reports each test with a hint for the ones that fail.
+//
+// Hints live in the hidden test files, as `// hint:` comments right above
+// the test they belong to. The reference solutions are the functions of the
+// same name in the lesson modules; the unit tests below check that they pass.
+// ============================================================================
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A stub to fill in, with the tests that check it
+pub struct Exercise {
+    /// `<section>/<name>`, also the stub's path under `exercises/`
+    pub id: &'static str,
+    /// The lesson that teaches what the exercise needs
+    pub lesson: &'static str,
+    tests: &'static str,
+}
+
+// Unused in a build without any of the exercises' sections
+#[allow(unused_macros)]
+macro_rules! exercise {
+    ($id:literal, $lesson:literal) => {
+        Exercise {
+            id: $id,
+            lesson: $lesson,
+            tests: include_str!(concat!("../exercises/.hidden/", $id, ".rs")),
+        }
+    };
+}
+
+pub static EXERCISES: &[Exercise] = &[
+    #[cfg(feature = "ownership")]
+    exercise!("ownership/first_word", "ownership::string_slices"),
+    #[cfg(feature = "traits_generics")]
+    exercise!("traits_generics/largest", "traits_generics::generic_functions"),
+    #[cfg(feature = "error_handling")]
+    exercise!("error_handling/parse_age", "error_handling::custom_errors"),
+    #[cfg(feature = "unsafe-lessons")]
+    exercise!("advanced/split_at_mut", "advanced::unsafe_functions"),
+];
+
+pub fn find(id: &str) -> Option<&'static Exercise> {
+    EXERCISES.iter().find(|exercise| exercise.id == id)
+}
+
+impl Exercise {
+    /// Where the learner's copy of the stub lives
+    pub fn path(&self) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("exercises")
+            .join(format!("{}.rs", self.id))
+    }
+
+    /// The `// hint:` comments above each hidden test, by test name
+    fn hints(&self) -> HashMap<&'static str, String> {
+        let mut hints = HashMap::new();
+        let mut pending: Vec<&str> = Vec::new();
+        for line in self.tests.lines().map(str::trim) {
+            if let Some(hint) = line.strip_prefix("// hint:") {
+                pending.push(hint.trim());
+            } else if let Some(rest) = line.strip_prefix("fn ") {
+                let name = rest.split('(').next().unwrap_or(rest).trim();
+                if !pending.is_empty() {
+                    hints.insert(name, pending.join(" "));
+                }
+                pending.clear();
+            }
+        }
+        hints
+    }
+}
+
+/// What happened when an exercise was checked
+#[derive(Debug)]
+pub enum Outcome {
+    /// rustc's error messages
+    CompileError(String),
+    Ran(Vec<TestResult>),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct TestResult {
+    pub name: String,
+    pub passed: bool,
+    /// The panic message of a failed test
+    pub message: Option<String>,
+}
+
+/// Compiles `source` with the exercise's hidden tests and runs them
+pub fn check(exercise: &Exercise, source: &str) -> io::Result<Outcome> {
+    static BUILDS: AtomicUsize = AtomicUsize::new(0);
+
+    let dir = std::env::temp_dir().join(format!(
+        "happyr-exercise-{}-{}",
+        std::process::id(),
+        BUILDS.fetch_add(1, Ordering::Relaxed)
+    ));
+    fs::create_dir_all(&dir)?;
+    let result = compile_and_run(exercise, source, &dir);
+    let _ = fs::remove_dir_all(&dir);
+    result
+}
+
+fn compile_and_run(exercise: &Exercise, source: &str, dir: &std::path::Path) -> io::Result<Outcome> {
+    let file = dir.join("exercise.rs");
+    let binary = dir.join("exercise");
+    fs::write(
+        &file,
+        format!(
+            "{}\n\n#[cfg(test)]\nmod hidden_tests {{\n    use super::*;\n\n{}\n}}\n",
+            source, exercise.tests
+        ),
+    )?;
+
+    let rustc = std::env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+    let compiled = Command::new(rustc)
+        .args(["--edition", "2021", "--test", "-A", "warnings", "-o"])
+        .arg(&binary)
+        .arg(&file)
+        .output()
+        .map_err(|e| io::Error::new(e.kind(), format!("can't run rustc: {}", e)))?;
+    if !compiled.status.success() {
+        let errors = String::from_utf8_lossy(&compiled.stderr);
+        // Point at the learner's file rather than the temporary copy
+        let errors = errors.replace(&file.display().to_string(), &format!("exercises/{}.rs", exercise.id));
+        return Ok(Outcome::CompileError(errors));
+    }
+
+    let ran = Command::new(&binary)
+        .arg("--test-threads=1")
+        .env("RUST_BACKTRACE", "0")
+        .output()?;
+    Ok(Outcome::Ran(parse_results(&String::from_utf8_lossy(&ran.stdout))))
+}
+
+/// Reads the test harness output: one `test hidden_tests::x ... ok` line per
+/// test, then a `---- hidden_tests::x stdout ----` block per failure
+fn parse_results(stdout: &str) -> Vec<TestResult> {
+    let mut messages: HashMap<&str, String> = HashMap::new();
+    let mut current: Option<(&str, Vec<&str>)> = None;
+    for line in stdout.lines() {
+        if let Some(header) = line.strip_prefix("---- hidden_tests::") {
+            if let Some((name, lines)) = current.take() {
+                messages.insert(name, lines.join("\n"));
+            }
+            current = header.strip_suffix(" stdout ----").map(|name| (name, Vec::new()));
+        } else if let Some((_, lines)) = current.as_mut() {
+            let noise = line.starts_with("thread '") || line.starts_with("note: run with `RUST_BACKTRACE");
+            if line == "failures:" || line.starts_with("test result:") {
+                break;
+            } else if !noise && !line.trim().is_empty() {
+                lines.push(line);
+            }
+        }
+    }
+    if let Some((name, lines)) = current {
+        messages.insert(name, lines.join("\n"));
+    }
+
+    stdout
+        .lines()
+        .filter_map(|line| line.strip_prefix("test hidden_tests::"))
+        .filter_map(|rest| rest.split_once(" ... "))
+        .map(|(name, status)| TestResult {
+            name: name.to_string(),
+            passed: status.starts_with("ok"),
+            message: messages.get(name).cloned(),
+        })
+        .collect()
+}
+
+/// Writes a report of the outcome, returning whether every test passed
+pub fn report(exercise: &Exercise, outcome: &Outcome, out: &mut dyn Write) -> io::Result<bool> {
+    let results = match outcome {
+        Outcome::CompileError(errors) => {
+            writeln!(out, "✗ exercises/{}.rs doesn't compile yet:\n", exercise.id)?;
+            writeln!(out, "{}", errors.trim_end())?;
+            return Ok(false);
+        }
+        Outcome::Ran(results) => results,
+    };
+
+    let hints = exercise.hints();
+    for result in results {
+        if result.passed {
+            writeln!(out, "  ✓ {}", result.name)?;
+            continue;
+        }
+        writeln!(out, "  ✗ {}", result.name)?;
+        let message = result.message.as_deref().unwrap_or("");
+        for line in message.lines() {
+            writeln!(out, "      {}", line)?;
+        }
+        if message.contains("not yet implemented") {
+            writeln!(out, "      hint: Replace the todo!() with your implementation")?;
+        } else if let Some(hint) = hints.get(result.name.as_str()) {
+            writeln!(out, "      hint: {}", hint)?;
+        }
+    }
+
+    let passed = results.iter().filter(|result| result.passed).count();
+    writeln!(out)?;
+    if passed == results.len() && passed > 0 {
+        writeln!(out, "All {} tests passed! 🎉", passed)?;
+        writeln!(out, "Compare with the lesson's version: happyr run {}", exercise.lesson)?;
+        Ok(true)
+    } else {
+        writeln!(out, "{}/{} tests passed.", passed, results.len())?;
+        writeln!(out, "Stuck? The lesson covers it: happyr run {}", exercise.lesson)?;
+        Ok(false)
+    }
+}
+
+// ============================================================================
+// UNIT TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::{self, function_span};
+
+    /// The learner's copy of the stub with the exercise's function swapped
+    /// for the lesson's version, or for its signature and a `todo!()` body
+    /// when `solved` is false; either way, whatever the learner has written
+    /// in that function doesn't matter
+    fn stub(exercise: &Exercise, solved: bool) -> String {
+        let (section, name) = exercise.id.split_once('/').unwrap();
+        let module = source::module(section).unwrap();
+        let mut stub = fs::read_to_string(exercise.path()).unwrap();
+        let mut function = module[function_span(module, name).unwrap()].to_string();
+        if !solved {
+            function.truncate(function.find('{').unwrap());
+            function.push_str("{\n    todo!()\n}");
+        }
+        stub.replace_range(function_span(&stub, name).unwrap(), &function);
+        stub
+    }
+
+    #[test]
+    fn test_reference_solutions_pass() {
+        for exercise in EXERCISES {
+            let outcome = check(exercise, &stub(exercise, true)).unwrap();
+            let mut out = Vec::new();
+            let passed = report(exercise, &outcome, &mut out).unwrap();
+            assert!(passed, "{}:\n{}", exercise.id, String::from_utf8_lossy(&out));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "ownership")]
+    fn test_stub_fails_with_todo_hint() {
+        let exercise = find("ownership/first_word").unwrap();
+        let outcome = check(exercise, &stub(exercise, false)).unwrap();
+        let mut out = Vec::new();
+        assert!(!report(exercise, &outcome, &mut out).unwrap());
+        let report = String::from_utf8(out).unwrap();
+        assert!(report.contains("  ✗ two_words\n      not yet implemented\n      hint: Replace the todo!()"));
+        assert!(report.contains("0/5 tests passed."));
+    }
+
+    #[test]
+    fn test_parse_results() {
+        let stdout = "\
+running 2 tests
+test hidden_tests::first ... ok
+test hidden_tests::second ... FAILED
+
+failures:
+
+---- hidden_tests::second stdout ----
+
+thread 'hidden_tests::second' panicked at /tmp/x/exercise.rs:3:5:
+assertion `left == right` failed
+  left: 1
+ right: 2
+note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace
+
+
+failures:
+    hidden_tests::second
+
+test result: FAILED. 1 passed; 1 failed; 0 ignored; 0 measured; 0 filtered out
+";
+        let results = parse_results(stdout);
+        assert_eq!(results.len(), 2);
+        assert!(results[0].passed && results[0].message.is_none());
+        assert!(!results[1].passed);
+        assert_eq!(
+            results[1].message.as_deref(),
+            Some("assertion `left == right` failed\n  left: 1\n right: 2")
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "unsafe-lessons")]
+    fn test_hints_attach_to_the_next_test() {
+        let hints = find("advanced/split_at_mut").unwrap().hints();
+        assert!(hints["mid_past_the_end_panics"].contains("assert!(mid <= len)"));
+        assert!(!hints.contains_key("split_at_the_ends"));
+        for exercise in EXERCISES {
+            assert!(exercise.path().exists(), "missing stub for {}", exercise.id);
+        }
+    }
+}
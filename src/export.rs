@@ -0,0 +1,307 @@
+// ============================================================================
+// BOOK EXPORT
+// ============================================================================
+// `happyr export --html <DIR>` (or `--markdown <DIR>`) turns the registry
+// into a static book that can be read offline, without cargo:
+//
+//     index.html              table of contents, one entry per section
+//     ownership.html          the section's intro, then every lesson with
+//     ...                     its doc comment, source code and output
+//
+// Each section page links to the contents and to its neighbours. The HTML
+// carries its own stylesheet, so the directory can be copied anywhere.
+// ============================================================================
+
+use crate::lesson::{Lesson, Section};
+use crate::report;
+use crate::source;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Html,
+    Markdown,
+}
+
+impl Format {
+    fn extension(self) -> &'static str {
+        match self {
+            Format::Html => "html",
+            Format::Markdown => "md",
+        }
+    }
+}
+
+/// Everything shown for one lesson
+struct Page {
+    title: String,
+    id: String,
+    doc: String,
+    code: String,
+    output: String,
+}
+
+impl Page {
+    fn new(lesson: &dyn Lesson) -> Page {
+        let example = source::example(lesson);
+        Page {
+            title: lesson.title().to_string(),
+            id: lesson.id(),
+            doc: example.as_ref().map_or(String::new(), |example| example.doc.clone()),
+            code: example.map_or(String::new(), |example| example.code.to_string()),
+            output: report::capture(lesson).stdout,
+        }
+    }
+}
+
+/// Writes the book for `sections` into `dir`, returning how many files it
+/// wrote
+pub fn export(sections: &[&Section], format: Format, dir: &Path) -> io::Result<usize> {
+    fs::create_dir_all(dir)?;
+    let file_name = |section: &Section| format!("{}.{}", section.id, format.extension());
+
+    let contents = match format {
+        Format::Html => html_index(sections, file_name),
+        Format::Markdown => markdown_index(sections, file_name),
+    };
+    let index = match format {
+        Format::Html => "index.html",
+        Format::Markdown => "README.md",
+    };
+    fs::write(dir.join(index), contents)?;
+
+    for (i, section) in sections.iter().enumerate() {
+        let pages: Vec<Page> = section.lessons.iter().map(|&lesson| Page::new(lesson)).collect();
+        let previous = i.checked_sub(1).map(|i| sections[i]);
+        let next = sections.get(i + 1).copied();
+        let links = Links {
+            index,
+            previous: previous.map(|section| (section.title, file_name(section))),
+            next: next.map(|section| (section.title, file_name(section))),
+        };
+        let text = match format {
+            Format::Html => html_section(section, &pages, &links),
+            Format::Markdown => markdown_section(section, &pages, &links),
+        };
+        fs::write(dir.join(file_name(section)), text)?;
+    }
+    Ok(sections.len() + 1)
+}
+
+/// Where a section page's navigation points: titles and file names
+struct Links {
+    index: &'static str,
+    previous: Option<(&'static str, String)>,
+    next: Option<(&'static str, String)>,
+}
+
+/// A lesson's anchor within its section page
+fn anchor(id: &str) -> String {
+    id.replace("::", "-").replace('_', "-")
+}
+
+// ----------------------------------------------------------------------------
+// Markdown
+// ----------------------------------------------------------------------------
+
+fn markdown_index(sections: &[&Section], file_name: impl Fn(&Section) -> String) -> String {
+    let mut text = String::from("# 🦀 HappyR\n\nA comprehensive, hands-on Rust course.\n\n## Contents\n\n");
+    for section in sections {
+        text.push_str(&format!(
+            "{}. {} [{}]({})\n",
+            section.number,
+            section.icon,
+            section.title,
+            file_name(section)
+        ));
+        for lesson in section.lessons {
+            text.push_str(&format!(
+                "   - [{}]({}#{})\n",
+                lesson.title(),
+                file_name(section),
+                anchor(&lesson.id())
+            ));
+        }
+    }
+    text
+}
+
+fn markdown_section(section: &Section, pages: &[Page], links: &Links) -> String {
+    let mut text = format!("{}\n\n# {} {}\n\n", markdown_nav(links), section.icon, section.title);
+    if let Some(intro) = source::module_intro(section.id) {
+        text.push_str(&format!("{}\n\n", intro.trim()));
+    }
+    for page in pages {
+        text.push_str(&format!("<a id=\"{}\"></a>\n\n## {}\n\n", anchor(&page.id), page.title));
+        if !page.doc.is_empty() {
+            text.push_str(&format!("```text\n{}\n```\n\n", page.doc));
+        }
+        text.push_str(&format!("```rust\n{}\n```\n\n", page.code));
+        text.push_str(&format!("**Output** (`happyr run {}`)\n\n", page.id));
+        text.push_str(&format!("```text\n{}\n```\n\n", page.output.trim_matches('\n')));
+    }
+    text.push_str(&format!("{}\n", markdown_nav(links)));
+    text
+}
+
+fn markdown_nav(links: &Links) -> String {
+    let mut parts = Vec::new();
+    if let Some((title, file)) = &links.previous {
+        parts.push(format!("[← {}]({})", title, file));
+    }
+    parts.push(format!("[Contents]({})", links.index));
+    if let Some((title, file)) = &links.next {
+        parts.push(format!("[{} →]({})", title, file));
+    }
+    parts.join(" · ")
+}
+
+// ----------------------------------------------------------------------------
+// HTML
+// ----------------------------------------------------------------------------
+
+const STYLE: &str = "\
+body { font-family: system-ui, sans-serif; max-width: 52rem; margin: 2rem auto; padding: 0 1rem; line-height: 1.5; color: #222; }
+pre { background: #f6f8fa; padding: 0.8rem; overflow-x: auto; border-radius: 6px; }
+pre.output { background: #1e1e1e; color: #d4d4d4; }
+pre.doc { background: none; border-left: 4px solid #dea584; white-space: pre-wrap; }
+nav { display: flex; justify-content: space-between; margin: 1rem 0; }
+a { color: #b7410e; }";
+
+fn html_page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n{}\n</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        escape(title),
+        STYLE,
+        body
+    )
+}
+
+fn html_index(sections: &[&Section], file_name: impl Fn(&Section) -> String) -> String {
+    let mut body = String::from("<h1>🦀 HappyR</h1>\n<p>A comprehensive, hands-on Rust course.</p>\n<h2>Contents</h2>\n<ol>\n");
+    for section in sections {
+        body.push_str(&format!(
+            "<li>{} <a href=\"{}\">{}</a>\n<ul>\n",
+            section.icon,
+            file_name(section),
+            escape(section.title)
+        ));
+        for lesson in section.lessons {
+            body.push_str(&format!(
+                "<li><a href=\"{}#{}\">{}</a></li>\n",
+                file_name(section),
+                anchor(&lesson.id()),
+                escape(lesson.title())
+            ));
+        }
+        body.push_str("</ul></li>\n");
+    }
+    body.push_str("</ol>\n");
+    html_page("HappyR", &body)
+}
+
+fn html_section(section: &Section, pages: &[Page], links: &Links) -> String {
+    let mut body = html_nav(links);
+    body.push_str(&format!("<h1>{} {}</h1>\n", section.icon, escape(section.title)));
+    if let Some(intro) = source::module_intro(section.id) {
+        body.push_str(&format!("<pre class=\"doc\">{}</pre>\n", escape(intro.trim())));
+    }
+    for page in pages {
+        body.push_str(&format!("<h2 id=\"{}\">{}</h2>\n", anchor(&page.id), escape(&page.title)));
+        if !page.doc.is_empty() {
+            body.push_str(&format!("<pre class=\"doc\">{}</pre>\n", escape(&page.doc)));
+        }
+        body.push_str(&format!("<pre><code>{}</code></pre>\n", escape(&page.code)));
+        body.push_str(&format!("<p><strong>Output</strong> (<code>happyr run {}</code>)</p>\n", page.id));
+        body.push_str(&format!(
+            "<pre class=\"output\">{}</pre>\n",
+            escape(page.output.trim_matches('\n'))
+        ));
+    }
+    body.push_str(&html_nav(links));
+    html_page(section.title, &body)
+}
+
+fn html_nav(links: &Links) -> String {
+    let link = |link: &Option<(&str, String)>, arrow: &str| match link {
+        Some((title, file)) if arrow == "←" => format!("<a href=\"{}\">← {}</a>", file, escape(title)),
+        Some((title, file)) => format!("<a href=\"{}\">{} →</a>", file, escape(title)),
+        None => "<span></span>".to_string(),
+    };
+    format!(
+        "<nav>{}<a href=\"{}\">Contents</a>{}</nav>\n",
+        link(&links.previous, "←"),
+        links.index,
+        link(&links.next, "→")
+    )
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// ============================================================================
+// UNIT TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(all(feature = "ownership", feature = "collections"))]
+    fn export_to_temp(format: Format) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("happyr-export-{:?}-{}", format, std::process::id()));
+        let section = |id| crate::registry::section(id).unwrap();
+        let sections = [section("ownership"), section("collections")];
+        assert_eq!(export(&sections, format, &dir).unwrap(), 3);
+        dir
+    }
+
+    #[test]
+    #[cfg(all(feature = "ownership", feature = "collections"))]
+    fn test_markdown_book() {
+        let dir = export_to_temp(Format::Markdown);
+        let index = fs::read_to_string(dir.join("README.md")).unwrap();
+        assert!(index.contains("2. 🔐 [OWNERSHIP & BORROWING](ownership.md)\n"));
+        assert!(index.contains("   - [String Slices](ownership.md#ownership-string-slices)\n"));
+
+        let page = fs::read_to_string(dir.join("ownership.md")).unwrap();
+        assert!(page.starts_with("[Contents](README.md) · [COLLECTIONS"));
+        assert!(page.contains("This module covers Rust's most unique feature"));
+        assert!(page.contains("OWNERSHIP RULES:"));
+        assert!(page.contains("```rust\npub fn string_slices(out: &mut dyn Write)"));
+        assert!(page.contains("**Output** (`happyr run ownership::string_slices`)\n\n```text\n--- String Slices ---"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    #[cfg(all(feature = "ownership", feature = "collections"))]
+    fn test_html_book() {
+        let dir = export_to_temp(Format::Html);
+        let page = fs::read_to_string(dir.join("collections.html")).unwrap();
+        assert!(page.starts_with("<!DOCTYPE html>"));
+        assert!(page.contains("<a href=\"ownership.html\">← OWNERSHIP &amp; BORROWING</a>"));
+        assert!(page.contains("<h2 id=\"collections-hashmap-basics\">HashMap Basics</h2>"));
+        assert!(page.contains("HASH MAPS:"));
+        assert!(page.contains("HashMap&lt;K, V&gt;"));
+        assert!(dir.join("index.html").exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("<a href=\"x\">&</a>"), "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;");
+    }
+}
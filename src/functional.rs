@@ -6,36 +6,56 @@
 // - Iterators and iterator adaptors
 // - Functional patterns (map, filter, fold, etc.)
 // - Performance considerations
+//
+// `concurrency::parallel_iterators` runs the same kind of chains across
+// threads, with `par_iter()` in place of `iter()`.
 // ============================================================================
 
+// vec! and fold are used deliberately so each adaptor can be shown alone,
+// and an indexed loop is benchmarked against the iterator version.
+#![allow(
+    dead_code,
+    clippy::iter_count,
+    clippy::needless_range_loop,
+    clippy::unnecessary_fold,
+    clippy::useless_vec,
+)]
+
+use crate::bench::{black_box, Bench};
+use crate::lesson::{lesson, Difficulty, Section};
+use std::io::{self, Write};
+
 /// Demonstrates closure basics
 /// 
 /// CLOSURES:
 /// - Anonymous functions that can capture environment
 /// - Syntax: |params| expression or |params| { body }
 /// - Type inference for parameters and return type
-pub fn closure_basics() {
-    println!("\n--- Closure Basics ---");
+pub fn closure_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Closure Basics ---")?;
     
     // Simple closure
     let add_one = |x| x + 1;
-    println!("5 + 1 = {}", add_one(5));
+    writeln!(out, "5 + 1 = {}", add_one(5))?;
     
     // Closure with type annotations
     let add_two = |x: i32| -> i32 { x + 2 };
-    println!("5 + 2 = {}", add_two(5));
+    writeln!(out, "5 + 2 = {}", add_two(5))?;
     
     // Multi-line closure
-    let multiply = |x, y| {
+    let mut multiply = |x, y| -> io::Result<i32> {
         let result = x * y;
-        println!("Multiplying {} * {}", x, y);
-        result
+        writeln!(out, "Multiplying {} * {}", x, y)?;
+        Ok(result)
     };
-    println!("Result: {}", multiply(3, 4));
+    let product = multiply(3, 4)?;
+    writeln!(out, "Result: {}", product)?;
     
     // Closure with no parameters
-    let say_hello = || println!("Hello from closure!");
-    say_hello();
+    let mut say_hello = || writeln!(out, "Hello from closure!");
+    say_hello()?;
+    
+    Ok(())
 }
 
 /// Demonstrates closure capturing
@@ -43,34 +63,37 @@ pub fn closure_basics() {
 /// CAPTURING:
 /// - Closures can capture variables from environment
 /// - Three ways: by reference, by mutable reference, by value
-pub fn closure_capturing() {
-    println!("\n--- Closure Capturing ---");
+pub fn closure_capturing(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Closure Capturing ---")?;
     
     // Capture by immutable reference
+    // (`out` is passed in rather than captured, so only x is borrowed)
     let x = 10;
-    let print_x = || println!("x = {}", x);
-    print_x();
-    println!("x is still valid: {}", x);
+    let print_x = |out: &mut dyn Write| writeln!(out, "x = {}", x);
+    print_x(out)?;
+    writeln!(out, "x is still valid: {}", x)?;
     
     // Capture by mutable reference
     let mut count = 0;
     let mut increment = || {
         count += 1;
-        println!("Count: {}", count);
+        writeln!(out, "Count: {}", count)
     };
-    increment();
-    increment();
-    increment();
-    println!("Final count: {}", count);
+    increment()?;
+    increment()?;
+    increment()?;
+    writeln!(out, "Final count: {}", count)?;
     
     // Capture by value (move)
     let s = String::from("hello");
-    let consume = move || {
-        println!("Consumed: {}", s);
+    let consume = move |out: &mut dyn Write| {
+        writeln!(out, "Consumed: {}", s)
         // s is moved into closure
     };
-    consume();
+    consume(out)?;
     // println!("{}", s); // ❌ Error! s was moved
+    
+    Ok(())
 }
 
 /// Demonstrates closure traits: Fn, FnMut, FnOnce
@@ -79,16 +102,17 @@ pub fn closure_capturing() {
 /// - FnOnce: Takes ownership, can be called once
 /// - FnMut: Mutable borrow, can be called multiple times
 /// - Fn: Immutable borrow, can be called multiple times
-pub fn closure_traits() {
-    println!("\n--- Closure Traits ---");
+pub fn closure_traits(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Closure Traits ---")?;
     
     // FnOnce: consumes captured variables
     let s = String::from("hello");
-    let consume_string = move || {
-        println!("Consuming: {}", s);
+    let consume_string = move |out: &mut dyn Write| {
+        writeln!(out, "Consuming: {}", s)?;
         drop(s); // Takes ownership
+        Ok(())
     };
-    call_once(consume_string);
+    call_once(out, consume_string)?;
     // consume_string(); // ❌ Error! Already called
     
     // FnMut: mutates captured variables
@@ -96,25 +120,29 @@ pub fn closure_traits() {
     let mut increment = || count += 1;
     call_mut(&mut increment);
     call_mut(&mut increment);
-    println!("Count after FnMut: {}", count);
+    writeln!(out, "Count after FnMut: {}", count)?;
     
     // Fn: only reads captured variables
     let x = 10;
-    let read_x = || println!("Reading x: {}", x);
-    call_fn(&read_x);
-    call_fn(&read_x);
+    let read_x = |out: &mut dyn Write| writeln!(out, "Reading x: {}", x);
+    call_fn(out, &read_x)?;
+    call_fn(out, &read_x)?;
+    
+    Ok(())
 }
 
-fn call_once<F: FnOnce()>(f: F) {
-    f();
+// The closures that print take the writer as an argument: capturing it
+// would need a mutable borrow and turn every one of them into FnMut
+fn call_once<F: FnOnce(&mut dyn Write) -> io::Result<()>>(out: &mut dyn Write, f: F) -> io::Result<()> {
+    f(out)
 }
 
 fn call_mut<F: FnMut()>(f: &mut F) {
     f();
 }
 
-fn call_fn<F: Fn()>(f: &F) {
-    f();
+fn call_fn<F: Fn(&mut dyn Write) -> io::Result<()>>(out: &mut dyn Write, f: &F) -> io::Result<()> {
+    f(out)
 }
 
 /// Demonstrates iterator basics
@@ -123,16 +151,16 @@ fn call_fn<F: Fn()>(f: &F) {
 /// - Lazy: don't do work until consumed
 /// - Implement Iterator trait
 /// - next() method returns Option<Item>
-pub fn iterator_basics() {
-    println!("\n--- Iterator Basics ---");
+pub fn iterator_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Iterator Basics ---")?;
     
     let v = vec![1, 2, 3, 4, 5];
     
     // Creating iterators
     let iter = v.iter(); // Immutable references
-    println!("Iterating with iter():");
+    writeln!(out, "Iterating with iter():")?;
     for val in iter {
-        println!("  {}", val);
+        writeln!(out, "  {}", val)?;
     }
     
     // iter_mut for mutable references
@@ -140,14 +168,16 @@ pub fn iterator_basics() {
     for val in v2.iter_mut() {
         *val *= 2;
     }
-    println!("After iter_mut: {:?}", v2);
+    writeln!(out, "After iter_mut: {:?}", v2)?;
     
     // into_iter takes ownership
     let v3 = vec![1, 2, 3];
     for val in v3.into_iter() {
-        println!("  Owned: {}", val);
+        writeln!(out, "  Owned: {}", val)?;
     }
     // println!("{:?}", v3); // ❌ Error! v3 was moved
+    
+    Ok(())
 }
 
 /// Demonstrates iterator adaptors
@@ -156,44 +186,46 @@ pub fn iterator_basics() {
 /// - Transform iterators into different iterators
 /// - Lazy: don't do work until consumed
 /// - Common: map, filter, take, skip, zip, etc.
-pub fn iterator_adaptors() {
-    println!("\n--- Iterator Adaptors ---");
+pub fn iterator_adaptors(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Iterator Adaptors ---")?;
     
     let v = vec![1, 2, 3, 4, 5];
     
     // map: transform each element
     let doubled: Vec<i32> = v.iter().map(|x| x * 2).collect();
-    println!("Doubled: {:?}", doubled);
+    writeln!(out, "Doubled: {:?}", doubled)?;
     
     // filter: keep elements matching predicate
     let evens: Vec<i32> = v.iter().filter(|x| *x % 2 == 0).cloned().collect();
-    println!("Evens: {:?}", evens);
+    writeln!(out, "Evens: {:?}", evens)?;
     
     // Chaining adaptors
     let result: Vec<i32> = v.iter()
         .filter(|x| *x % 2 == 0)
         .map(|x| x * 2)
         .collect();
-    println!("Filtered and doubled: {:?}", result);
+    writeln!(out, "Filtered and doubled: {:?}", result)?;
     
     // take: first n elements
     let first_three: Vec<i32> = v.iter().take(3).cloned().collect();
-    println!("First three: {:?}", first_three);
+    writeln!(out, "First three: {:?}", first_three)?;
     
     // skip: skip first n elements
     let skip_two: Vec<i32> = v.iter().skip(2).cloned().collect();
-    println!("Skip two: {:?}", skip_two);
+    writeln!(out, "Skip two: {:?}", skip_two)?;
     
     // enumerate: add index
     for (i, val) in v.iter().enumerate() {
-        println!("  Index {}: {}", i, val);
+        writeln!(out, "  Index {}: {}", i, val)?;
     }
     
     // zip: combine two iterators
     let names = vec!["Alice", "Bob", "Charlie"];
     let ages = vec![25, 30, 35];
     let combined: Vec<_> = names.iter().zip(ages.iter()).collect();
-    println!("Zipped: {:?}", combined);
+    writeln!(out, "Zipped: {:?}", combined)?;
+    
+    Ok(())
 }
 
 /// Demonstrates consuming adaptors
@@ -201,39 +233,41 @@ pub fn iterator_adaptors() {
 /// CONSUMERS:
 /// - Consume iterator and produce final value
 /// - Common: collect, sum, count, fold, any, all, find
-pub fn consuming_adaptors() {
-    println!("\n--- Consuming Adaptors ---");
+pub fn consuming_adaptors(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Consuming Adaptors ---")?;
     
     let v = vec![1, 2, 3, 4, 5];
     
     // sum: add all elements
     let total: i32 = v.iter().sum();
-    println!("Sum: {}", total);
+    writeln!(out, "Sum: {}", total)?;
     
     // count: number of elements
     let count = v.iter().count();
-    println!("Count: {}", count);
+    writeln!(out, "Count: {}", count)?;
     
     // any: check if any element matches
     let has_even = v.iter().any(|x| x % 2 == 0);
-    println!("Has even: {}", has_even);
+    writeln!(out, "Has even: {}", has_even)?;
     
     // all: check if all elements match
     let all_positive = v.iter().all(|x| *x > 0);
-    println!("All positive: {}", all_positive);
+    writeln!(out, "All positive: {}", all_positive)?;
     
     // find: first element matching predicate
     let first_even = v.iter().find(|x| *x % 2 == 0);
-    println!("First even: {:?}", first_even);
+    writeln!(out, "First even: {:?}", first_even)?;
     
     // max and min
     let max = v.iter().max();
     let min = v.iter().min();
-    println!("Max: {:?}, Min: {:?}", max, min);
+    writeln!(out, "Max: {:?}, Min: {:?}", max, min)?;
     
     // collect: build collection
     let doubled: Vec<i32> = v.iter().map(|x| x * 2).collect();
-    println!("Collected: {:?}", doubled);
+    writeln!(out, "Collected: {:?}", doubled)?;
+    
+    Ok(())
 }
 
 /// Demonstrates fold and reduce
@@ -242,22 +276,22 @@ pub fn consuming_adaptors() {
 /// - Accumulate values into single result
 /// - fold: with initial value
 /// - reduce: without initial value (returns Option)
-pub fn fold_and_reduce() {
-    println!("\n--- Fold and Reduce ---");
+pub fn fold_and_reduce(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Fold and Reduce ---")?;
     
     let v = vec![1, 2, 3, 4, 5];
     
     // fold: accumulate with initial value
     let sum = v.iter().fold(0, |acc, x| acc + x);
-    println!("Sum with fold: {}", sum);
+    writeln!(out, "Sum with fold: {}", sum)?;
     
     // fold for product
     let product = v.iter().fold(1, |acc, x| acc * x);
-    println!("Product: {}", product);
+    writeln!(out, "Product: {}", product)?;
     
     // reduce: like fold but no initial value
     let sum2 = v.iter().copied().reduce(|acc, x| acc + x);
-    println!("Sum with reduce: {:?}", sum2);
+    writeln!(out, "Sum with reduce: {:?}", sum2)?;
     
     // Building a string
     let words = vec!["Hello", "from", "Rust"];
@@ -268,22 +302,24 @@ pub fn fold_and_reduce() {
         acc.push_str(word);
         acc
     });
-    println!("Sentence: {}", sentence);
+    writeln!(out, "Sentence: {}", sentence)?;
+    
+    Ok(())
 }
 
 /// Demonstrates custom iterators
-pub fn custom_iterators() {
-    println!("\n--- Custom Iterators ---");
+pub fn custom_iterators(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Custom Iterators ---")?;
     
     let counter = Counter::new();
     
     for num in counter.take(5) {
-        println!("Counter: {}", num);
+        writeln!(out, "Counter: {}", num)?;
     }
     
     // Using iterator methods
     let sum: u32 = Counter::new().take(10).sum();
-    println!("Sum of first 10: {}", sum);
+    writeln!(out, "Sum of first 10: {}", sum)?;
     
     // Chaining operations
     let result: Vec<u32> = Counter::new()
@@ -291,7 +327,9 @@ pub fn custom_iterators() {
         .filter(|x| x % 2 == 0)
         .map(|x| x * x)
         .collect();
-    println!("Even squares: {:?}", result);
+    writeln!(out, "Even squares: {:?}", result)?;
+    
+    Ok(())
 }
 
 struct Counter {
@@ -319,8 +357,8 @@ impl Iterator for Counter {
 }
 
 /// Demonstrates functional patterns
-pub fn functional_patterns() {
-    println!("\n--- Functional Patterns ---");
+pub fn functional_patterns(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Functional Patterns ---")?;
     
     let numbers = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
     
@@ -329,12 +367,12 @@ pub fn functional_patterns() {
         .filter(|x| *x % 2 == 0)
         .map(|x| x * x)
         .collect();
-    println!("Even squares: {:?}", result);
+    writeln!(out, "Even squares: {:?}", result)?;
     
     // Pattern: partition (split into two collections)
     let (evens, odds): (Vec<i32>, Vec<i32>) = numbers.iter()
         .partition(|x| *x % 2 == 0);
-    println!("Evens: {:?}, Odds: {:?}", evens, odds);
+    writeln!(out, "Evens: {:?}, Odds: {:?}", evens, odds)?;
     
     // Pattern: flat_map (flatten nested iterators)
     let nested = vec![vec![1, 2], vec![3, 4], vec![5, 6]];
@@ -342,7 +380,7 @@ pub fn functional_patterns() {
         .flat_map(|v| v.iter())
         .cloned()
         .collect();
-    println!("Flattened: {:?}", flattened);
+    writeln!(out, "Flattened: {:?}", flattened)?;
     
     // Pattern: scan (stateful map)
     let running_sum: Vec<i32> = numbers.iter()
@@ -351,20 +389,28 @@ pub fn functional_patterns() {
             Some(*state)
         })
         .collect();
-    println!("Running sum: {:?}", running_sum);
+    writeln!(out, "Running sum: {:?}", running_sum)?;
+    
+    Ok(())
 }
 
 /// Demonstrates iterator performance
-pub fn iterator_performance() {
-    println!("\n--- Iterator Performance ---");
+/// 
+/// ZERO-COST ABSTRACTIONS:
+/// - Iterator chains compile to the same machine code as a hand-written loop
+/// - Indexing with v[i] checks bounds on every access; iterators don't need to
+/// - Measure instead of guessing: the table times each version with
+///   crate::bench (run with --release, debug builds don't optimize)
+pub fn iterator_performance(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Iterator Performance ---")?;
     
-    println!("Iterators are zero-cost abstractions!");
-    println!("They compile to the same code as hand-written loops.");
+    writeln!(out, "Iterators are zero-cost abstractions!")?;
+    writeln!(out, "They compile to the same code as hand-written loops.")?;
     
-    let v: Vec<i32> = (1..=1000).collect();
+    let v: Vec<u64> = (1..=10_000).collect();
     
     // Iterator style (zero-cost!)
-    let sum1: i32 = v.iter().sum();
+    let sum1: u64 = v.iter().sum();
     
     // Loop style (same performance)
     let mut sum2 = 0;
@@ -372,14 +418,41 @@ pub fn iterator_performance() {
         sum2 += i;
     }
     
-    println!("Iterator sum: {}", sum1);
-    println!("Loop sum: {}", sum2);
-    println!("Both have identical performance!");
+    writeln!(out, "Iterator sum: {}", sum1)?;
+    writeln!(out, "Loop sum: {}", sum2)?;
+    
+    // black_box hides the input from the optimizer, so it can't compute
+    // the sums at compile time
+    let mut bench = Bench::new();
+    bench.run("iterator sum", || black_box(&v).iter().sum::<u64>());
+    bench.run("for loop sum", || {
+        let mut sum = 0;
+        for x in black_box(&v) {
+            sum += x;
+        }
+        sum
+    });
+    bench.run("indexed loop sum", || {
+        let v = black_box(&v);
+        let mut sum = 0;
+        for i in 0..v.len() {
+            sum += v[i];
+        }
+        sum
+    });
+    bench.run("filter + map + sum", || {
+        black_box(&v).iter().filter(|&&x| x % 2 == 0).map(|x| x * x).sum::<u64>()
+    });
+    writeln!(out, "\nSumming {} numbers:", v.len())?;
+    bench.write_table(out)?;
+    writeln!(out, "\nWith --release the first three are within noise of each other.")?;
+    
+    Ok(())
 }
 
 /// Demonstrates practical examples
-pub fn practical_examples() {
-    println!("\n--- Practical Examples ---");
+pub fn practical_examples(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Practical Examples ---")?;
     
     // Example 1: Process a list of users
     #[derive(Debug)]
@@ -400,7 +473,7 @@ pub fn practical_examples() {
         .map(|u| u.name.clone())
         .collect();
     
-    println!("Active users: {:?}", active_names);
+    writeln!(out, "Active users: {:?}", active_names)?;
     
     // Example 2: Word frequency counter
     let text = "hello world hello rust world rust rust";
@@ -411,7 +484,7 @@ pub fn practical_examples() {
             *word_count.entry(word).or_insert(0) += 1;
         });
     
-    println!("Word count: {:?}", word_count);
+    writeln!(out, "Word count: {:?}", word_count)?;
     
     // Example 3: Pipeline processing
     let result: i32 = (1..=10)
@@ -419,26 +492,57 @@ pub fn practical_examples() {
         .map(|x| x * x)
         .sum();
     
-    println!("Sum of even squares (1-10): {}", result);
+    writeln!(out, "Sum of even squares (1-10): {}", result)?;
+    
+    Ok(())
 }
 
 // ============================================================================
 // PUBLIC INTERFACE
 // ============================================================================
 
-pub fn run_all_examples() {
-    closure_basics();
-    closure_capturing();
-    closure_traits();
-    iterator_basics();
-    iterator_adaptors();
-    consuming_adaptors();
-    fold_and_reduce();
-    custom_iterators();
-    functional_patterns();
-    iterator_performance();
-    practical_examples();
-}
+/// This module's lessons, in teaching order
+pub static SECTION: Section = Section {
+    id: "functional",
+    number: 7,
+    title: "FUNCTIONAL PROGRAMMING",
+    icon: "🔄",
+    lessons: &[
+        &lesson!(closure_basics, "Closure Basics")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["closures"]),
+        &lesson!(closure_capturing, "Closure Capturing")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["closures", "ownership"]),
+        &lesson!(closure_traits, "Closure Traits")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["closures", "traits"]),
+        &lesson!(iterator_basics, "Iterator Basics")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["iterators"]),
+        &lesson!(iterator_adaptors, "Iterator Adaptors")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["iterators", "closures"]),
+        &lesson!(consuming_adaptors, "Consuming Adaptors")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["iterators"]),
+        &lesson!(fold_and_reduce, "Fold and Reduce")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["iterators"]),
+        &lesson!(custom_iterators, "Custom Iterators")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["iterators", "traits"]),
+        &lesson!(functional_patterns, "Functional Patterns")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["iterators", "closures"]),
+        &lesson!(iterator_performance, "Iterator Performance")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["iterators", "performance"]),
+        &lesson!(practical_examples, "Practical Examples")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["iterators", "closures", "std::collections"]),
+    ],
+};
 
 // ============================================================================
 // UNIT TESTS
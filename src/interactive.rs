@@ -0,0 +1,296 @@
+// ============================================================================
+// INTERACTIVE MODE
+// ============================================================================
+// `happyr interactive` lets a learner browse the sections and run one lesson
+// at a time instead of watching every section scroll past. It only reads
+// lines from stdin, so it works in any terminal.
+//
+//   sections menu  --number-->  section menu  --number/Enter-->  lesson
+//        ^                           |  b                          |
+//        +---------------------------+-----------------------------+ m
+//
+// After each lesson the learner picks next, previous, repeat or menu. Next
+// and previous continue into the neighbouring sections.
+// ============================================================================
+
+use crate::lesson::{Lesson, Section};
+use crate::progress::{Progress, Status};
+use std::io::{self, BufRead, Write};
+
+/// Where the learner currently is
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Screen {
+    Sections,
+    /// Index into the sections
+    Section(usize),
+    /// Index into every lesson, across sections
+    Lesson(usize),
+    Quit,
+}
+
+/// Runs the menu until the learner quits or `input` ends, recording each
+/// lesson shown in `progress`
+pub fn run(
+    sections: &[&Section],
+    input: &mut dyn BufRead,
+    out: &mut dyn Write,
+    progress: &mut Progress,
+) -> io::Result<()> {
+    // Flattened so next and previous can cross section boundaries
+    let lessons: Vec<(&Section, &dyn Lesson)> = sections
+        .iter()
+        .flat_map(|&section| section.lessons.iter().map(move |&lesson| (section, lesson)))
+        .collect();
+
+    let mut screen = Screen::Sections;
+    while screen != Screen::Quit {
+        screen = match screen {
+            Screen::Sections => sections_menu(sections, input, out)?,
+            Screen::Section(index) => section_menu(sections, index, &lessons, progress, input, out)?,
+            Screen::Lesson(index) => lesson_screen(&lessons, index, progress, input, out)?,
+            Screen::Quit => Screen::Quit,
+        };
+    }
+
+    writeln!(out, "Happy coding! 🦀")
+}
+
+fn sections_menu(sections: &[&Section], input: &mut dyn BufRead, out: &mut dyn Write) -> io::Result<Screen> {
+    writeln!(out, "\n{}", "=".repeat(70))?;
+    writeln!(out, "Pick a section:")?;
+    for (i, section) in sections.iter().enumerate() {
+        writeln!(out, "  {:>2}. {}  ({} lessons)", i + 1, section.title, section.lessons.len())?;
+    }
+
+    loop {
+        let choice = match prompt(input, out, "Section number, or q to quit")? {
+            None => return Ok(Screen::Quit),
+            Some(choice) => choice.to_lowercase(),
+        };
+        match choice.as_str() {
+            "q" | "quit" => return Ok(Screen::Quit),
+            _ => match pick(&choice, sections.len()) {
+                Some(index) => return Ok(Screen::Section(index)),
+                None => writeln!(out, "No section '{}'", choice)?,
+            },
+        }
+    }
+}
+
+fn section_menu(
+    sections: &[&Section],
+    index: usize,
+    lessons: &[(&Section, &dyn Lesson)],
+    progress: &Progress,
+    input: &mut dyn BufRead,
+    out: &mut dyn Write,
+) -> io::Result<Screen> {
+    let section = sections[index];
+    writeln!(out, "\n{}", section.banner())?;
+    writeln!(out, "{}", "=".repeat(70))?;
+    for (i, lesson) in section.lessons.iter().enumerate() {
+        let mark = if progress.is_done(&lesson.id()) { "✓" } else { " " };
+        writeln!(out, "  {} {:>2}. {}", mark, i + 1, lesson.title())?;
+    }
+
+    // Where this section's lessons start in the flattened list
+    let first = lessons
+        .iter()
+        .position(|(owner, _)| std::ptr::eq(*owner, section))
+        .unwrap_or(0);
+
+    loop {
+        let choice = match prompt(input, out, "Lesson number, Enter to start, b to go back, q to quit")? {
+            None => return Ok(Screen::Quit),
+            Some(choice) => choice.to_lowercase(),
+        };
+        match choice.as_str() {
+            "" if !section.lessons.is_empty() => return Ok(Screen::Lesson(first)),
+            "b" | "back" | "m" | "menu" => return Ok(Screen::Sections),
+            "q" | "quit" => return Ok(Screen::Quit),
+            _ => match pick(&choice, section.lessons.len()) {
+                Some(i) => return Ok(Screen::Lesson(first + i)),
+                None => writeln!(out, "No lesson '{}'", choice)?,
+            },
+        }
+    }
+}
+
+fn lesson_screen(
+    lessons: &[(&Section, &dyn Lesson)],
+    index: usize,
+    progress: &mut Progress,
+    input: &mut dyn BufRead,
+    out: &mut dyn Write,
+) -> io::Result<Screen> {
+    let (section, lesson) = lessons[index];
+    let position = section
+        .lessons
+        .iter()
+        .position(|&other| other.id() == lesson.id())
+        .unwrap_or(0);
+
+    writeln!(out, "\n{}", "-".repeat(70))?;
+    writeln!(
+        out,
+        "{} — lesson {} of {}: {}",
+        section.title,
+        position + 1,
+        section.lessons.len(),
+        lesson.title()
+    )?;
+    record(progress, lesson, Status::Started, out)?;
+    lesson.run(out)?;
+    record(progress, lesson, Status::Done, out)?;
+
+    loop {
+        let choice = match prompt(input, out, "[n]ext (Enter), [p]revious, [r]epeat, [m]enu, [q]uit")? {
+            None => return Ok(Screen::Quit),
+            Some(choice) => choice.to_lowercase(),
+        };
+        match choice.as_str() {
+            "" | "n" | "next" => {
+                if index + 1 < lessons.len() {
+                    return Ok(Screen::Lesson(index + 1));
+                }
+                writeln!(out, "That was the last lesson, well done!")?;
+                return Ok(Screen::Sections);
+            }
+            "p" | "previous" | "prev" => {
+                if index > 0 {
+                    return Ok(Screen::Lesson(index - 1));
+                }
+                writeln!(out, "This is the first lesson.")?;
+            }
+            "r" | "repeat" => return Ok(Screen::Lesson(index)),
+            "m" | "menu" | "b" | "back" => return Ok(Screen::Sections),
+            "q" | "quit" => return Ok(Screen::Quit),
+            other => writeln!(out, "Unknown choice '{}'", other)?,
+        }
+    }
+}
+
+/// Records a lesson's status, warning rather than failing if it can't be
+/// saved
+fn record(progress: &mut Progress, lesson: &dyn Lesson, status: Status, out: &mut dyn Write) -> io::Result<()> {
+    match progress.record(&lesson.id(), status) {
+        Ok(()) => Ok(()),
+        Err(error) => writeln!(out, "(progress won't be saved: {})", error),
+    }
+}
+
+/// Shows `message` and reads one trimmed line, or `None` once the input
+/// has ended
+pub fn prompt(input: &mut dyn BufRead, out: &mut dyn Write, message: &str) -> io::Result<Option<String>> {
+    write!(out, "{} > ", message)?;
+    out.flush()?;
+
+    let mut line = String::new();
+    if input.read_line(&mut line)? == 0 {
+        writeln!(out)?;
+        return Ok(None);
+    }
+    Ok(Some(line.trim().to_string()))
+}
+
+/// Turns a 1-based menu choice into an index below `len`
+fn pick(choice: &str, len: usize) -> Option<usize> {
+    match choice.parse::<usize>() {
+        Ok(number) if (1..=len).contains(&number) => Some(number - 1),
+        _ => None,
+    }
+}
+
+// ============================================================================
+// UNIT TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lesson::lesson;
+
+    fn first(out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "<first>")
+    }
+
+    fn second(out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "<second>")
+    }
+
+    fn third(out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "<third>")
+    }
+
+    static ONE: Section = Section {
+        id: "one",
+        number: 1,
+        title: "ONE",
+        icon: "1️⃣",
+        lessons: &[&lesson!(first, "First"), &lesson!(second, "Second")],
+    };
+
+    static TWO: Section = Section {
+        id: "two",
+        number: 2,
+        title: "TWO",
+        icon: "2️⃣",
+        lessons: &[&lesson!(third, "Third")],
+    };
+
+    /// Runs a session with the given input lines, returning only the lesson
+    /// output markers in the order they were shown
+    fn session(input: &str) -> Vec<String> {
+        let mut out = Vec::new();
+        run(&[&ONE, &TWO], &mut input.as_bytes(), &mut out, &mut Progress::default()).unwrap();
+        String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .filter(|line| line.starts_with('<'))
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn test_next_crosses_sections() {
+        assert_eq!(session("1\n\n\nn\nq\n"), ["<first>", "<second>", "<third>"]);
+    }
+
+    #[test]
+    fn test_previous_repeat_and_menu() {
+        assert_eq!(
+            session("1\n2\np\nr\nm\n2\n1\n"),
+            ["<second>", "<first>", "<first>", "<third>"]
+        );
+    }
+
+    #[test]
+    fn test_invalid_choices_reprompt() {
+        let mut out = Vec::new();
+        let mut progress = Progress::default();
+        run(&[&ONE, &TWO], &mut "7\nx\n1\nb\nq\n".as_bytes(), &mut out, &mut progress).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("No section '7'"));
+        assert!(out.contains("No section 'x'"));
+        assert!(out.contains("SECTION 1: ONE"));
+        assert!(out.ends_with("Happy coding! 🦀\n"));
+    }
+
+    #[test]
+    fn test_lessons_shown_are_recorded() {
+        let mut progress = Progress::default();
+        let mut out = Vec::new();
+        run(&[&ONE, &TWO], &mut "2\n1\nq\n".as_bytes(), &mut out, &mut progress).unwrap();
+        assert!(progress.is_done("tests::third"));
+        assert_eq!(progress.status("tests::first"), None);
+    }
+
+    #[test]
+    fn test_pick() {
+        assert_eq!(pick("1", 3), Some(0));
+        assert_eq!(pick("3", 3), Some(2));
+        assert_eq!(pick("0", 3), None);
+        assert_eq!(pick("4", 3), None);
+        assert_eq!(pick("two", 3), None);
+    }
+}
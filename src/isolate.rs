@@ -0,0 +1,240 @@
+// ============================================================================
+// ISOLATED LESSON RUNS
+// ============================================================================
+// `happyr run --isolate ...` runs every lesson in a child process of the same
+// binary (`happyr __lesson <ID>`), so a lesson that panics, hangs or aborts
+// only takes itself down. The parent captures the child's stdout and stderr,
+// kills it if it outlives the timeout, and collects a `Report` per lesson for
+// the summary table printed at the end of the run.
+//
+// Uncommenting the panics in `error_handling::panic_basics`, or a second
+// `borrow_mut()` in `smart_pointers`, is a good way to see this in action.
+// ============================================================================
+
+use crate::sched;
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How long a lesson may run when no `--timeout` is given
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How an isolated lesson ended
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    Passed,
+    /// Panicked, with the panic message
+    Panicked(String),
+    /// Exited unsuccessfully without a panic; `None` when killed by a signal
+    Failed(Option<i32>),
+    TimedOut,
+}
+
+impl Outcome {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Outcome::Passed => "ok",
+            Outcome::Panicked(_) => "panicked",
+            Outcome::Failed(_) => "failed",
+            Outcome::TimedOut => "timed out",
+        }
+    }
+}
+
+/// Everything a lesson's child process left behind
+#[derive(Debug)]
+pub struct Report {
+    pub id: String,
+    pub outcome: Outcome,
+    pub stdout: String,
+    pub stderr: String,
+    pub elapsed: Duration,
+}
+
+/// Runs one lesson in a child process of this binary
+pub fn run_lesson(id: &str, timeout: Duration) -> io::Result<Report> {
+    run(lesson_command(&std::env::current_exe()?, id), id, timeout)
+}
+
+/// The command running lesson `id` in `exe`, scheduled like this process
+pub fn lesson_command(exe: &Path, id: &str) -> Command {
+    let mut command = Command::new(exe);
+    if let sched::Mode::Seeded(seed) = sched::mode() {
+        command.args(["--seed", &seed.to_string()]);
+    }
+    command.args(["__lesson", id]);
+    command
+}
+
+/// Runs `command`, capturing its output and killing it after `timeout`
+pub fn run(mut command: Command, id: &str, timeout: Duration) -> io::Result<Report> {
+    let start = Instant::now();
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // Read both pipes on their own threads, so a chatty child can't fill one
+    // while we wait on the other
+    let stdout = read_all(child.stdout.take());
+    let stderr = read_all(child.stderr.take());
+
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break Some(status);
+        }
+        if start.elapsed() >= timeout {
+            child.kill()?;
+            child.wait()?;
+            break None;
+        }
+        thread::sleep(Duration::from_millis(5));
+    };
+    let elapsed = start.elapsed();
+
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+    let outcome = match status {
+        None => Outcome::TimedOut,
+        Some(status) if status.success() => Outcome::Passed,
+        Some(status) => match panic_message(&stderr) {
+            Some(message) => Outcome::Panicked(message),
+            None => Outcome::Failed(status.code()),
+        },
+    };
+
+    Ok(Report {
+        id: id.to_string(),
+        outcome,
+        stdout,
+        stderr,
+        elapsed,
+    })
+}
+
+fn read_all(pipe: Option<impl Read + Send + 'static>) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut bytes = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut bytes);
+        }
+        String::from_utf8_lossy(&bytes).into_owned()
+    })
+}
+
+/// Finds the message of the last panic in `stderr`, which the default hook
+/// prints as `thread '...' panicked at file:line:col:` and then the message
+/// (newer toolchains add the thread id after the name)
+fn panic_message(stderr: &str) -> Option<String> {
+    let lines: Vec<&str> = stderr.lines().collect();
+    let start = lines
+        .iter()
+        .rposition(|line| line.starts_with("thread '") && line.contains(" panicked at "))?;
+    let message: Vec<&str> = lines[start + 1..]
+        .iter()
+        .take_while(|line| !line.starts_with("note: ") && !line.starts_with("stack backtrace:"))
+        .copied()
+        .collect();
+    Some(message.join("\n").trim().to_string())
+}
+
+/// Writes one line per lesson, then the totals
+pub fn write_summary(reports: &[Report], out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "{:<45} {:<10} {:>8}", "Lesson", "Result", "Time")?;
+    writeln!(out, "{}", "-".repeat(70))?;
+    for report in reports {
+        let time = format!("{}ms", report.elapsed.as_millis());
+        write!(out, "{:<45} {:<10} {:>8}", report.id, report.outcome.label(), time)?;
+        match &report.outcome {
+            Outcome::Panicked(message) => writeln!(out, "  {}", message.lines().next().unwrap_or(""))?,
+            Outcome::Failed(Some(code)) => writeln!(out, "  exit status {}", code)?,
+            Outcome::Failed(None) => writeln!(out, "  killed by a signal")?,
+            Outcome::Passed | Outcome::TimedOut => writeln!(out)?,
+        }
+    }
+    writeln!(out, "{}", "-".repeat(70))?;
+
+    let count = |label| reports.iter().filter(|report| report.outcome.label() == label).count();
+    writeln!(
+        out,
+        "{} ok, {} panicked, {} timed out, {} failed",
+        count("ok"),
+        count("panicked"),
+        count("timed out"),
+        count("failed")
+    )
+}
+
+// ============================================================================
+// UNIT TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    fn shell(script: &str) -> Command {
+        let mut command = Command::new("sh");
+        command.args(["-c", script]);
+        command
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_captures_output_and_status() {
+        let report = run(shell("echo out; echo err >&2"), "a::b", DEFAULT_TIMEOUT).unwrap();
+        assert_eq!(report.outcome, Outcome::Passed);
+        assert_eq!(report.stdout, "out\n");
+        assert_eq!(report.stderr, "err\n");
+
+        let report = run(shell("exit 3"), "a::b", DEFAULT_TIMEOUT).unwrap();
+        assert_eq!(report.outcome, Outcome::Failed(Some(3)));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_kills_lessons_that_time_out() {
+        let report = run(shell("echo started; exec sleep 5"), "a::b", Duration::from_millis(200)).unwrap();
+        assert_eq!(report.outcome, Outcome::TimedOut);
+        assert!(report.elapsed < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_panic_message() {
+        let stderr = "\
+thread 'main' panicked at src/error_handling.rs:38:5:
+crash and burn
+note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace
+";
+        assert_eq!(panic_message(stderr).as_deref(), Some("crash and burn"));
+        let stderr = "thread 'main' (4242) panicked at src/main.rs:1:1:\nalready borrowed: BorrowMutError\n";
+        assert_eq!(panic_message(stderr).as_deref(), Some("already borrowed: BorrowMutError"));
+        assert_eq!(panic_message("just a warning\n"), None);
+    }
+
+    #[test]
+    fn test_summary_table() {
+        let report = |id: &str, outcome| Report {
+            id: id.to_string(),
+            outcome,
+            stdout: String::new(),
+            stderr: String::new(),
+            elapsed: Duration::from_millis(12),
+        };
+        let reports = [
+            report("basics::control_flow", Outcome::Passed),
+            report("error_handling::panic_basics", Outcome::Panicked("crash and burn".into())),
+            report("concurrency::deadlock_prevention", Outcome::TimedOut),
+        ];
+        let mut out = Vec::new();
+        write_summary(&reports, &mut out).unwrap();
+        let table = String::from_utf8(out).unwrap();
+
+        assert!(table.contains("\nerror_handling::panic_basics                  panicked       12ms  crash and burn\n"));
+        assert!(table.ends_with("1 ok, 1 panicked, 1 timed out, 0 failed\n"));
+    }
+}
@@ -0,0 +1,298 @@
+// ============================================================================
+// LESSONS
+// ============================================================================
+// The `Lesson` trait describes one runnable example, and `Section` groups the
+// lessons of one module. Each module declares its own `SECTION`, listing its
+// example functions with the `lesson!` macro; `registry` collects them.
+// ============================================================================
+
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+/// How much Rust a learner should know before attempting a lesson
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Difficulty {
+    Beginner = 1,
+    Intermediate = 2,
+    Advanced = 3,
+}
+
+impl Difficulty {
+    /// Accepts a level, `1` to `3`, or its name
+    pub fn parse(text: &str) -> Option<Difficulty> {
+        match text.to_ascii_lowercase().as_str() {
+            "1" | "beginner" => Some(Difficulty::Beginner),
+            "2" | "intermediate" => Some(Difficulty::Intermediate),
+            "3" | "advanced" => Some(Difficulty::Advanced),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // `pad` so callers can align difficulties in columns
+        f.pad(match self {
+            Difficulty::Beginner => "beginner",
+            Difficulty::Intermediate => "intermediate",
+            Difficulty::Advanced => "advanced",
+        })
+    }
+}
+
+/// A single runnable lesson
+pub trait Lesson: Sync {
+    /// The example function's name, unique within its section
+    fn name(&self) -> &'static str;
+
+    /// A human-readable title such as "Lifetime Basics"
+    fn title(&self) -> &'static str;
+
+    /// The id of the section this lesson belongs to, e.g. `ownership`
+    fn section(&self) -> &'static str;
+
+    /// Topics covered, used for searching and filtering
+    fn tags(&self) -> &'static [&'static str];
+
+    fn difficulty(&self) -> Difficulty;
+
+    /// Runs the lesson, writing its output to `out`
+    fn run(&self, out: &mut dyn Write) -> io::Result<()>;
+
+    /// The fully qualified id, e.g. `ownership::lifetime_basics`
+    fn id(&self) -> String {
+        format!("{}::{}", self.section(), self.name())
+    }
+}
+
+/// A lesson backed by one of the example functions in a module
+///
+/// Built with the `lesson!` macro, which fills in the module and the
+/// function name automatically.
+pub struct Example {
+    module: &'static str,
+    name: &'static str,
+    title: &'static str,
+    tags: &'static [&'static str],
+    difficulty: Difficulty,
+    run: fn(&mut dyn Write) -> io::Result<()>,
+}
+
+impl Example {
+    pub const fn new(
+        module: &'static str,
+        name: &'static str,
+        title: &'static str,
+        run: fn(&mut dyn Write) -> io::Result<()>,
+    ) -> Example {
+        Example {
+            module,
+            name,
+            title,
+            tags: &[],
+            difficulty: Difficulty::Beginner,
+            run,
+        }
+    }
+
+    pub const fn with_difficulty(mut self, difficulty: Difficulty) -> Example {
+        self.difficulty = difficulty;
+        self
+    }
+
+    pub const fn with_tags(mut self, tags: &'static [&'static str]) -> Example {
+        self.tags = tags;
+        self
+    }
+}
+
+impl Lesson for Example {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn title(&self) -> &'static str {
+        self.title
+    }
+
+    fn section(&self) -> &'static str {
+        // `module_path!()` gives e.g. "happyr::ownership"
+        self.module.rsplit("::").next().unwrap_or(self.module)
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        self.tags
+    }
+
+    fn difficulty(&self) -> Difficulty {
+        self.difficulty
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        crate::sched::run(|| (self.run)(out))
+    }
+}
+
+/// Declares an `Example` lesson for a function in the current module
+///
+/// ```ignore
+/// lesson!(lifetime_basics, "Lifetime Basics")
+/// ```
+#[allow(unused_macros)] // in a build without any lesson modules
+macro_rules! lesson {
+    ($function:ident, $title:literal) => {
+        $crate::lesson::Example::new(module_path!(), stringify!($function), $title, $function)
+    };
+}
+
+#[allow(unused_imports)]
+pub(crate) use lesson;
+
+/// A cloneable writer whose clones all append to one shared buffer
+///
+/// Lesson output sometimes comes from code that cannot borrow the lesson's
+/// writer, such as spawned threads or `Drop` impls. Those write into a
+/// `SharedOutput` instead, and the lesson copies it out when it is done.
+#[derive(Clone, Default)]
+pub struct SharedOutput(Arc<Mutex<Vec<u8>>>);
+
+impl SharedOutput {
+    pub fn new() -> SharedOutput {
+        SharedOutput::default()
+    }
+
+    /// Moves everything written so far into `out`
+    pub fn drain_into(&self, out: &mut dyn Write) -> io::Result<()> {
+        let bytes = std::mem::take(&mut *self.lock());
+        out.write_all(&bytes)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<u8>> {
+        // A writer thread that panicked mid-lesson leaves whole lines behind,
+        // so the buffer is still worth reading
+        self.0.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Write for SharedOutput {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        (&*self).write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Like `&File`, a shared reference is enough to write, so scoped threads
+/// can borrow one log instead of cloning it
+impl Write for &SharedOutput {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.lock().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// One numbered section of the learning journey, backed by a module
+pub struct Section {
+    /// The module name, used in lesson ids and selectors
+    pub id: &'static str,
+    /// Position in the recommended learning order, starting at 1
+    pub number: u32,
+    pub title: &'static str,
+    pub icon: &'static str,
+    pub lessons: &'static [&'static dyn Lesson],
+}
+
+impl Section {
+    /// The heading printed before the section's lessons, e.g.
+    /// "🔐 SECTION 2: OWNERSHIP & BORROWING"
+    pub fn banner(&self) -> String {
+        // Emoji drawn with a variation selector (🏗️, ⚠️) are reported as one
+        // column wide but render as two, so give them an extra space
+        let padding = if self.icon.contains('\u{FE0F}') { "  " } else { " " };
+        format!("{}{}SECTION {}: {}", self.icon, padding, self.number, self.title)
+    }
+}
+
+// ============================================================================
+// UNIT TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "sample output")
+    }
+
+    static SAMPLE: Example = lesson!(sample, "Sample Lesson")
+        .with_difficulty(Difficulty::Intermediate)
+        .with_tags(&["testing"]);
+
+    #[test]
+    fn test_example_metadata() {
+        assert_eq!(SAMPLE.name(), "sample");
+        assert_eq!(SAMPLE.section(), "tests");
+        assert_eq!(SAMPLE.id(), "tests::sample");
+        assert_eq!(SAMPLE.title(), "Sample Lesson");
+        assert_eq!(SAMPLE.tags(), ["testing"]);
+        assert_eq!(SAMPLE.difficulty(), Difficulty::Intermediate);
+    }
+
+    #[test]
+    fn test_example_runs_into_writer() {
+        let mut out = Vec::new();
+        SAMPLE.run(&mut out).unwrap();
+        assert_eq!(out, b"sample output\n");
+    }
+
+    #[test]
+    fn test_shared_output_collects_every_clone() {
+        let shared = SharedOutput::new();
+        let mut from_thread = shared.clone();
+        std::thread::spawn(move || writeln!(from_thread, "from thread").unwrap())
+            .join()
+            .unwrap();
+        writeln!(shared.clone(), "from main").unwrap();
+
+        let mut out = Vec::new();
+        shared.drain_into(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "from thread\nfrom main\n");
+
+        let mut empty = Vec::new();
+        shared.drain_into(&mut empty).unwrap();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_difficulty_ordering() {
+        assert!(Difficulty::Beginner < Difficulty::Advanced);
+        assert_eq!(Difficulty::Advanced.to_string(), "advanced");
+    }
+
+    #[test]
+    fn test_parse_difficulty() {
+        assert_eq!(Difficulty::parse("2"), Some(Difficulty::Intermediate));
+        assert_eq!(Difficulty::parse("Advanced"), Some(Difficulty::Advanced));
+        assert_eq!(Difficulty::parse("4"), None);
+    }
+
+    #[test]
+    fn test_section_banner() {
+        let section = Section {
+            id: "structures",
+            number: 3,
+            title: "STRUCTURES & ENUMS",
+            icon: "🏗️",
+            lessons: &[],
+        };
+        assert_eq!(section.banner(), "🏗️  SECTION 3: STRUCTURES & ENUMS");
+    }
+}
@@ -0,0 +1,79 @@
+// ============================================================================
+// HAPPYR - A Comprehensive Rust Learning Project
+// ============================================================================
+// The lessons, and the machinery that runs, tests and presents them, as a
+// library. `src/main.rs` is the `happyr` command built on top of it; other
+// crates can depend on it to run lessons, read the registry or reuse the
+// helpers the lessons define, such as `traits_generics::largest`,
+// `error_handling::parse_age` or `advanced::split_at_mut`.
+//
+// Every lesson is a `pub fn name(out: &mut dyn Write) -> io::Result<()>`
+// in its section's module, listed in that module's `SECTION` table;
+// `registry` collects the sections in teaching order.
+//
+// LESSON MODULES:
+// - basics: Variables, data types, functions, control flow
+// - ownership: Ownership rules, borrowing, references, lifetimes
+// - structures: Structs, enums, pattern matching
+// - traits_generics: Traits, generics, trait bounds
+// - collections: Vectors, HashMaps, strings
+// - error_handling: Result, Option, custom errors
+// - functional: Closures, iterators, functional programming
+// - smart_pointers: Box, Rc, RefCell, Arc
+// - concurrency: Threads, channels, shared state
+// - advanced: Macros, unsafe, FFI, and more
+//
+// Each lesson module is compiled only with the Cargo feature of the same
+// name; all of them are on by default. The `unsafe-lessons` and
+// `ffi-lessons` features add those lessons to the advanced section.
+//
+// SUPPORTING MODULES:
+// - lesson, registry: the Lesson trait, sections and the list of them
+// - cli, commands, report, json, isolate, sched: running lessons and
+//   reporting on them
+// - progress, interactive, quiz, exercise, playlist: learning paths
+// - search, show, source, export, watch: finding and reading lessons
+// - bench: timing code for the performance lessons
+// ============================================================================
+
+#[cfg(feature = "basics")]
+pub mod basics;
+#[cfg(feature = "ownership")]
+pub mod ownership;
+#[cfg(feature = "structures")]
+pub mod structures;
+#[cfg(feature = "traits_generics")]
+pub mod traits_generics;
+#[cfg(feature = "collections")]
+pub mod collections;
+#[cfg(feature = "error_handling")]
+pub mod error_handling;
+#[cfg(feature = "functional")]
+pub mod functional;
+#[cfg(feature = "smart_pointers")]
+pub mod smart_pointers;
+#[cfg(feature = "concurrency")]
+pub mod concurrency;
+#[cfg(feature = "advanced")]
+pub mod advanced;
+pub mod bench;
+pub mod cli;
+pub mod commands;
+pub mod exercise;
+pub mod export;
+pub mod interactive;
+pub mod isolate;
+pub mod json;
+pub mod lesson;
+pub mod playlist;
+pub mod progress;
+pub mod quiz;
+pub mod registry;
+pub mod report;
+pub mod sched;
+pub mod search;
+pub mod show;
+pub mod source;
+pub mod watch;
+#[cfg(test)]
+mod snapshot;
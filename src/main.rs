@@ -5,122 +5,70 @@
 // and practical examples. Perfect for beginners who want to master Rust!
 //
 // HOW TO USE THIS PROJECT:
-// 1. Read through each module in order (they build on each other)
-// 2. Run the entire project: cargo run
-// 3. Run specific examples by uncommenting them in main()
-// 4. Experiment by modifying the code and seeing what happens!
-// 5. Run tests: cargo test
+// 1. Read through each module in order (they build on each other).
+// 2. Run the whole journey: cargo run
+// 3. Find lessons: cargo run -- list, search weak or playlists.
+// 4. Run some of them: cargo run -- run ownership::lifetime_basics, or
+//    run --tag iterators --max-difficulty 2, or run --playlist beginner.
+// 5. Read one: cargo run -- show functional::iterator_adaptors prints its
+//    source with the output under each statement.
+// 6. Keep your place: cargo run -- interactive, progress and next.
+// 7. Test yourself: cargo run -- quiz ownership, then fill in a stub in
+//    exercises/ and run exercise check ownership/first_word.
+// 8. Experiment: cargo run -- watch concurrency::arc_mutex re-runs a
+//    lesson each time you save. Add --isolate to survive a panic or a
+//    hang, --deterministic to replay the same thread interleaving, or
+//    --format json for a report scripts can read.
+// 9. Read offline: cargo run -- export --html book/
+// 10. Run the tests: cargo test, or UPDATE_SNAPSHOTS=1 cargo test snapshot
+//     to accept changed lesson output.
 //
-// MODULE ORGANIZATION:
-// - basics: Variables, data types, functions, control flow
-// - ownership: Ownership rules, borrowing, references, lifetimes
-// - structures: Structs, enums, pattern matching
-// - traits_generics: Traits, generics, trait bounds
-// - collections: Vectors, HashMaps, strings
-// - error_handling: Result, Option, custom errors
-// - functional: Closures, iterators, functional programming
-// - smart_pointers: Box, Rc, RefCell, Arc
-// - concurrency: Threads, channels, shared state
-// - advanced: Macros, unsafe, FFI, and more
+// This binary only parses the command line and hands it to the library:
+// each command is a function in src/commands.rs.
 // ============================================================================
 
-// Declare all modules
-mod basics;
-mod ownership;
-mod structures;
-mod traits_generics;
-mod collections;
-mod error_handling;
-mod functional;
-mod smart_pointers;
-mod concurrency;
-mod advanced;
+use happyr::cli::{self, Command};
+use happyr::{commands, sched};
+use std::io;
 
 fn main() {
-    println!("🦀 Welcome to HappyR - Your Comprehensive Rust Learning Journey! 🦀\n");
-    println!("=" .repeat(70));
-    
-    // ========================================================================
-    // SECTION 1: BASICS
-    // ========================================================================
-    println!("\n📚 SECTION 1: RUST BASICS");
-    println!("=" .repeat(70));
-    basics::run_all_examples();
-    
-    // ========================================================================
-    // SECTION 2: OWNERSHIP & BORROWING
-    // ========================================================================
-    println!("\n🔐 SECTION 2: OWNERSHIP & BORROWING");
-    println!("=" .repeat(70));
-    ownership::run_all_examples();
-    
-    // ========================================================================
-    // SECTION 3: STRUCTURES & ENUMS
-    // ========================================================================
-    println!("\n🏗️  SECTION 3: STRUCTURES & ENUMS");
-    println!("=" .repeat(70));
-    structures::run_all_examples();
-    
-    // ========================================================================
-    // SECTION 4: TRAITS & GENERICS
-    // ========================================================================
-    println!("\n🎭 SECTION 4: TRAITS & GENERICS");
-    println!("=" .repeat(70));
-    traits_generics::run_all_examples();
-    
-    // ========================================================================
-    // SECTION 5: COLLECTIONS
-    // ========================================================================
-    println!("\n📦 SECTION 5: COLLECTIONS");
-    println!("=" .repeat(70));
-    collections::run_all_examples();
-    
-    // ========================================================================
-    // SECTION 6: ERROR HANDLING
-    // ========================================================================
-    println!("\n⚠️  SECTION 6: ERROR HANDLING");
-    println!("=" .repeat(70));
-    error_handling::run_all_examples();
-    
-    // ========================================================================
-    // SECTION 7: FUNCTIONAL PROGRAMMING
-    // ========================================================================
-    println!("\n🔄 SECTION 7: FUNCTIONAL PROGRAMMING");
-    println!("=" .repeat(70));
-    functional::run_all_examples();
-    
-    // ========================================================================
-    // SECTION 8: SMART POINTERS
-    // ========================================================================
-    println!("\n🧠 SECTION 8: SMART POINTERS");
-    println!("=" .repeat(70));
-    smart_pointers::run_all_examples();
-    
-    // ========================================================================
-    // SECTION 9: CONCURRENCY
-    // ========================================================================
-    println!("\n⚡ SECTION 9: CONCURRENCY");
-    println!("=" .repeat(70));
-    concurrency::run_all_examples();
-    
-    // ========================================================================
-    // SECTION 10: ADVANCED FEATURES
-    // ========================================================================
-    println!("\n🚀 SECTION 10: ADVANCED FEATURES");
-    println!("=" .repeat(70));
-    advanced::run_all_examples();
-    
-    // ========================================================================
-    // CONCLUSION
-    // ========================================================================
-    println!("\n" .repeat(2));
-    println!("=" .repeat(70));
-    println!("🎉 Congratulations! You've completed the Rust learning journey! 🎉");
-    println!("=" .repeat(70));
-    println!("\n💡 Next Steps:");
-    println!("   1. Modify the examples and experiment");
-    println!("   2. Run 'cargo test' to see all unit tests");
-    println!("   3. Build your own project using these concepts");
-    println!("   4. Check out the Rust Book: https://doc.rust-lang.org/book/");
-    println!("\nHappy Coding! 🦀\n");
+    let parsed = cli::parse_options(std::env::args().skip(1))
+        .and_then(|(options, args)| Ok((options, cli::parse_args(args)?)));
+    let (options, command) = match parsed {
+        Ok(parsed) => parsed,
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+    sched::set_mode(options.schedule);
+
+    let result = match command {
+        Command::RunAll => commands::run_all(&options),
+        Command::Run(selectors) => commands::run(&selectors, &options),
+        Command::List => commands::list(&options.filter),
+        Command::Playlists => commands::playlists(),
+        Command::Progress => commands::progress(),
+        Command::Next => commands::next(&options),
+        Command::Quiz(section) => commands::quiz(&section),
+        Command::ExerciseList => commands::exercise_list(),
+        Command::ExerciseCheck(id) => commands::exercise_check(&id),
+        Command::Interactive => commands::interactive(),
+        Command::Search(query) => commands::search(&query, &options),
+        Command::Show(id) => commands::show(&id),
+        Command::Watch(id) => commands::watch(&id, &options),
+        Command::Export(format, dir) => commands::export(format, &dir),
+        Command::Lesson(id) => commands::run_child_lesson(&id),
+        Command::Help => commands::help(),
+    };
+    if let Err(failure) = result {
+        // Whatever reads the output has stopped, e.g. `happyr list | head`
+        if failure.kind == Some(io::ErrorKind::BrokenPipe) {
+            std::process::exit(0);
+        }
+        if let Some(message) = failure.message {
+            eprintln!("error: {}", message);
+        }
+        std::process::exit(failure.code);
+    }
 }
@@ -9,42 +9,56 @@
 // - The slice type
 // ============================================================================
 
+// first_word, calculate_length and no_dangle are written the way the Rust
+// Book first introduces them, not the way clippy would prefer.
+#![allow(
+    clippy::let_and_return,
+    clippy::ptr_arg,
+    clippy::redundant_slicing,
+    clippy::single_char_add_str,
+)]
+
+use crate::lesson::{lesson, Difficulty, Section};
+use std::io::{self, Write};
+
 /// Demonstrates the three ownership rules in Rust
 /// 
 /// OWNERSHIP RULES:
 /// 1. Each value has a single owner
 /// 2. When the owner goes out of scope, the value is dropped
 /// 3. Values can be moved or borrowed, but not both simultaneously
-pub fn ownership_basics() {
-    println!("\n--- Ownership Basics ---");
+pub fn ownership_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Ownership Basics ---")?;
     
     // Rule 1: Each value has an owner
     let s1 = String::from("hello");
-    println!("s1 owns: {}", s1);
+    writeln!(out, "s1 owns: {}", s1)?;
     
     // Rule 3: Move semantics (ownership transfer)
     let s2 = s1; // s1's ownership moves to s2
     // println!("{}", s1); // ❌ Error! s1 no longer valid
-    println!("s2 now owns: {}", s2);
+    writeln!(out, "s2 now owns: {}", s2)?;
     
     // Rule 2: Value dropped when owner goes out of scope
     {
         let s3 = String::from("scoped");
-        println!("s3 in scope: {}", s3);
+        writeln!(out, "s3 in scope: {}", s3)?;
     } // s3 is dropped here
     // println!("{}", s3); // ❌ Error! s3 out of scope
     
     // Copy types (stored on stack) don't move
     let x = 5;
     let y = x; // Copy, not move
-    println!("x: {}, y: {} (both valid!)", x, y);
+    writeln!(out, "x: {}, y: {} (both valid!)", x, y)?;
     
     // Types that implement Copy trait:
     // - All integers, floats, booleans, char
     // - Tuples containing only Copy types
     let tuple = (1, 2.5, true);
     let tuple_copy = tuple;
-    println!("Original tuple: {:?}, Copy: {:?}", tuple, tuple_copy);
+    writeln!(out, "Original tuple: {:?}, Copy: {:?}", tuple, tuple_copy)?;
+    
+    Ok(())
 }
 
 /// Demonstrates ownership with functions
@@ -52,36 +66,38 @@ pub fn ownership_basics() {
 /// KEY CONCEPTS:
 /// - Passing a value to a function moves or copies it
 /// - Returning values transfers ownership
-pub fn ownership_and_functions() {
-    println!("\n--- Ownership and Functions ---");
+pub fn ownership_and_functions(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Ownership and Functions ---")?;
     
     let s = String::from("hello");
-    println!("Before function: {}", s);
+    writeln!(out, "Before function: {}", s)?;
     
-    takes_ownership(s); // s moves into function
+    takes_ownership(out, s)?; // s moves into function
     // println!("{}", s); // ❌ Error! s no longer valid
     
     let x = 5;
-    makes_copy(x); // x is copied (i32 is Copy)
-    println!("After function: {} (still valid!)", x);
+    makes_copy(out, x)?; // x is copied (i32 is Copy)
+    writeln!(out, "After function: {} (still valid!)", x)?;
     
     // Getting ownership back via return
     let s1 = String::from("world");
     let s2 = takes_and_gives_back(s1);
-    println!("Got ownership back: {}", s2);
+    writeln!(out, "Got ownership back: {}", s2)?;
     
     // Tedious pattern: take and return ownership
     let s3 = String::from("data");
     let (s4, len) = calculate_length_with_ownership(s3);
-    println!("String '{}' has length {}", s4, len);
+    writeln!(out, "String '{}' has length {}", s4, len)?;
+    
+    Ok(())
 }
 
-fn takes_ownership(s: String) {
-    println!("Function owns: {}", s);
+fn takes_ownership(out: &mut dyn Write, s: String) -> io::Result<()> {
+    writeln!(out, "Function owns: {}", s)
 } // s is dropped here
 
-fn makes_copy(x: i32) {
-    println!("Function has copy: {}", x);
+fn makes_copy(out: &mut dyn Write, x: i32) -> io::Result<()> {
+    writeln!(out, "Function has copy: {}", x)
 }
 
 fn takes_and_gives_back(s: String) -> String {
@@ -99,24 +115,26 @@ fn calculate_length_with_ownership(s: String) -> (String, usize) {
 /// - References allow using values without taking ownership
 /// - & creates a reference (immutable by default)
 /// - References must always be valid (no dangling references)
-pub fn references_and_borrowing() {
-    println!("\n--- References and Borrowing ---");
+pub fn references_and_borrowing(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- References and Borrowing ---")?;
     
     let s1 = String::from("hello");
     
     // Borrowing: pass a reference instead of ownership
     let len = calculate_length(&s1);
-    println!("Length of '{}' is {}", s1, len); // s1 still valid!
+    writeln!(out, "Length of '{}' is {}", s1, len)?; // s1 still valid!
     
     // Multiple immutable references are allowed
     let r1 = &s1;
     let r2 = &s1;
-    println!("References: {} and {}", r1, r2);
+    writeln!(out, "References: {} and {}", r1, r2)?;
     
     // References are like pointers but guaranteed to be valid
     let x = 5;
     let r = &x;
-    println!("Value: {}, Reference: {}", x, *r); // Dereference with *
+    writeln!(out, "Value: {}, Reference: {}", x, *r)?; // Dereference with *
+    
+    Ok(())
 }
 
 fn calculate_length(s: &String) -> usize {
@@ -129,20 +147,20 @@ fn calculate_length(s: &String) -> usize {
 /// - &mut creates a mutable reference
 /// - Only ONE mutable reference allowed at a time
 /// - Cannot have mutable and immutable references simultaneously
-pub fn mutable_references() {
-    println!("\n--- Mutable References ---");
+pub fn mutable_references(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Mutable References ---")?;
     
     let mut s = String::from("hello");
-    println!("Before: {}", s);
+    writeln!(out, "Before: {}", s)?;
     
     change(&mut s); // Pass mutable reference
-    println!("After: {}", s);
+    writeln!(out, "After: {}", s)?;
     
     // Only one mutable reference at a time
     let r1 = &mut s;
     r1.push_str("!");
     // let r2 = &mut s; // ❌ Error! Can't have two mutable refs
-    println!("Modified: {}", r1);
+    writeln!(out, "Modified: {}", r1)?;
     
     // Can have multiple mutable refs in different scopes
     {
@@ -151,18 +169,20 @@ pub fn mutable_references() {
     } // r2 goes out of scope
     
     let r3 = &mut s; // Now this is okay
-    println!("Final: {}", r3);
+    writeln!(out, "Final: {}", r3)?;
     
     // Cannot mix mutable and immutable references
     let mut value = 10;
     let r_immut = &value;
     // let r_mut = &mut value; // ❌ Error! Can't borrow as mutable
-    println!("Immutable ref: {}", r_immut);
+    writeln!(out, "Immutable ref: {}", r_immut)?;
     
     // After last use of immutable refs, can create mutable ref
     let r_mut = &mut value;
     *r_mut += 5;
-    println!("Mutable ref: {}", r_mut);
+    writeln!(out, "Mutable ref: {}", r_mut)?;
+    
+    Ok(())
 }
 
 fn change(s: &mut String) {
@@ -174,15 +194,17 @@ fn change(s: &mut String) {
 /// DANGLING REFERENCES:
 /// - Rust prevents dangling references at compile time
 /// - References must always point to valid data
-pub fn no_dangling_references() {
-    println!("\n--- No Dangling References ---");
+pub fn no_dangling_references(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- No Dangling References ---")?;
     
     // This works: return ownership
     let s = no_dangle();
-    println!("Valid string: {}", s);
+    writeln!(out, "Valid string: {}", s)?;
     
     // This would fail (commented out):
     // let reference = dangle(); // ❌ Error! Returns reference to dropped value
+    
+    Ok(())
 }
 
 fn no_dangle() -> String {
@@ -202,36 +224,39 @@ fn no_dangle() -> String {
 /// - Reference a contiguous sequence of elements
 /// - Don't take ownership
 /// - Type: &str for strings, &[T] for arrays
-pub fn string_slices() {
-    println!("\n--- String Slices ---");
+pub fn string_slices(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- String Slices ---")?;
     
     let s = String::from("hello world");
     
     // Slice syntax: &s[start..end]
     let hello = &s[0..5]; // or &s[..5]
     let world = &s[6..11]; // or &s[6..]
-    println!("Slices: '{}' and '{}'", hello, world);
+    writeln!(out, "Slices: '{}' and '{}'", hello, world)?;
     
     // Full slice
     let full = &s[..];
-    println!("Full slice: '{}'", full);
+    writeln!(out, "Full slice: '{}'", full)?;
     
     // Finding first word
     let first = first_word(&s);
-    println!("First word: '{}'", first);
+    writeln!(out, "First word: '{}'", first)?;
     
     // String literals are slices
     let literal = "Hello, world!"; // Type: &str
-    println!("Literal (slice): {}", literal);
+    writeln!(out, "Literal (slice): {}", literal)?;
     
     // Slices work with String and &str
     let my_string = String::from("hello world");
     let word1 = first_word(&my_string[..]); // String slice
     let word2 = first_word(literal); // String literal
-    println!("Words: '{}', '{}'", word1, word2);
+    writeln!(out, "Words: '{}', '{}'", word1, word2)?;
+    
+    Ok(())
 }
 
-fn first_word(s: &str) -> &str {
+/// The text before the first space in `s`, or all of it
+pub fn first_word(s: &str) -> &str {
     let bytes = s.as_bytes();
     
     for (i, &item) in bytes.iter().enumerate() {
@@ -244,17 +269,19 @@ fn first_word(s: &str) -> &str {
 }
 
 /// Demonstrates array slices
-pub fn array_slices() {
-    println!("\n--- Array Slices ---");
+pub fn array_slices(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Array Slices ---")?;
     
     let a = [1, 2, 3, 4, 5];
     
     let slice = &a[1..4]; // Elements at index 1, 2, 3
-    println!("Array: {:?}", a);
-    println!("Slice: {:?}", slice);
+    writeln!(out, "Array: {:?}", a)?;
+    writeln!(out, "Slice: {:?}", slice)?;
     
     // Slices have type &[T]
     assert_eq!(slice, &[2, 3, 4]);
+    
+    Ok(())
 }
 
 /// Demonstrates lifetime basics
@@ -263,33 +290,37 @@ pub fn array_slices() {
 /// - Ensure references are valid as long as needed
 /// - Prevent dangling references
 /// - Usually inferred, sometimes need explicit annotations
-pub fn lifetime_basics() {
-    println!("\n--- Lifetime Basics ---");
+pub fn lifetime_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Lifetime Basics ---")?;
     
     let string1 = String::from("long string");
     let string2 = String::from("short");
     
     let result = longest(string1.as_str(), string2.as_str());
-    println!("Longest string: '{}'", result);
+    writeln!(out, "Longest string: '{}'", result)?;
     
     // Lifetime ensures result is valid
     {
         let string3 = String::from("xyz");
         let result2 = longest(string1.as_str(), string3.as_str());
-        println!("Longest in scope: '{}'", result2);
+        writeln!(out, "Longest in scope: '{}'", result2)?;
     } // string3 dropped, but result2 already used
     
     // Struct with lifetime
     let novel = String::from("Call me Ishmael. Some years ago...");
     let first_sentence = novel.split('.').next().expect("No '.'");
     let excerpt = ImportantExcerpt { part: first_sentence };
-    println!("Excerpt: '{}'", excerpt.part);
+    writeln!(out, "Excerpt: '{}'", excerpt.part)?;
+    
+    Ok(())
 }
 
-// Lifetime annotation: 'a
-// Means: returned reference valid as long as both parameters are valid
-fn longest<'a>(x: &'a str, y: &'a str) -> &'a str {
-    if x.len() > y.len() {
+/// The longer of two strings, borrowed for as long as both are
+///
+/// Lifetime annotation: 'a means the returned reference is valid as long
+/// as both parameters are
+pub fn longest<'a>(x: &'a str, y: &'a str) -> &'a str {
+    if x.len() >= y.len() { // Ties keep the first argument
         x
     } else {
         y
@@ -307,19 +338,21 @@ struct ImportantExcerpt<'a> {
 /// LIFETIME ELISION:
 /// - Compiler can infer lifetimes in many cases
 /// - Three rules for elision
-pub fn lifetime_elision() {
-    println!("\n--- Lifetime Elision ---");
+pub fn lifetime_elision(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Lifetime Elision ---")?;
     
     let s = String::from("hello");
     
     // No explicit lifetime needed (elided)
     let result = first_word_elided(&s);
-    println!("First word: '{}'", result);
+    writeln!(out, "First word: '{}'", result)?;
     
     // Compiler applies elision rules:
     // 1. Each parameter gets its own lifetime
     // 2. If one input lifetime, it's assigned to all outputs
     // 3. If multiple inputs and one is &self/&mut self, use that lifetime
+    
+    Ok(())
 }
 
 // Lifetime elided (compiler infers it)
@@ -338,34 +371,49 @@ fn first_word_elided(s: &str) -> &str {
 /// STATIC LIFETIME:
 /// - 'static means reference lives for entire program
 /// - All string literals have 'static lifetime
-pub fn static_lifetime() {
-    println!("\n--- Static Lifetime ---");
+pub fn static_lifetime(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Static Lifetime ---")?;
     
     // String literal has 'static lifetime
     let s: &'static str = "I live forever!";
-    println!("Static string: {}", s);
+    writeln!(out, "Static string: {}", s)?;
     
     // Static variables
     static LANGUAGE: &str = "Rust";
-    println!("Static variable: {}", LANGUAGE);
+    writeln!(out, "Static variable: {}", LANGUAGE)?;
+    
+    Ok(())
 }
 
 // ============================================================================
 // PUBLIC INTERFACE
 // ============================================================================
 
-pub fn run_all_examples() {
-    ownership_basics();
-    ownership_and_functions();
-    references_and_borrowing();
-    mutable_references();
-    no_dangling_references();
-    string_slices();
-    array_slices();
-    lifetime_basics();
-    lifetime_elision();
-    static_lifetime();
-}
+/// This module's lessons, in teaching order
+pub static SECTION: Section = Section {
+    id: "ownership",
+    number: 2,
+    title: "OWNERSHIP & BORROWING",
+    icon: "🔐",
+    lessons: &[
+        &lesson!(ownership_basics, "Ownership Basics").with_tags(&["ownership", "move-semantics"]),
+        &lesson!(ownership_and_functions, "Ownership and Functions").with_tags(&["ownership", "functions"]),
+        &lesson!(references_and_borrowing, "References and Borrowing").with_tags(&["borrowing", "references"]),
+        &lesson!(mutable_references, "Mutable References").with_tags(&["borrowing", "references", "mutability"]),
+        &lesson!(no_dangling_references, "No Dangling References").with_tags(&["borrowing", "lifetimes"]),
+        &lesson!(string_slices, "String Slices").with_tags(&["slices", "strings"]),
+        &lesson!(array_slices, "Array Slices").with_tags(&["slices", "arrays"]),
+        &lesson!(lifetime_basics, "Lifetime Basics")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["lifetimes"]),
+        &lesson!(lifetime_elision, "Lifetime Elision")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["lifetimes"]),
+        &lesson!(static_lifetime, "Static Lifetime")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["lifetimes"]),
+    ],
+};
 
 // ============================================================================
 // UNIT TESTS
@@ -0,0 +1,222 @@
+// ============================================================================
+// PLAYLISTS
+// ============================================================================
+// Learning paths picked out of the lessons by their difficulty and tags, so
+// a new lesson joins the right paths as soon as it's declared:
+//
+//     beginner        every beginner lesson, in teaching order
+//     intermediate    the intermediate ones
+//     advanced        the advanced ones
+//     memory          ownership, borrowing, lifetimes and smart pointers
+//     concurrency     threads, channels and shared state
+//
+// `happyr playlists` lists them and `happyr run --playlist beginner` runs
+// one. README.md shows the lessons of each, generated from this table into
+// the block between its `playlists` markers. When lessons change, rewrite
+// the block and review the diff:
+//
+//     UPDATE_SNAPSHOTS=1 cargo test playlist
+// ============================================================================
+
+use crate::lesson::{Difficulty, Lesson, Section};
+use std::io::{self, Write};
+
+/// A learning path through the lessons
+#[derive(Debug, PartialEq)]
+pub struct Playlist {
+    /// What to pass to `--playlist`
+    pub name: &'static str,
+    pub title: &'static str,
+    pub icon: &'static str,
+    /// Who it's for, in a sentence
+    pub summary: &'static str,
+    /// The easiest and hardest lessons it includes
+    difficulty: (Difficulty, Difficulty),
+    /// A lesson needs one of these tags, unless there are none
+    tags: &'static [&'static str],
+}
+
+pub static PLAYLISTS: &[Playlist] = &[
+    Playlist {
+        name: "beginner",
+        title: "Complete Beginner",
+        icon: "🌱",
+        summary: "New to Rust? Start here: variables and functions, ownership, structs and enums, \
+                  then first steps with collections and errors.",
+        difficulty: (Difficulty::Beginner, Difficulty::Beginner),
+        tags: &[],
+    },
+    Playlist {
+        name: "intermediate",
+        title: "Intermediate Learner",
+        icon: "🌿",
+        summary: "Comfortable with the basics? Lifetimes, traits and generics, error handling, \
+                  closures and iterators.",
+        difficulty: (Difficulty::Intermediate, Difficulty::Intermediate),
+        tags: &[],
+    },
+    Playlist {
+        name: "advanced",
+        title: "Advanced Topics",
+        icon: "🌳",
+        summary: "Smart pointers, fearless concurrency, unsafe code, FFI and macros.",
+        difficulty: (Difficulty::Advanced, Difficulty::Advanced),
+        tags: &[],
+    },
+    Playlist {
+        name: "memory",
+        title: "Memory Without a Garbage Collector",
+        icon: "🔐",
+        summary: "How Rust keeps memory safe, from ownership rules to reference counting.",
+        difficulty: (Difficulty::Beginner, Difficulty::Advanced),
+        tags: &["ownership", "borrowing", "lifetimes", "smart-pointers", "rc", "interior-mutability"],
+    },
+    Playlist {
+        name: "concurrency",
+        title: "Fearless Concurrency",
+        icon: "🧵",
+        summary: "Threads, message passing and shared state, and how the compiler keeps them safe.",
+        difficulty: (Difficulty::Beginner, Difficulty::Advanced),
+        tags: &["threads", "channels", "sync"],
+    },
+];
+
+/// Looks up a playlist by name, e.g. `beginner`
+pub fn find(name: &str) -> Option<&'static Playlist> {
+    PLAYLISTS.iter().find(|playlist| playlist.name == name)
+}
+
+impl Playlist {
+    pub fn contains(&self, lesson: &dyn Lesson) -> bool {
+        let (easiest, hardest) = self.difficulty;
+        (easiest..=hardest).contains(&lesson.difficulty())
+            && (self.tags.is_empty() || lesson.tags().iter().any(|tag| self.tags.contains(tag)))
+    }
+
+    /// The playlist's lessons in `sections`, in teaching order
+    pub fn lessons<'a>(&self, sections: &[&'a Section]) -> Vec<&'a dyn Lesson> {
+        sections
+            .iter()
+            .flat_map(|section| section.lessons.iter().copied())
+            .filter(|lesson| self.contains(*lesson))
+            .collect()
+    }
+}
+
+/// Prints every playlist with its size, for `happyr playlists`
+pub fn write_list(sections: &[&Section], out: &mut dyn Write) -> io::Result<()> {
+    for playlist in PLAYLISTS {
+        writeln!(
+            out,
+            "  {:<14} {} {} ({} lessons)",
+            playlist.name,
+            playlist.icon,
+            playlist.title,
+            playlist.lessons(sections).len()
+        )?;
+        writeln!(out, "  {:<14} {}", "", playlist.summary)?;
+    }
+    writeln!(out, "\nRun one with 'happyr run --playlist <NAME>'")
+}
+
+// ============================================================================
+// UNIT TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry;
+    use std::fs;
+    use std::path::Path;
+
+    /// Marks the generated block in README.md
+    const BEGIN: &str = "<!-- playlists:begin (generated by `UPDATE_SNAPSHOTS=1 cargo test playlist`) -->";
+    const END: &str = "<!-- playlists:end -->";
+
+    /// The README's description of every playlist, lessons grouped by section
+    fn markdown(sections: &[&Section]) -> String {
+        let mut text = String::new();
+        for playlist in PLAYLISTS {
+            let lessons = playlist.lessons(sections);
+            text.push_str(&format!("\n### {} {}\n\n{}\n\n", playlist.icon, playlist.title, playlist.summary));
+            text.push_str(&format!(
+                "`cargo run -- run --playlist {}` ({} lessons)\n\n",
+                playlist.name,
+                lessons.len()
+            ));
+            for section in sections {
+                let titles: Vec<&str> = lessons
+                    .iter()
+                    .filter(|lesson| lesson.section() == section.id)
+                    .map(|lesson| lesson.title())
+                    .collect();
+                if !titles.is_empty() {
+                    text.push_str(&format!("- **{}**: {}\n", section.title, titles.join(", ")));
+                }
+            }
+        }
+        text
+    }
+
+    /// `readme` with the text between the markers replaced by `block`, or
+    /// `None` if the markers are missing
+    fn splice(readme: &str, block: &str) -> Option<String> {
+        let start = readme.find(BEGIN)? + BEGIN.len();
+        let end = start + readme[start..].find(END)?;
+        Some(format!("{}\n{}\n{}", &readme[..start], block, &readme[end..]))
+    }
+
+    #[test]
+    #[cfg(all(feature = "basics", feature = "concurrency"))]
+    fn test_playlists_pick_by_metadata() {
+        let beginner = find("beginner").unwrap().lessons(registry::SECTIONS);
+        assert!(beginner.iter().all(|lesson| lesson.difficulty() == Difficulty::Beginner));
+        assert_eq!(beginner[0].id(), "basics::variables_and_mutability");
+
+        let concurrency = find("concurrency").unwrap().lessons(registry::SECTIONS);
+        assert!(concurrency.iter().any(|lesson| lesson.id() == "concurrency::channel_basics"));
+        assert!(!concurrency.iter().any(|lesson| lesson.section() == "basics"));
+        assert_eq!(find("expert"), None);
+    }
+
+    #[test]
+    fn test_every_playlist_has_lessons() {
+        // Leaving sections out of the build can empty a playlist
+        if !registry::missing_features().is_empty() {
+            return;
+        }
+        for playlist in PLAYLISTS {
+            assert!(!playlist.lessons(registry::SECTIONS).is_empty(), "{} is empty", playlist.name);
+        }
+    }
+
+    #[test]
+    fn test_splice() {
+        let readme = format!("intro\n{}\nold\n{}\noutro\n", BEGIN, END);
+        assert_eq!(
+            splice(&readme, "new\n"),
+            Some(format!("intro\n{}\nnew\n\n{}\noutro\n", BEGIN, END))
+        );
+        assert_eq!(splice("no markers", "new"), None);
+    }
+
+    #[test]
+    fn test_readme_playlists_are_current() {
+        // README.md shows the playlists of a build with every section
+        if !registry::missing_features().is_empty() {
+            return;
+        }
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("README.md");
+        let readme = fs::read_to_string(&path).unwrap();
+        let expected = splice(&readme, &markdown(registry::SECTIONS)).expect("README.md has no playlist markers");
+        if readme != expected && std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            fs::write(&path, expected).unwrap();
+            return;
+        }
+        assert!(
+            readme == expected,
+            "README.md playlists are out of date, rerun with UPDATE_SNAPSHOTS=1 to regenerate them"
+        );
+    }
+}
@@ -0,0 +1,264 @@
+// ============================================================================
+// LEARNER PROGRESS
+// ============================================================================
+// Remembers which lessons a learner has started and finished, in a small
+// text file at `~/.happyr/progress` (or `$HAPPYR_HOME/progress`):
+//
+//     # happyr progress
+//     done basics::variables_and_mutability
+//     started concurrency::thread_basics
+//
+// A lesson is "started" when it begins running and "done" once it has run
+// to the end. The registry's section order is the recommended path, so the
+// next lesson is simply the first one that isn't done.
+// ============================================================================
+
+use crate::lesson::{Lesson, Section};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Started,
+    Done,
+}
+
+impl Status {
+    fn as_str(self) -> &'static str {
+        match self {
+            Status::Started => "started",
+            Status::Done => "done",
+        }
+    }
+}
+
+/// Where happyr keeps its files: `~/.happyr`, or `HAPPYR_HOME` for tests
+/// and sandboxes
+pub fn home() -> Option<PathBuf> {
+    match std::env::var_os("HAPPYR_HOME") {
+        Some(dir) => Some(PathBuf::from(dir)),
+        None => Some(PathBuf::from(std::env::var_os("HOME")?).join(".happyr")),
+    }
+}
+
+/// Lesson statuses by lesson id, optionally backed by a file
+#[derive(Debug, Default)]
+pub struct Progress {
+    statuses: BTreeMap<String, Status>,
+    /// Where `record` saves to; `None` keeps progress in memory only
+    path: Option<PathBuf>,
+}
+
+impl Progress {
+    /// The progress file, in `home()`
+    pub fn default_path() -> Option<PathBuf> {
+        Some(home()?.join("progress"))
+    }
+
+    /// Loads progress from `path`; a missing file means nothing done yet
+    pub fn load(path: &Path) -> io::Result<Progress> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e),
+        };
+        let mut progress = Progress::parse(&text);
+        progress.path = Some(path.to_path_buf());
+        Ok(progress)
+    }
+
+    /// Parses the file format; lines it doesn't understand are skipped
+    pub fn parse(text: &str) -> Progress {
+        let mut progress = Progress::default();
+        for line in text.lines().map(str::trim) {
+            let (id, status) = match line.split_once(' ') {
+                Some(("done", id)) => (id, Status::Done),
+                Some(("started", id)) => (id, Status::Started),
+                _ => continue,
+            };
+            progress.mark(id.trim(), status);
+        }
+        progress
+    }
+
+    pub fn status(&self, id: &str) -> Option<Status> {
+        self.statuses.get(id).copied()
+    }
+
+    pub fn is_done(&self, id: &str) -> bool {
+        self.status(id) == Some(Status::Done)
+    }
+
+    /// Updates a lesson's status and saves, if this progress has a file
+    ///
+    /// If saving fails, the error is returned once and progress is only
+    /// kept in memory from then on.
+    pub fn record(&mut self, id: &str, status: Status) -> io::Result<()> {
+        self.mark(id, status);
+        let saved = match &self.path {
+            Some(path) => self.save(path),
+            None => return Ok(()),
+        };
+        if saved.is_err() {
+            self.path = None;
+        }
+        saved
+    }
+
+    fn mark(&mut self, id: &str, status: Status) {
+        // Rerunning a finished lesson doesn't make it unfinished
+        if !self.is_done(id) {
+            self.statuses.insert(id.to_string(), status);
+        }
+    }
+
+    fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut text = String::from("# happyr progress\n");
+        for (id, status) in &self.statuses {
+            text.push_str(&format!("{} {}\n", status.as_str(), id));
+        }
+        fs::write(path, text)
+    }
+
+    /// The first unfinished lesson along the recommended path
+    pub fn next(&self, sections: &[&'static Section]) -> Option<(&'static Section, &'static dyn Lesson)> {
+        sections.iter().find_map(|&section| {
+            section
+                .lessons
+                .iter()
+                .find(|lesson| !self.is_done(&lesson.id()))
+                .map(|&lesson| (section, lesson))
+        })
+    }
+
+    /// Writes the per-section completion table shown by `happyr progress`
+    pub fn write_table(&self, sections: &[&Section], out: &mut dyn Write) -> io::Result<()> {
+        const BAR_WIDTH: usize = 20;
+
+        writeln!(out, "{:<33}{:>7}  {:>7}", "Section", "Done", "Started")?;
+        writeln!(out, "{}", "-".repeat(70))?;
+
+        let (mut all_done, mut all_total) = (0, 0);
+        for section in sections {
+            let total = section.lessons.len();
+            let count = |wanted| {
+                section
+                    .lessons
+                    .iter()
+                    .filter(|lesson| self.status(&lesson.id()) == Some(wanted))
+                    .count()
+            };
+            let (done, started) = (count(Status::Done), count(Status::Started));
+            all_done += done;
+            all_total += total;
+
+            let filled = (done * BAR_WIDTH).checked_div(total).unwrap_or(0);
+            writeln!(
+                out,
+                "{:>2}. {:<28} {:>7}  {:>7}  [{}{}]",
+                section.number,
+                section.title,
+                format!("{}/{}", done, total),
+                started,
+                "#".repeat(filled),
+                "-".repeat(BAR_WIDTH - filled)
+            )?;
+        }
+
+        writeln!(out, "{}", "-".repeat(70))?;
+        let percent = (all_done * 100).checked_div(all_total).unwrap_or(0);
+        let total = format!("{}/{}", all_done, all_total);
+        writeln!(out, "{:<33}{:>7}  ({}% complete)", "Total", total, percent)
+    }
+}
+
+// ============================================================================
+// UNIT TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lesson::lesson;
+
+    fn noop(_: &mut dyn Write) -> io::Result<()> {
+        Ok(())
+    }
+
+    use noop as first;
+    use noop as second;
+    use noop as third;
+
+    static ONE: Section = Section {
+        id: "tests",
+        number: 1,
+        title: "ONE",
+        icon: "1️⃣",
+        lessons: &[&lesson!(first, "First"), &lesson!(second, "Second")],
+    };
+
+    static TWO: Section = Section {
+        id: "tests",
+        number: 2,
+        title: "TWO",
+        icon: "2️⃣",
+        lessons: &[&lesson!(third, "Third")],
+    };
+
+    #[test]
+    fn test_parse_and_done_sticks() {
+        let mut progress = Progress::parse("# comment\ndone a::b\nstarted a::c\nbogus line\n\n");
+        assert_eq!(progress.status("a::b"), Some(Status::Done));
+        assert_eq!(progress.status("a::c"), Some(Status::Started));
+        assert_eq!(progress.status("a::d"), None);
+
+        progress.record("a::b", Status::Started).unwrap();
+        assert!(progress.is_done("a::b"));
+    }
+
+    #[test]
+    fn test_next_follows_section_order() {
+        let mut progress = Progress::default();
+        let name = |progress: &Progress| progress.next(&[&ONE, &TWO]).map(|(_, lesson)| lesson.name());
+
+        assert_eq!(name(&progress), Some("first"));
+        progress.record("tests::first", Status::Done).unwrap();
+        progress.record("tests::second", Status::Started).unwrap();
+        assert_eq!(name(&progress), Some("second"));
+        progress.record("tests::second", Status::Done).unwrap();
+        progress.record("tests::third", Status::Done).unwrap();
+        assert_eq!(name(&progress), None);
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let dir = std::env::temp_dir().join(format!("happyr-progress-{}", std::process::id()));
+        let path = dir.join("progress");
+
+        let mut progress = Progress::load(&path).unwrap();
+        progress.record("ownership::slices", Status::Started).unwrap();
+        progress.record("basics::control_flow", Status::Done).unwrap();
+
+        let reloaded = Progress::load(&path).unwrap();
+        assert!(reloaded.is_done("basics::control_flow"));
+        assert_eq!(reloaded.status("ownership::slices"), Some(Status::Started));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_table_counts_per_section() {
+        let progress = Progress::parse("done tests::first\nstarted tests::second\n");
+        let mut out = Vec::new();
+        progress.write_table(&[&ONE, &TWO], &mut out).unwrap();
+        let table = String::from_utf8(out).unwrap();
+
+        assert!(table.contains(" 1. ONE"));
+        assert!(table.contains(" 1. ONE                              1/2        1  [##########----------]"));
+        assert!(table.ends_with("Total                                1/3  (33% complete)\n"));
+    }
+}
@@ -0,0 +1,383 @@
+// ============================================================================
+// QUIZZES
+// ============================================================================
+// `happyr quiz ownership` asks the questions in `quizzes/ownership.quiz`.
+// Each bank is a plain text file of blocks separated by blank lines:
+//
+//     kind: prints
+//     question: What does this print?
+//     code: let s2 = s1;
+//     code: println!("s2 now owns: {}", s2);
+//     lesson: ownership::ownership_basics
+//     line: s2 now owns:
+//     explain: The String moved into s2.
+//
+// Kinds are `choice` (with `choice:` options and a 1-based `answer:`),
+// `compiles` (`answer: yes` or `no`) and `prints`. A `prints` question either
+// has a fixed `answer:`, or names a `lesson:` and the start of one of its
+// output `line:`s, in which case the answer is the rest of that line as the
+// lesson actually prints it, so quizzes can't drift from the lessons.
+// Lines starting with `#` are comments.
+// ============================================================================
+
+use crate::interactive::prompt;
+use crate::lesson::Section;
+use crate::registry;
+use std::io::{self, BufRead, Write};
+
+/// The question banks, compiled in so the binary works from any directory
+static BANKS: &[(&str, &str)] = &[
+    #[cfg(feature = "basics")]
+    ("basics", include_str!("../quizzes/basics.quiz")),
+    #[cfg(feature = "ownership")]
+    ("ownership", include_str!("../quizzes/ownership.quiz")),
+    #[cfg(feature = "structures")]
+    ("structures", include_str!("../quizzes/structures.quiz")),
+    #[cfg(feature = "traits_generics")]
+    ("traits_generics", include_str!("../quizzes/traits_generics.quiz")),
+    #[cfg(feature = "collections")]
+    ("collections", include_str!("../quizzes/collections.quiz")),
+    #[cfg(feature = "error_handling")]
+    ("error_handling", include_str!("../quizzes/error_handling.quiz")),
+    #[cfg(feature = "functional")]
+    ("functional", include_str!("../quizzes/functional.quiz")),
+    #[cfg(feature = "smart_pointers")]
+    ("smart_pointers", include_str!("../quizzes/smart_pointers.quiz")),
+    #[cfg(feature = "concurrency")]
+    ("concurrency", include_str!("../quizzes/concurrency.quiz")),
+    #[cfg(feature = "advanced")]
+    ("advanced", include_str!("../quizzes/advanced.quiz")),
+];
+
+/// The question bank text for a section, if it has one
+pub fn bank(section: &str) -> Option<&'static str> {
+    BANKS.iter().find(|(id, _)| *id == section).map(|&(_, text)| text)
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Kind {
+    /// Pick one of the options; `answer` is an index into them
+    Choice { options: Vec<String>, answer: usize },
+    /// Decide whether the code compiles
+    Compiles(bool),
+    /// Predict what the code prints
+    Prints(Expected),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Expected {
+    Text(String),
+    /// The rest of the first output line of `lesson` that starts with `line`
+    LessonLine { lesson: String, line: String },
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Question {
+    pub kind: Kind,
+    pub question: String,
+    pub code: Vec<String>,
+    pub explain: Option<String>,
+}
+
+impl Question {
+    /// The correct answer, in the form `check` compares against
+    pub fn answer(&self) -> Result<String, String> {
+        match &self.kind {
+            Kind::Choice { answer, .. } => Ok((answer + 1).to_string()),
+            Kind::Compiles(true) => Ok("yes".to_string()),
+            Kind::Compiles(false) => Ok("no".to_string()),
+            Kind::Prints(Expected::Text(text)) => Ok(text.clone()),
+            Kind::Prints(Expected::LessonLine { lesson, line }) => lesson_line(lesson, line),
+        }
+    }
+
+    /// Whether `given` matches the correct answer `expected`
+    pub fn check(&self, expected: &str, given: &str) -> bool {
+        let given = given.trim();
+        match self.kind {
+            Kind::Choice { .. } => given == expected,
+            Kind::Compiles(_) => {
+                let given = match given.to_lowercase().as_str() {
+                    "y" | "yes" | "true" => "yes",
+                    "n" | "no" | "false" => "no",
+                    _ => return false,
+                };
+                given == expected
+            }
+            Kind::Prints(_) => squash_spaces(given) == squash_spaces(expected),
+        }
+    }
+}
+
+/// Runs a lesson and finds what it prints after `prefix`
+fn lesson_line(id: &str, prefix: &str) -> Result<String, String> {
+    let lesson = registry::find(id).ok_or_else(|| format!("no lesson '{}'", id))?;
+    let mut out = Vec::new();
+    lesson.run(&mut out).map_err(|e| format!("{} failed: {}", id, e))?;
+
+    String::from_utf8_lossy(&out)
+        .lines()
+        .find_map(|line| line.strip_prefix(prefix))
+        .map(|rest| rest.trim().to_string())
+        .ok_or_else(|| format!("{} prints no line starting with '{}'", id, prefix))
+}
+
+fn squash_spaces(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Parses a question bank, reporting the first mistake with its line number
+pub fn parse(text: &str) -> Result<Vec<Question>, String> {
+    let mut questions = Vec::new();
+    let mut block: Vec<(usize, &str, &str)> = Vec::new();
+
+    // The trailing `None` flushes the last block
+    for line in text.lines().enumerate().map(Some).chain([None]) {
+        match line {
+            Some((_, line)) if line.trim_start().starts_with('#') => {}
+            Some((number, line)) if !line.trim().is_empty() => {
+                let (key, value) = line
+                    .split_once(':')
+                    .ok_or_else(|| format!("line {}: expected 'key: value'", number + 1))?;
+                // Code keeps its indentation, apart from the space after the colon
+                let value = value.strip_prefix(' ').unwrap_or(value);
+                block.push((number + 1, key.trim(), value));
+            }
+            _ if !block.is_empty() => {
+                questions.push(parse_question(&block)?);
+                block.clear();
+            }
+            _ => {}
+        }
+    }
+
+    Ok(questions)
+}
+
+fn parse_question(block: &[(usize, &str, &str)]) -> Result<Question, String> {
+    let start = block[0].0;
+    let error = |message: String| format!("question at line {}: {}", start, message);
+    let one = |key: &str| {
+        block
+            .iter()
+            .find(|(_, k, _)| *k == key)
+            .map(|(_, _, value)| value.trim().to_string())
+    };
+    let all = |key: &str| -> Vec<String> {
+        block
+            .iter()
+            .filter(|(_, k, _)| *k == key)
+            .map(|(_, _, value)| value.to_string())
+            .collect()
+    };
+
+    for (number, key, _) in block {
+        let known = ["kind", "question", "code", "choice", "answer", "lesson", "line", "explain"];
+        if !known.contains(key) {
+            return Err(format!("line {}: unknown key '{}'", number, key));
+        }
+    }
+
+    let question = one("question").ok_or_else(|| error("missing 'question'".into()))?;
+    let answer = one("answer");
+    let kind = match one("kind").as_deref() {
+        Some("choice") => {
+            let options = all("choice");
+            if options.len() < 2 {
+                return Err(error("a choice question needs at least two 'choice' lines".into()));
+            }
+            let answer = match answer.as_deref().map(str::parse::<usize>) {
+                Some(Ok(n)) if (1..=options.len()).contains(&n) => n - 1,
+                _ => return Err(error(format!("'answer' must be a choice from 1 to {}", options.len()))),
+            };
+            Kind::Choice { options, answer }
+        }
+        Some("compiles") => match answer.as_deref() {
+            Some("yes") => Kind::Compiles(true),
+            Some("no") => Kind::Compiles(false),
+            _ => return Err(error("'answer' must be yes or no".into())),
+        },
+        Some("prints") => match (answer, one("lesson"), one("line")) {
+            (Some(text), None, None) => Kind::Prints(Expected::Text(text)),
+            (None, Some(lesson), Some(line)) => Kind::Prints(Expected::LessonLine { lesson, line }),
+            _ => return Err(error("needs either 'answer', or both 'lesson' and 'line'".into())),
+        },
+        Some(other) => return Err(error(format!("unknown kind '{}'", other))),
+        None => return Err(error("missing 'kind'".into())),
+    };
+
+    Ok(Question {
+        kind,
+        question,
+        code: all("code"),
+        explain: one("explain"),
+    })
+}
+
+/// How a quiz went
+#[derive(Debug, PartialEq)]
+pub struct Score {
+    pub correct: usize,
+    pub asked: usize,
+}
+
+/// Asks each question in turn, stopping early if `input` ends
+pub fn run(
+    section: &Section,
+    questions: &[Question],
+    input: &mut dyn BufRead,
+    out: &mut dyn Write,
+) -> io::Result<Score> {
+    writeln!(out, "{} quiz, {} questions", section.banner(), questions.len())?;
+    let mut score = Score { correct: 0, asked: 0 };
+
+    for (number, question) in questions.iter().enumerate() {
+        writeln!(out, "\n{}", "-".repeat(70))?;
+        writeln!(out, "Question {} of {}: {}", number + 1, questions.len(), question.question)?;
+        if !question.code.is_empty() {
+            writeln!(out)?;
+            for line in &question.code {
+                writeln!(out, "    {}", line)?;
+            }
+            writeln!(out)?;
+        }
+
+        let expected = match question.answer() {
+            Ok(expected) => expected,
+            Err(message) => {
+                writeln!(out, "(skipped: {})", message)?;
+                continue;
+            }
+        };
+
+        let hint = match &question.kind {
+            Kind::Choice { options, .. } => {
+                for (i, option) in options.iter().enumerate() {
+                    writeln!(out, "  {}. {}", i + 1, option)?;
+                }
+                format!("Your answer (1-{})", options.len())
+            }
+            Kind::Compiles(_) => "Your answer (yes/no)".to_string(),
+            Kind::Prints(_) => "Your answer".to_string(),
+        };
+
+        let given = match prompt(input, out, &hint)? {
+            Some(given) => given,
+            None => break,
+        };
+        score.asked += 1;
+
+        if question.check(&expected, &given) {
+            score.correct += 1;
+            writeln!(out, "✓ Correct!")?;
+        } else {
+            writeln!(out, "✗ Not quite, the answer is: {}", expected)?;
+        }
+        if let Some(explain) = &question.explain {
+            writeln!(out, "  {}", explain)?;
+        }
+    }
+
+    writeln!(out, "\n{}", "=".repeat(70))?;
+    let percent = (score.correct * 100).checked_div(score.asked).unwrap_or(0);
+    writeln!(out, "Score: {}/{} ({}%)", score.correct, score.asked, percent)?;
+    Ok(score)
+}
+
+// ============================================================================
+// UNIT TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "\
+# A comment
+kind: choice
+question: Pick two
+choice: one
+choice: two
+answer: 2
+
+kind: compiles
+question: Compiles?
+code: let x = 5;
+code:     x += 1;
+answer: no
+
+kind: prints
+question: What does it print?
+answer: hello   world
+";
+
+    static SAMPLE_SECTION: Section = Section {
+        id: "sample",
+        number: 1,
+        title: "SAMPLE",
+        icon: "🧪",
+        lessons: &[],
+    };
+
+    #[test]
+    fn test_parse_kinds() {
+        let questions = parse(SAMPLE).unwrap();
+        assert_eq!(questions.len(), 3);
+        assert_eq!(
+            questions[0].kind,
+            Kind::Choice { options: vec!["one".into(), "two".into()], answer: 1 }
+        );
+        assert_eq!(questions[1].kind, Kind::Compiles(false));
+        assert_eq!(questions[1].code, ["let x = 5;", "    x += 1;"]);
+        assert_eq!(questions[2].kind, Kind::Prints(Expected::Text("hello   world".into())));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse("kind: choice\nquestion: q\nchoice: a\nchoice: b\nanswer: 3\n")
+            .unwrap_err()
+            .contains("line 1"));
+        assert!(parse("kind: compiles\nquestion: q\nanswer: maybe\n").is_err());
+        assert!(parse("kind: prints\nquestion: q\nlesson: basics::x\n").is_err());
+        assert!(parse("kind: compiles\nquestion: q\nanswr: yes\n")
+            .unwrap_err()
+            .contains("unknown key 'answr'"));
+    }
+
+    #[test]
+    fn test_check_answers() {
+        let questions = parse(SAMPLE).unwrap();
+        assert!(questions[0].check("2", " 2 "));
+        assert!(!questions[0].check("2", "1"));
+        assert!(questions[1].check("no", "N"));
+        assert!(!questions[1].check("no", "yes"));
+        assert!(questions[2].check("hello   world", "hello world"));
+    }
+
+    #[test]
+    fn test_every_bank_parses_and_resolves() {
+        for section in registry::SECTIONS {
+            let text = bank(section.id).unwrap_or_else(|| panic!("no quiz for {}", section.id));
+            let questions = parse(text).unwrap_or_else(|e| panic!("{}: {}", section.id, e));
+            assert!(questions.len() >= 3, "{} has too few questions", section.id);
+            for question in &questions {
+                if let Err(message) = question.answer() {
+                    panic!("{}: '{}': {}", section.id, question.question, message);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_run_scores_answers() {
+        let questions = parse(SAMPLE).unwrap();
+        let mut out = Vec::new();
+        let mut input = "2\nyes\nhello world\n".as_bytes();
+        let score = run(&SAMPLE_SECTION, &questions, &mut input, &mut out).unwrap();
+        assert_eq!(score, Score { correct: 2, asked: 3 });
+
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("✗ Not quite, the answer is: no"));
+        assert!(out.ends_with("Score: 2/3 (66%)\n"));
+    }
+}
@@ -0,0 +1,125 @@
+// ============================================================================
+// LESSON REGISTRY
+// ============================================================================
+// The single list of sections, in the recommended learning order. Each module
+// owns its `SECTION` table, so adding an example only touches that module;
+// adding a whole new module means adding one line here.
+//
+// A section is only listed if its Cargo feature is enabled, so a build
+// with some left out simply doesn't offer them.
+// ============================================================================
+
+use crate::lesson::{Lesson, Section};
+
+/// Every section, from basics to advanced
+pub static SECTIONS: &[&Section] = &[
+    #[cfg(feature = "basics")]
+    &crate::basics::SECTION,
+    #[cfg(feature = "ownership")]
+    &crate::ownership::SECTION,
+    #[cfg(feature = "structures")]
+    &crate::structures::SECTION,
+    #[cfg(feature = "traits_generics")]
+    &crate::traits_generics::SECTION,
+    #[cfg(feature = "collections")]
+    &crate::collections::SECTION,
+    #[cfg(feature = "error_handling")]
+    &crate::error_handling::SECTION,
+    #[cfg(feature = "functional")]
+    &crate::functional::SECTION,
+    #[cfg(feature = "smart_pointers")]
+    &crate::smart_pointers::SECTION,
+    #[cfg(feature = "concurrency")]
+    &crate::concurrency::SECTION,
+    #[cfg(feature = "advanced")]
+    &crate::advanced::SECTION,
+];
+
+/// The sections and lesson packs that can be left out of the build, and
+/// whether this one includes them
+pub static FEATURES: &[(&str, bool)] = &[
+    ("basics", cfg!(feature = "basics")),
+    ("ownership", cfg!(feature = "ownership")),
+    ("structures", cfg!(feature = "structures")),
+    ("traits_generics", cfg!(feature = "traits_generics")),
+    ("collections", cfg!(feature = "collections")),
+    ("error_handling", cfg!(feature = "error_handling")),
+    ("functional", cfg!(feature = "functional")),
+    ("smart_pointers", cfg!(feature = "smart_pointers")),
+    ("concurrency", cfg!(feature = "concurrency")),
+    ("advanced", cfg!(feature = "advanced")),
+    ("unsafe-lessons", cfg!(feature = "unsafe-lessons")),
+    ("ffi-lessons", cfg!(feature = "ffi-lessons")),
+];
+
+/// The features this build was compiled without
+pub fn missing_features() -> Vec<&'static str> {
+    FEATURES
+        .iter()
+        .filter(|(_, enabled)| !enabled)
+        .map(|&(feature, _)| feature)
+        .collect()
+}
+
+/// Every lesson of every section, in learning order
+pub fn lessons() -> impl Iterator<Item = &'static dyn Lesson> {
+    SECTIONS.iter().flat_map(|section| section.lessons.iter().copied())
+}
+
+/// Looks up a section by id, e.g. `ownership`
+pub fn section(id: &str) -> Option<&'static Section> {
+    SECTIONS.iter().copied().find(|section| section.id == id)
+}
+
+/// Looks up a lesson by its full id, e.g. `ownership::lifetime_basics`
+pub fn find(id: &str) -> Option<&'static dyn Lesson> {
+    lessons().find(|lesson| lesson.id() == id)
+}
+
+// ============================================================================
+// UNIT TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_sections_are_numbered_in_order() {
+        for pair in SECTIONS.windows(2) {
+            assert!(pair[0].number < pair[1].number, "{} is numbered after {}", pair[0].id, pair[1].id);
+        }
+        // Without gaps, unless some sections are left out of the build
+        if missing_features().is_empty() {
+            for (index, section) in SECTIONS.iter().enumerate() {
+                assert_eq!(section.number as usize, index + 1, "{}", section.id);
+            }
+        }
+    }
+
+    #[test]
+    fn test_lesson_ids_are_unique() {
+        let mut seen = HashSet::new();
+        for lesson in lessons() {
+            assert!(seen.insert(lesson.id()), "duplicate lesson {}", lesson.id());
+        }
+    }
+
+    #[test]
+    fn test_lessons_belong_to_their_section() {
+        for section in SECTIONS {
+            assert!(!section.lessons.is_empty(), "{} has no lessons", section.id);
+            for lesson in section.lessons {
+                assert_eq!(lesson.section(), section.id);
+            }
+        }
+    }
+
+    #[test]
+    fn test_every_section_has_a_feature() {
+        for section in SECTIONS {
+            assert!(FEATURES.contains(&(section.id, true)), "{} has no feature", section.id);
+        }
+    }
+}
@@ -0,0 +1,213 @@
+// ============================================================================
+// RUN REPORTS
+// ============================================================================
+// `happyr run --format json` (or `ndjson`) replaces the usual lesson output
+// with one record per lesson that ran, for dashboards and scripts:
+//
+//     {"id":"basics::control_flow","module":"basics","function":"control_flow",
+//      "title":"Control Flow","status":"ok","panic":null,"duration_ms":0.412,
+//      "output":"\n--- Control Flow ---\n...","stderr":""}
+//
+// `json` writes a single document once the run is over, with the records
+// under "lessons" and timing stats under "stats". `ndjson` writes each record
+// on its own line as soon as the lesson finishes.
+//
+// Without `--isolate`, lessons run in this process with their output
+// captured, and a panic is caught and reported instead of ending the run.
+// ============================================================================
+
+use crate::isolate::{Outcome, Report};
+use crate::json::Value;
+use crate::lesson::Lesson;
+use std::io::{self, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::time::Instant;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Format {
+    /// The lessons' own output, for reading
+    #[default]
+    Text,
+    Json,
+    Ndjson,
+}
+
+impl Format {
+    pub fn parse(name: &str) -> Option<Format> {
+        match name {
+            "text" => Some(Format::Text),
+            "json" => Some(Format::Json),
+            "ndjson" => Some(Format::Ndjson),
+            _ => None,
+        }
+    }
+}
+
+/// Runs a lesson in this process, capturing its output and any panic
+pub fn capture(lesson: &dyn Lesson) -> Report {
+    let mut out = Vec::new();
+    let start = Instant::now();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| lesson.run(&mut out)));
+    let elapsed = start.elapsed();
+
+    let (outcome, stderr) = match result {
+        Ok(Ok(())) => (Outcome::Passed, String::new()),
+        Ok(Err(error)) => (Outcome::Failed(None), format!("error: {}\n", error)),
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|message| message.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "Box<dyn Any>".to_string());
+            (Outcome::Panicked(message), String::new())
+        }
+    };
+
+    Report {
+        id: lesson.id(),
+        outcome,
+        stdout: String::from_utf8_lossy(&out).into_owned(),
+        stderr,
+        elapsed,
+    }
+}
+
+/// One lesson's record
+pub fn record(lesson: &dyn Lesson, report: &Report) -> Value {
+    let (status, panic) = match &report.outcome {
+        Outcome::Passed => ("ok", None),
+        Outcome::Panicked(message) => ("panicked", Some(message.as_str())),
+        Outcome::TimedOut => ("timed_out", None),
+        Outcome::Failed(_) => ("failed", None),
+    };
+    let exit_code = match report.outcome {
+        Outcome::Failed(code) => code.map(|code| Value::Number(code.into())),
+        _ => None,
+    };
+
+    Value::object([
+        ("id", Value::from(report.id.as_str())),
+        ("module", Value::from(lesson.section())),
+        ("function", Value::from(lesson.name())),
+        ("title", Value::from(lesson.title())),
+        ("status", Value::from(status)),
+        ("panic", Value::from(panic)),
+        ("exit_code", exit_code.unwrap_or(Value::Null)),
+        ("duration_ms", Value::from(millis(report))),
+        ("output", Value::from(report.stdout.as_str())),
+        ("stderr", Value::from(report.stderr.as_str())),
+    ])
+}
+
+/// Writes the whole run as one JSON document
+pub fn write_json(records: Vec<Value>, reports: &[Report], out: &mut dyn Write) -> io::Result<()> {
+    let document = Value::object([("lessons", Value::Array(records)), ("stats", stats(reports))]);
+    writeln!(out, "{}", document)
+}
+
+/// Counts by status and duration stats, in milliseconds
+fn stats(reports: &[Report]) -> Value {
+    let times: Vec<f64> = reports.iter().map(millis).collect();
+    let total: f64 = times.iter().sum();
+    let slowest = reports
+        .iter()
+        .zip(&times)
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(report, _)| report.id.as_str());
+    let passed = reports.iter().filter(|report| report.outcome == Outcome::Passed).count();
+
+    Value::object([
+        ("lessons", Value::from(reports.len())),
+        ("passed", Value::from(passed)),
+        ("failed", Value::from(reports.len() - passed)),
+        ("total_ms", Value::from(round(total))),
+        ("mean_ms", Value::from((!times.is_empty()).then(|| round(total / times.len() as f64)))),
+        ("min_ms", Value::from(times.iter().copied().reduce(f64::min))),
+        ("max_ms", Value::from(times.iter().copied().reduce(f64::max))),
+        ("slowest", Value::from(slowest)),
+    ])
+}
+
+fn millis(report: &Report) -> f64 {
+    round(report.elapsed.as_secs_f64() * 1000.0)
+}
+
+/// Rounds to microseconds, which is as precise as the timings are
+fn round(millis: f64) -> f64 {
+    (millis * 1000.0).round() / 1000.0
+}
+
+// ============================================================================
+// UNIT TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lesson::lesson;
+    use std::time::Duration;
+
+    fn says_hi(out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "hi \"there\"")
+    }
+
+    fn gives_up(out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "about to panic")?;
+        panic!("gave up on {}", 42)
+    }
+
+    fn report(id: &str, outcome: Outcome, millis: u64) -> Report {
+        Report {
+            id: id.to_string(),
+            outcome,
+            stdout: String::new(),
+            stderr: String::new(),
+            elapsed: Duration::from_millis(millis),
+        }
+    }
+
+    #[test]
+    fn test_capture_and_record() {
+        let lesson = &lesson!(says_hi, "Says Hi");
+        let mut captured = capture(lesson);
+        assert_eq!(captured.outcome, Outcome::Passed);
+        assert_eq!(captured.stdout, "hi \"there\"\n");
+
+        captured.elapsed = Duration::from_micros(1500);
+        assert_eq!(
+            record(lesson, &captured).to_string(),
+            r#"{"id":"tests::says_hi","module":"tests","function":"says_hi","title":"Says Hi","status":"ok","panic":null,"exit_code":null,"duration_ms":1.5,"output":"hi \"there\"\n","stderr":""}"#
+        );
+    }
+
+    #[test]
+    fn test_capture_catches_panics() {
+        let captured = capture(&lesson!(gives_up, "Gives Up"));
+        assert_eq!(captured.outcome, Outcome::Panicked("gave up on 42".to_string()));
+        assert_eq!(captured.stdout, "about to panic\n");
+    }
+
+    #[test]
+    fn test_json_document_stats() {
+        let reports = [
+            report("a::fast", Outcome::Passed, 1),
+            report("a::slow", Outcome::TimedOut, 5),
+        ];
+        let mut out = Vec::new();
+        write_json(Vec::new(), &reports, &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            r#"{"lessons":[],"stats":{"lessons":2,"passed":1,"failed":1,"total_ms":6,"mean_ms":3,"min_ms":1,"max_ms":5,"slowest":"a::slow"}}"#
+                .to_string()
+                + "\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!(Format::parse("json"), Some(Format::Json));
+        assert_eq!(Format::parse("ndjson"), Some(Format::Ndjson));
+        assert_eq!(Format::parse("text"), Some(Format::Text));
+        assert_eq!(Format::parse("xml"), None);
+    }
+}
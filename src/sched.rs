@@ -0,0 +1,843 @@
+// ============================================================================
+// THREAD SCHEDULING
+// ============================================================================
+// The concurrency lessons use `sched::thread`, `sched::mpsc`, `sched::Mutex`
+// and `sched::Condvar` where they'd normally use std's. By default these are thin
+// wrappers that do exactly what std does, so threads interleave however the
+// OS schedules them and the output changes from run to run.
+//
+// `happyr run --deterministic concurrency` (or `--seed <N>`) swaps in a
+// scheduler that makes every interleaving reproducible:
+//
+// - Threads are still real threads, but only one runs at a time. At each
+//   spawn, sleep, lock, wait, send, receive or join, the scheduler picks
+//   which thread goes next with a random number generator seeded by `--seed`.
+// - A thread that has to wait (for a lock, a message, a condition variable
+//   or another thread) steps aside until something happens that might let
+//   it continue.
+// - `sleep` uses logical time: a sleeping thread is only woken once no
+//   other thread can run, so it costs nothing and always wakes in order.
+// - If every thread is waiting on another, they'd hang forever; here they
+//   panic with a message saying so instead.
+//
+// The same seed always reproduces the same output, and trying a few seeds
+// shows the different ways the threads can interleave.
+// ============================================================================
+
+use std::cell::RefCell;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, LockResult, PoisonError, TryLockError};
+use std::time::Duration;
+
+/// The seed `--deterministic` uses when no `--seed` is given
+pub const DEFAULT_SEED: u64 = 0;
+
+/// How lessons schedule their threads
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Mode {
+    /// Real OS scheduling
+    #[default]
+    Threads,
+    /// One thread at a time, in an order picked by the seed
+    Seeded(u64),
+}
+
+static SEEDED: AtomicBool = AtomicBool::new(false);
+static SEED: AtomicU64 = AtomicU64::new(DEFAULT_SEED);
+
+/// Sets the mode `run` uses for every lesson from now on
+pub fn set_mode(mode: Mode) {
+    if let Mode::Seeded(seed) = mode {
+        SEED.store(seed, Ordering::SeqCst);
+    }
+    SEEDED.store(mode != Mode::Threads, Ordering::SeqCst);
+}
+
+pub fn mode() -> Mode {
+    if SEEDED.load(Ordering::SeqCst) {
+        Mode::Seeded(SEED.load(Ordering::SeqCst))
+    } else {
+        Mode::Threads
+    }
+}
+
+/// Runs `f`, a lesson, in the current mode
+pub fn run<T>(f: impl FnOnce() -> T) -> T {
+    match mode() {
+        Mode::Threads => f(),
+        Mode::Seeded(seed) => run_seeded(seed, f),
+    }
+}
+
+/// Runs `f` with every thread it starts scheduled by `seed`, returning
+/// once they have all finished
+pub fn run_seeded<T>(seed: u64, f: impl FnOnce() -> T) -> T {
+    if current().is_some() {
+        return f();
+    }
+
+    /// Lets the threads `f` left behind finish, even if `f` panicked
+    struct Finish(Arc<Scheduler>);
+
+    impl Drop for Finish {
+        fn drop(&mut self) {
+            self.0.finish(ROOT);
+            self.0.wait_until_all_finished();
+            set_current(None);
+        }
+    }
+
+    let scheduler = Scheduler::new(seed);
+    set_current(Some((Arc::clone(&scheduler), ROOT)));
+    let _finish = Finish(scheduler);
+    f()
+}
+
+// ----------------------------------------------------------------------------
+// The scheduler
+// ----------------------------------------------------------------------------
+
+/// The task that called `run_seeded`
+const ROOT: usize = 0;
+
+const DEADLOCK: &str = "deadlock: every thread is waiting for another one";
+
+/// Passes a single turn between the threads of one seeded run
+struct Scheduler {
+    state: std::sync::Mutex<State>,
+    /// Signalled whenever the turn moves or a task finishes
+    turn: std::sync::Condvar,
+}
+
+struct State {
+    rng: u64,
+    /// Logical time since the run started
+    now: Duration,
+    /// Indexed by task id
+    tasks: Vec<Task>,
+    /// The task whose turn it is
+    current: usize,
+    deadlocked: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Task {
+    Runnable,
+    /// Waiting for a lock, a message or another task
+    Blocked,
+    /// Asleep until this logical time
+    Sleeping(Duration),
+    /// Blocked, but gives up waiting at this logical time
+    BlockedUntil(Duration),
+    Finished,
+}
+
+thread_local! {
+    /// The scheduler running this thread, and its task id there
+    static CURRENT: RefCell<Option<(Arc<Scheduler>, usize)>> = const { RefCell::new(None) };
+}
+
+fn current() -> Option<(Arc<Scheduler>, usize)> {
+    CURRENT.with(|current| current.borrow().clone())
+}
+
+fn set_current(value: Option<(Arc<Scheduler>, usize)>) {
+    CURRENT.with(|current| *current.borrow_mut() = value);
+}
+
+impl State {
+    /// The next number from a splitmix64 generator
+    fn random(&mut self) -> u64 {
+        self.rng = self.rng.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.rng;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Gives the turn to a runnable task picked at random, moving the clock
+    /// forward to wake sleepers if none is. Records a deadlock if the only
+    /// tasks left are blocked.
+    fn pick(&mut self) {
+        loop {
+            let runnable: Vec<usize> = (0..self.tasks.len())
+                .filter(|&task| self.tasks[task] == Task::Runnable)
+                .collect();
+            if !runnable.is_empty() {
+                self.current = runnable[(self.random() % runnable.len() as u64) as usize];
+                return;
+            }
+
+            let wake = self
+                .tasks
+                .iter()
+                .filter_map(|task| match task {
+                    Task::Sleeping(at) | Task::BlockedUntil(at) => Some(*at),
+                    _ => None,
+                })
+                .min();
+            let Some(wake) = wake else {
+                self.deadlocked = self.tasks.contains(&Task::Blocked);
+                return;
+            };
+            self.now = wake;
+            for task in &mut self.tasks {
+                if let Task::Sleeping(at) | Task::BlockedUntil(at) = *task {
+                    if at == wake {
+                        *task = Task::Runnable;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Something happened that a blocked task may have been waiting for
+    fn wake_blocked(&mut self) {
+        for task in &mut self.tasks {
+            if let Task::Blocked | Task::BlockedUntil(_) = task {
+                *task = Task::Runnable;
+            }
+        }
+    }
+}
+
+impl Scheduler {
+    fn new(seed: u64) -> Arc<Scheduler> {
+        Arc::new(Scheduler {
+            state: std::sync::Mutex::new(State {
+                rng: seed,
+                now: Duration::ZERO,
+                tasks: vec![Task::Runnable],
+                current: ROOT,
+                deadlocked: false,
+            }),
+            turn: std::sync::Condvar::new(),
+        })
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        // A task that panicked never does so while holding the state
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn add_task(&self) -> usize {
+        let mut state = self.lock();
+        state.tasks.push(Task::Runnable);
+        state.tasks.len() - 1
+    }
+
+    /// Puts `me` in the state `next` gives, hands the turn to whichever
+    /// task the seed picks, and returns when it's `me`'s turn again
+    fn switch(&self, me: usize, next: impl FnOnce(&State) -> Task) {
+        let mut state = self.lock();
+        state.tasks[me] = next(&state);
+        state.pick();
+        self.turn.notify_all();
+        self.wait_turn(state, me);
+    }
+
+    fn wait_turn(&self, mut state: std::sync::MutexGuard<'_, State>, me: usize) {
+        loop {
+            if state.deadlocked {
+                drop(state);
+                panic!("{}", DEADLOCK);
+            }
+            if state.current == me && state.tasks[me] == Task::Runnable {
+                return;
+            }
+            state = self.turn.wait(state).unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    fn finish(&self, me: usize) {
+        let mut state = self.lock();
+        state.tasks[me] = Task::Finished;
+        state.wake_blocked();
+        if !state.deadlocked {
+            state.pick();
+        }
+        self.turn.notify_all();
+    }
+
+    fn wait_until_all_finished(&self) {
+        let mut state = self.lock();
+        while state.tasks.iter().any(|task| *task != Task::Finished) {
+            state = self.turn.wait(state).unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    /// Steps aside until `task` has finished
+    fn join(&self, me: usize, task: usize) {
+        while self.lock().tasks[task] != Task::Finished {
+            self.switch(me, |_| Task::Blocked);
+        }
+    }
+
+    /// The body of a spawned task's thread
+    fn run_task<T>(self: Arc<Self>, id: usize, f: impl FnOnce() -> T) -> T {
+        set_current(Some((Arc::clone(&self), id)));
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            self.wait_turn(self.lock(), id);
+            f()
+        }));
+        self.finish(id);
+        set_current(None);
+        result.unwrap_or_else(|payload| panic::resume_unwind(payload))
+    }
+}
+
+/// Gives other tasks a chance to run first, in seeded mode
+fn yield_turn() {
+    if let Some((scheduler, me)) = current() {
+        scheduler.switch(me, |_| Task::Runnable);
+    }
+}
+
+/// Lets blocked tasks try again, in seeded mode
+fn wake_blocked() {
+    if let Some((scheduler, _)) = current() {
+        scheduler.lock().wake_blocked();
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Threads
+// ----------------------------------------------------------------------------
+
+/// Stand-ins for the parts of `std::thread` the lessons use
+pub mod thread {
+    use super::{current, Scheduler, Task};
+    use std::cell::RefCell;
+    use std::io;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    pub struct JoinHandle<T> {
+        inner: std::thread::JoinHandle<T>,
+        task: Option<(Arc<Scheduler>, usize)>,
+    }
+
+    impl<T> JoinHandle<T> {
+        pub fn join(self) -> std::thread::Result<T> {
+            if let (Some((scheduler, task)), Some((_, me))) = (&self.task, current()) {
+                scheduler.join(me, *task);
+            }
+            self.inner.join()
+        }
+    }
+
+    pub fn spawn<F, T>(f: F) -> JoinHandle<T>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        Builder::new().spawn(f).expect("failed to spawn thread")
+    }
+
+    /// Like `std::thread::Builder`, for spawning a named thread
+    #[derive(Debug, Default)]
+    pub struct Builder {
+        name: Option<String>,
+    }
+
+    impl Builder {
+        pub fn new() -> Builder {
+            Builder::default()
+        }
+
+        pub fn name(self, name: String) -> Builder {
+            Builder { name: Some(name) }
+        }
+
+        pub fn spawn<F, T>(self, f: F) -> io::Result<JoinHandle<T>>
+        where
+            F: FnOnce() -> T + Send + 'static,
+            T: Send + 'static,
+        {
+            let mut builder = std::thread::Builder::new();
+            if let Some(name) = self.name {
+                builder = builder.name(name);
+            }
+            let Some((scheduler, me)) = current() else {
+                return Ok(JoinHandle {
+                    inner: builder.spawn(f)?,
+                    task: None,
+                });
+            };
+            let id = scheduler.add_task();
+            let task = Arc::clone(&scheduler);
+            let inner = match builder.spawn(move || task.run_task(id, f)) {
+                Ok(inner) => inner,
+                Err(error) => {
+                    // The task never started, so nobody should wait for it
+                    scheduler.lock().tasks[id] = Task::Finished;
+                    return Err(error);
+                }
+            };
+            scheduler.switch(me, |_| Task::Runnable);
+            Ok(JoinHandle {
+                inner,
+                task: Some((scheduler, id)),
+            })
+        }
+    }
+
+    pub fn sleep(duration: Duration) {
+        match current() {
+            None => std::thread::sleep(duration),
+            Some((scheduler, me)) => scheduler.switch(me, |state| Task::Sleeping(state.now + duration)),
+        }
+    }
+
+    /// Like `std::thread::Scope`, handed to the closure given to `scope`
+    pub struct Scope<'scope, 'env: 'scope> {
+        inner: &'scope std::thread::Scope<'scope, 'env>,
+        /// The seeded tasks spawned in it
+        tasks: RefCell<Vec<usize>>,
+    }
+
+    pub fn scope<'env, F, T>(f: F) -> T
+    where
+        F: for<'scope> FnOnce(&Scope<'scope, 'env>) -> T,
+    {
+        std::thread::scope(|inner| {
+            let scope = Scope {
+                inner,
+                tasks: RefCell::new(Vec::new()),
+            };
+            f(&scope)
+        })
+    }
+
+    impl<'scope> Scope<'scope, '_> {
+        /// Unlike std's, doesn't hand back a handle: the scope joins every
+        /// thread when it ends, and the lessons need nothing more
+        pub fn spawn<F, T>(&self, f: F)
+        where
+            F: FnOnce() -> T + Send + 'scope,
+            T: Send + 'scope,
+        {
+            let Some((scheduler, me)) = current() else {
+                self.inner.spawn(f);
+                return;
+            };
+            let id = scheduler.add_task();
+            self.tasks.borrow_mut().push(id);
+            let task = Arc::clone(&scheduler);
+            self.inner.spawn(move || task.run_task(id, f));
+            scheduler.switch(me, |_| Task::Runnable);
+        }
+    }
+
+    /// std joins a scope's threads when it ends, which would block while
+    /// holding the turn, so the scheduler waits for them first
+    impl Drop for Scope<'_, '_> {
+        fn drop(&mut self) {
+            if let Some((scheduler, me)) = current() {
+                for &task in self.tasks.borrow().iter() {
+                    scheduler.join(me, task);
+                }
+            }
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Channels
+// ----------------------------------------------------------------------------
+
+/// Stand-ins for `std::sync::mpsc`
+pub mod mpsc {
+    use super::{current, wake_blocked, yield_turn, Task};
+    use std::sync::mpsc as std_mpsc;
+    use std::sync::mpsc::TryRecvError;
+
+    pub use std::sync::mpsc::{RecvError, SendError};
+
+    pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
+        let (tx, rx) = std_mpsc::channel();
+        (Sender(tx), Receiver(rx))
+    }
+
+    pub struct Sender<T>(std_mpsc::Sender<T>);
+
+    impl<T> Sender<T> {
+        pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+            let result = self.0.send(value);
+            wake_blocked();
+            yield_turn();
+            result
+        }
+    }
+
+    impl<T> Clone for Sender<T> {
+        fn clone(&self) -> Sender<T> {
+            Sender(self.0.clone())
+        }
+    }
+
+    /// The last sender going away ends the receiver's wait
+    impl<T> Drop for Sender<T> {
+        fn drop(&mut self) {
+            wake_blocked();
+        }
+    }
+
+    pub struct Receiver<T>(std_mpsc::Receiver<T>);
+
+    impl<T> Receiver<T> {
+        pub fn recv(&self) -> Result<T, RecvError> {
+            let Some((scheduler, me)) = current() else {
+                return self.0.recv();
+            };
+            loop {
+                match self.0.try_recv() {
+                    Ok(value) => return Ok(value),
+                    Err(TryRecvError::Disconnected) => return Err(RecvError),
+                    Err(TryRecvError::Empty) => scheduler.switch(me, |_| Task::Blocked),
+                }
+            }
+        }
+
+        pub fn iter(&self) -> Iter<'_, T> {
+            Iter { rx: self }
+        }
+    }
+
+    /// Receives until every sender is gone
+    pub struct Iter<'a, T> {
+        rx: &'a Receiver<T>,
+    }
+
+    impl<T> Iterator for Iter<'_, T> {
+        type Item = T;
+
+        fn next(&mut self) -> Option<T> {
+            self.rx.recv().ok()
+        }
+    }
+
+    pub struct IntoIter<T> {
+        rx: Receiver<T>,
+    }
+
+    impl<T> Iterator for IntoIter<T> {
+        type Item = T;
+
+        fn next(&mut self) -> Option<T> {
+            self.rx.recv().ok()
+        }
+    }
+
+    impl<T> IntoIterator for Receiver<T> {
+        type Item = T;
+        type IntoIter = IntoIter<T>;
+
+        fn into_iter(self) -> IntoIter<T> {
+            IntoIter { rx: self }
+        }
+    }
+
+    impl<'a, T> IntoIterator for &'a Receiver<T> {
+        type Item = T;
+        type IntoIter = Iter<'a, T>;
+
+        fn into_iter(self) -> Iter<'a, T> {
+            self.iter()
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Locks
+// ----------------------------------------------------------------------------
+
+/// A stand-in for `std::sync::Mutex`
+pub struct Mutex<T>(std::sync::Mutex<T>);
+
+pub struct MutexGuard<'a, T> {
+    /// Only `None` while a `Condvar` is waiting with std's guard
+    inner: Option<std::sync::MutexGuard<'a, T>>,
+    mutex: &'a Mutex<T>,
+}
+
+impl<T> Mutex<T> {
+    pub fn new(value: T) -> Mutex<T> {
+        Mutex(std::sync::Mutex::new(value))
+    }
+
+    pub fn lock(&self) -> LockResult<MutexGuard<'_, T>> {
+        let Some((scheduler, me)) = current() else {
+            return self.guard(self.0.lock());
+        };
+        // Another thread may get to the lock first
+        scheduler.switch(me, |_| Task::Runnable);
+        loop {
+            match self.0.try_lock() {
+                Ok(guard) => return self.guard(Ok(guard)),
+                Err(TryLockError::Poisoned(poisoned)) => return self.guard(Err(poisoned)),
+                Err(TryLockError::WouldBlock) => scheduler.switch(me, |_| Task::Blocked),
+            }
+        }
+    }
+
+    fn guard<'a>(&'a self, result: LockResult<std::sync::MutexGuard<'a, T>>) -> LockResult<MutexGuard<'a, T>> {
+        let wrap = |inner| MutexGuard {
+            inner: Some(inner),
+            mutex: self,
+        };
+        result.map(wrap).map_err(|poisoned| PoisonError::new(wrap(poisoned.into_inner())))
+    }
+}
+
+/// Prints like std's, e.g. `Mutex { data: 6, poisoned: false, .. }`
+impl<T: fmt::Debug> fmt::Debug for Mutex<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<T> Deref for MutexGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.inner.as_ref().expect("the guard is only empty while waiting")
+    }
+}
+
+impl<T> DerefMut for MutexGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.inner.as_mut().expect("the guard is only empty while waiting")
+    }
+}
+
+/// Unlocking lets the tasks waiting for the lock try again
+impl<T> Drop for MutexGuard<'_, T> {
+    fn drop(&mut self) {
+        wake_blocked();
+    }
+}
+
+/// A stand-in for `std::sync::Condvar`
+///
+/// In seeded mode a waiting thread steps aside until another one notifies
+/// or unlocks something, which may wake it when its condition still doesn't
+/// hold: like std's, it's meant for loops that check again, such as
+/// `wait_while`.
+#[derive(Debug, Default)]
+pub struct Condvar(std::sync::Condvar);
+
+/// Whether `Condvar::wait_timeout_while` gave up waiting
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WaitTimeoutResult(bool);
+
+impl WaitTimeoutResult {
+    pub fn timed_out(&self) -> bool {
+        self.0
+    }
+}
+
+impl Condvar {
+    pub fn new() -> Condvar {
+        Condvar::default()
+    }
+
+    /// Unlocks `guard`'s mutex until notified, then locks it again
+    pub fn wait<'a, T>(&self, mut guard: MutexGuard<'a, T>) -> LockResult<MutexGuard<'a, T>> {
+        let Some((scheduler, me)) = current() else {
+            let mutex = guard.mutex;
+            let inner = guard.inner.take().expect("the guard is only empty while waiting");
+            return mutex.guard(self.0.wait(inner));
+        };
+        let mutex = guard.mutex;
+        drop(guard);
+        scheduler.switch(me, |_| Task::Blocked);
+        mutex.lock()
+    }
+
+    /// Waits for as long as `condition` holds
+    pub fn wait_while<'a, T>(
+        &self,
+        mut guard: MutexGuard<'a, T>,
+        mut condition: impl FnMut(&mut T) -> bool,
+    ) -> LockResult<MutexGuard<'a, T>> {
+        while condition(&mut *guard) {
+            guard = self.wait(guard)?;
+        }
+        Ok(guard)
+    }
+
+    /// Waits for as long as `condition` holds, but no longer than
+    /// `timeout`, which is logical time in seeded mode
+    pub fn wait_timeout_while<'a, T>(
+        &self,
+        mut guard: MutexGuard<'a, T>,
+        timeout: Duration,
+        mut condition: impl FnMut(&mut T) -> bool,
+    ) -> LockResult<(MutexGuard<'a, T>, WaitTimeoutResult)> {
+        let Some((scheduler, me)) = current() else {
+            let mutex = guard.mutex;
+            let inner = guard.inner.take().expect("the guard is only empty while waiting");
+            let (inner, result) = match self.0.wait_timeout_while(inner, timeout, condition) {
+                Ok(waited) => (Ok(waited.0), waited.1.timed_out()),
+                Err(poisoned) => {
+                    let (inner, result) = poisoned.into_inner();
+                    (Err(PoisonError::new(inner)), result.timed_out())
+                }
+            };
+            return match mutex.guard(inner) {
+                Ok(guard) => Ok((guard, WaitTimeoutResult(result))),
+                Err(poisoned) => Err(PoisonError::new((poisoned.into_inner(), WaitTimeoutResult(result)))),
+            };
+        };
+        let deadline = scheduler.lock().now + timeout;
+        loop {
+            if !condition(&mut *guard) {
+                return Ok((guard, WaitTimeoutResult(false)));
+            }
+            if scheduler.lock().now >= deadline {
+                return Ok((guard, WaitTimeoutResult(true)));
+            }
+            let mutex = guard.mutex;
+            drop(guard);
+            scheduler.switch(me, |_| Task::BlockedUntil(deadline));
+            guard = match mutex.lock() {
+                Ok(guard) => guard,
+                Err(poisoned) => return Err(PoisonError::new((poisoned.into_inner(), WaitTimeoutResult(false)))),
+            };
+        }
+    }
+
+    pub fn notify_one(&self) {
+        self.0.notify_one();
+        wake_blocked();
+    }
+
+    pub fn notify_all(&self) {
+        self.0.notify_all();
+        wake_blocked();
+    }
+}
+
+// ============================================================================
+// UNIT TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    /// Three threads each log their name twice, with a sleep in between
+    fn interleaving(seed: u64) -> Vec<String> {
+        run_seeded(seed, || {
+            let log = Arc::new(Mutex::new(Vec::new()));
+            let handles: Vec<_> = ["a", "b", "c"]
+                .into_iter()
+                .map(|name| {
+                    let log = Arc::clone(&log);
+                    thread::spawn(move || {
+                        for i in 1..=2 {
+                            log.lock().unwrap().push(format!("{}{}", name, i));
+                            thread::sleep(Duration::from_millis(1));
+                        }
+                    })
+                })
+                .collect();
+            for handle in handles {
+                handle.join().unwrap();
+            }
+            let order = log.lock().unwrap().clone();
+            order
+        })
+    }
+
+    #[test]
+    fn test_same_seed_same_interleaving() {
+        assert_eq!(interleaving(7), interleaving(7));
+        let orders: std::collections::HashSet<_> = (0..20).map(interleaving).collect();
+        assert!(orders.len() > 1, "every seed gave the same order");
+        // Logical time still orders the sleeps: everyone's first line
+        // comes before anyone's second
+        for order in orders {
+            assert!(order[..3].iter().all(|entry| entry.ends_with('1')), "{:?}", order);
+        }
+    }
+
+    #[test]
+    fn test_channels_and_sleeps_take_no_real_time() {
+        let start = std::time::Instant::now();
+        let received: Vec<u64> = run_seeded(DEFAULT_SEED, || {
+            let (tx, rx) = mpsc::channel();
+            for id in 0..3 {
+                let tx = tx.clone();
+                thread::spawn(move || {
+                    thread::sleep(Duration::from_secs(60 * (3 - id)));
+                    tx.send(id).unwrap();
+                });
+            }
+            drop(tx);
+            rx.iter().collect()
+        });
+        assert_eq!(received, [2, 1, 0]);
+        assert!(start.elapsed() < Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_scoped_threads_finish_before_the_scope_ends() {
+        let mut data = vec![1, 2, 3];
+        let sum = Mutex::new(0);
+        run_seeded(DEFAULT_SEED, || {
+            thread::scope(|s| {
+                for chunk in data.chunks(1) {
+                    let sum = &sum;
+                    s.spawn(move || *sum.lock().unwrap() += chunk[0]);
+                }
+            })
+        });
+        data.push(4);
+        assert_eq!(*sum.lock().unwrap(), 6);
+    }
+
+    #[test]
+    fn test_condvar_timeouts_use_logical_time() {
+        let start = std::time::Instant::now();
+        let woken = run_seeded(DEFAULT_SEED, || {
+            let pair = Arc::new((Mutex::new(0), Condvar::new()));
+            let (lock, ready) = &*pair;
+            let (_, waited) = ready
+                .wait_timeout_while(lock.lock().unwrap(), Duration::from_secs(60), |n| *n == 0)
+                .unwrap();
+            assert!(waited.timed_out());
+
+            let setter = Arc::clone(&pair);
+            thread::spawn(move || {
+                thread::sleep(Duration::from_secs(1));
+                *setter.0.lock().unwrap() = 7;
+                setter.1.notify_all();
+            });
+            let (guard, waited) = ready
+                .wait_timeout_while(lock.lock().unwrap(), Duration::from_secs(60), |n| *n == 0)
+                .unwrap();
+            (waited.timed_out(), *guard)
+        });
+        assert_eq!(woken, (false, 7));
+        assert!(start.elapsed() < Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_deadlock_panics_instead_of_hanging() {
+        let result = panic::catch_unwind(|| {
+            run_seeded(DEFAULT_SEED, || {
+                let (_tx, rx) = mpsc::channel::<()>();
+                rx.recv()
+            })
+        });
+        let payload = result.unwrap_err();
+        assert_eq!(payload.downcast_ref::<String>().map(String::as_str), Some(DEADLOCK));
+        assert!(current().is_none());
+    }
+}
@@ -0,0 +1,365 @@
+// ============================================================================
+// SEARCH
+// ============================================================================
+// `happyr search <QUERY>` finds lessons by what they teach: "where is Weak
+// explained", "which example uses scan". It looks words up in an inverted
+// index, a map from each word to the lessons containing it, built from:
+//
+//     title and tags     weighted highest
+//     `///` doc comment  the explanation
+//     source code        identifiers such as `scan` or `HashMap`
+//     output             what the lesson prints
+//
+// Identifiers are also indexed by their parts, so `HashMap` is found by
+// "hash" and `first_word` by "word". Results are ranked by how many of the
+// query's words they contain, then by a score favouring rare words.
+//
+// Building the index means running every lesson to see its output, so it's
+// cached in `~/.happyr/search-index` and rebuilt when the lessons change.
+// ============================================================================
+
+use crate::lesson::Lesson;
+use crate::report;
+use crate::source;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// How much one occurrence of a word counts, by where it appears
+const TITLE: u32 = 10;
+const TAG: u32 = 8;
+const DOC: u32 = 4;
+const CODE: u32 = 2;
+const OUTPUT: u32 = 1;
+
+/// Words too common in the lessons, or in questions about them, to say
+/// anything about what a lesson teaches
+const STOP_WORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "be", "by", "example", "explain", "explained", "fn", "for", "how",
+    "in", "io", "is", "it", "let", "mut", "of", "ok", "on", "or", "out", "pub", "the", "this", "to",
+    "uses", "what", "where", "which", "with", "write", "writeln",
+];
+
+/// Which lessons contain which words, and how prominently
+#[derive(Debug, PartialEq)]
+pub struct Index {
+    /// Lesson ids, in the order they were indexed
+    lessons: Vec<String>,
+    /// Each word's postings: the lesson's position in `lessons`, and the
+    /// summed weight of the word's occurrences in it
+    terms: BTreeMap<String, Vec<(usize, u32)>>,
+}
+
+/// A lesson matching a query
+#[derive(Debug, PartialEq)]
+pub struct Hit {
+    pub id: String,
+    /// How many of the query's words it contains
+    pub matched: usize,
+    pub score: f64,
+}
+
+impl Index {
+    pub fn build<'a>(lessons: impl IntoIterator<Item = &'a dyn Lesson>) -> Index {
+        let mut index = Index {
+            lessons: Vec::new(),
+            terms: BTreeMap::new(),
+        };
+        for lesson in lessons {
+            let position = index.lessons.len();
+            index.lessons.push(lesson.id());
+            index.add(position, lesson.title(), TITLE);
+            index.add(position, lesson.name(), TITLE);
+            for tag in lesson.tags() {
+                index.add(position, tag, TAG);
+            }
+            if let Some(example) = source::example(lesson) {
+                index.add(position, &example.doc, DOC);
+                index.add(position, example.code, CODE);
+            }
+            index.add(position, &report::capture(lesson).stdout, OUTPUT);
+        }
+        index
+    }
+
+    fn add(&mut self, lesson: usize, text: &str, weight: u32) {
+        for word in words(text) {
+            let postings = self.terms.entry(word).or_default();
+            match postings.last_mut() {
+                Some((last, total)) if *last == lesson => *total += weight,
+                _ => postings.push((lesson, weight)),
+            }
+        }
+    }
+
+    /// Ranked lessons containing any of the query's words
+    ///
+    /// A query word of three letters or more also matches longer words it
+    /// starts, at half weight, so "iter" finds `iterator`.
+    pub fn search(&self, query: &str) -> Vec<Hit> {
+        let mut query_words = words(query);
+        if query_words.is_empty() {
+            // Nothing but stop words: search for them anyway
+            query_words = query.split_whitespace().map(str::to_lowercase).collect();
+        }
+        query_words.sort();
+        query_words.dedup();
+
+        let total = self.lessons.len() as f64;
+        let mut found: HashMap<usize, (usize, f64)> = HashMap::new();
+        for word in &query_words {
+            let mut scores: HashMap<usize, f64> = HashMap::new();
+            let prefixed = self
+                .terms
+                .range(word.clone()..)
+                .take_while(|(term, _)| term.starts_with(word.as_str()))
+                .filter(|(term, _)| *term == word || word.len() >= 3);
+            for (term, postings) in prefixed {
+                let rarity = (1.0 + total / postings.len() as f64).ln();
+                let exact = if term == word { 1.0 } else { 0.5 };
+                for &(lesson, weight) in postings {
+                    *scores.entry(lesson).or_default() += weight as f64 * rarity * exact;
+                }
+            }
+            for (lesson, score) in scores {
+                let entry = found.entry(lesson).or_default();
+                entry.0 += 1;
+                entry.1 += score;
+            }
+        }
+
+        let mut hits: Vec<Hit> = found
+            .into_iter()
+            .map(|(lesson, (matched, score))| Hit {
+                id: self.lessons[lesson].clone(),
+                matched,
+                score,
+            })
+            .collect();
+        hits.sort_by(|a, b| {
+            b.matched
+                .cmp(&a.matched)
+                .then(b.score.total_cmp(&a.score))
+                .then(a.id.cmp(&b.id))
+        });
+        hits
+    }
+
+    /// The cache file's contents: a header with `key`, the lesson ids, then
+    /// one line per word with its postings
+    fn to_text(&self, key: u64) -> String {
+        let mut text = format!("happyr search index {:016x}\n{}\n", key, self.lessons.join(" "));
+        for (term, postings) in &self.terms {
+            let postings: Vec<String> = postings
+                .iter()
+                .map(|(lesson, weight)| format!("{}:{}", lesson, weight))
+                .collect();
+            text.push_str(&format!("{}\t{}\n", term, postings.join(" ")));
+        }
+        text
+    }
+
+    /// Reads a cache file, or `None` if it's malformed or for another `key`
+    fn parse(text: &str, key: u64) -> Option<Index> {
+        let mut lines = text.lines();
+        if lines.next()? != format!("happyr search index {:016x}", key) {
+            return None;
+        }
+        let lessons: Vec<String> = lines.next()?.split(' ').map(str::to_string).collect();
+
+        let mut terms = BTreeMap::new();
+        for line in lines {
+            let (term, postings) = line.split_once('\t')?;
+            let postings = postings
+                .split(' ')
+                .map(|posting| {
+                    let (lesson, weight) = posting.split_once(':')?;
+                    let lesson: usize = lesson.parse().ok()?;
+                    (lesson < lessons.len()).then_some(())?;
+                    Some((lesson, weight.parse().ok()?))
+                })
+                .collect::<Option<Vec<_>>>()?;
+            terms.insert(term.to_string(), postings);
+        }
+        Some(Index { lessons, terms })
+    }
+}
+
+/// Loads the index from `cache` if it's up to date, or builds it and saves
+/// it there
+pub fn load_or_build(lessons: &[&'static dyn Lesson], cache: Option<&Path>) -> Index {
+    let key = fingerprint(lessons);
+    if let Some(index) = cache
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|text| Index::parse(&text, key))
+    {
+        return index;
+    }
+
+    let index = Index::build(lessons.iter().copied());
+    if let Some(path) = cache {
+        // A cache that can't be written just means building again next time
+        let _ = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(path, index.to_text(key)));
+    }
+    index
+}
+
+/// Changes whenever a lesson, its source or this version of happyr does
+///
+/// The key is saved with the index, so it's an FNV-1a hash spelled out here:
+/// `DefaultHasher` may hash differently after a Rust upgrade.
+fn fingerprint(lessons: &[&dyn Lesson]) -> u64 {
+    let mut key: u64 = 0xcbf2_9ce4_8422_2325;
+    let mut add = |text: &str| {
+        // 0xff never appears in UTF-8, so it keeps "ab" + "c" apart from "a" + "bc"
+        for byte in text.bytes().chain([0xff]) {
+            key = (key ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+    };
+
+    add(env!("CARGO_PKG_VERSION"));
+    let mut section = None;
+    for lesson in lessons {
+        // The lessons come a section at a time, so each source is read once
+        if section != Some(lesson.section()) {
+            section = Some(lesson.section());
+            add(source::module(lesson.section()).unwrap_or(""));
+        }
+        add(&lesson.id());
+        add(lesson.title());
+        add(&lesson.tags().join(","));
+    }
+    key
+}
+
+/// Splits text into lowercase words, adding the parts of `snake_case` and
+/// `CamelCase` identifiers
+fn words(text: &str) -> Vec<String> {
+    let keep = |word: &str| {
+        word.len() >= 2 && !STOP_WORDS.contains(&word) && !word.chars().all(|c| c.is_ascii_digit())
+    };
+
+    let mut words = Vec::new();
+    for identifier in text.split(|c: char| !c.is_alphanumeric() && c != '_') {
+        let whole = identifier.trim_matches('_').to_lowercase();
+        let parts = identifier_parts(identifier);
+        if keep(&whole) {
+            words.push(whole);
+        }
+        if parts.len() > 1 {
+            words.extend(parts.into_iter().filter(|part| keep(part)));
+        }
+    }
+    words
+}
+
+fn identifier_parts(identifier: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    for piece in identifier.split('_').filter(|piece| !piece.is_empty()) {
+        let mut part = String::new();
+        let mut previous_lower = false;
+        for c in piece.chars() {
+            if c.is_uppercase() && previous_lower {
+                parts.push(std::mem::take(&mut part));
+            }
+            previous_lower = c.is_lowercase() || c.is_ascii_digit();
+            part.extend(c.to_lowercase());
+        }
+        parts.push(part);
+    }
+    parts
+}
+
+/// Writes the hits, numbered, with the line of each lesson's doc comment
+/// or code that best shows why it matched
+pub fn write_results(query: &str, hits: &[(Hit, &dyn Lesson)], out: &mut dyn Write) -> io::Result<()> {
+    if hits.is_empty() {
+        return writeln!(out, "No lessons match \"{}\".", query);
+    }
+    writeln!(out, "{} lesson(s) match \"{}\":\n", hits.len(), query)?;
+    let query_words = words(query);
+    for (number, (hit, lesson)) in hits.iter().enumerate() {
+        writeln!(out, "{:>3}. {:<45} {}", number + 1, hit.id, lesson.title())?;
+        if let Some(line) = snippet(*lesson, &query_words) {
+            writeln!(out, "       {}", line)?;
+        }
+    }
+    Ok(())
+}
+
+fn snippet(lesson: &dyn Lesson, query_words: &[String]) -> Option<String> {
+    let example = source::example(lesson)?;
+    let lines = example.doc.lines().chain(example.code.lines().skip(1));
+    lines
+        .map(str::trim)
+        .find(|line| {
+            let line = line.to_lowercase();
+            query_words.iter().any(|word| line.contains(word.as_str()))
+        })
+        .map(|line| line.chars().take(70).collect())
+}
+
+// ============================================================================
+// UNIT TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lesson::lesson;
+
+    fn prints_maps(out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "A HashMap of scores")
+    }
+
+    fn prints_lists(out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "A Vec of scores, and another Vec")
+    }
+
+    fn small_index() -> Index {
+        Index::build([
+            &lesson!(prints_maps, "Hash Maps") as &dyn Lesson,
+            &lesson!(prints_lists, "Vectors"),
+        ])
+    }
+
+    #[test]
+    fn test_words() {
+        assert_eq!(words("Weak<T> and HashMap<K, V>"), ["weak", "hashmap", "hash", "map"]);
+        assert_eq!(words("first_word(&s) is 42"), ["first_word", "first", "word"]);
+    }
+
+    #[test]
+    fn test_ranking() {
+        let index = small_index();
+        let ids = |query| index.search(query).into_iter().map(|hit| hit.id).collect::<Vec<_>>();
+        assert_eq!(ids("hash"), ["tests::prints_maps"]);
+        assert_eq!(ids("vec scores"), ["tests::prints_lists", "tests::prints_maps"]);
+        assert_eq!(ids("vect"), ["tests::prints_lists"]);
+        assert_eq!(ids("vec scores vec"), ["tests::prints_lists", "tests::prints_maps"]);
+        assert_eq!(index.search("vec scores vec")[0].matched, 2);
+        assert!(ids("zebra").is_empty());
+    }
+
+    #[test]
+    fn test_cache_round_trip() {
+        let index = small_index();
+        let text = index.to_text(7);
+        assert_eq!(Index::parse(&text, 7), Some(index));
+        assert_eq!(Index::parse(&text, 8), None);
+        assert_eq!(Index::parse("happyr search index 0000000000000007\nx\nterm\t5:1\n", 7), None);
+    }
+
+    #[test]
+    #[cfg(all(feature = "smart_pointers", feature = "functional"))]
+    fn test_finds_lessons_by_code_and_doc() {
+        let lessons: Vec<&dyn Lesson> = crate::registry::lessons().collect();
+        let index = load_or_build(&lessons, None);
+        assert_eq!(index.search("weak")[0].id, "smart_pointers::weak_references");
+        assert_eq!(index.search("scan")[0].id, "functional::functional_patterns");
+    }
+}
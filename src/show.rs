@@ -0,0 +1,330 @@
+// ============================================================================
+// SOURCE WITH OUTPUT
+// ============================================================================
+// `happyr show functional::iterator_adaptors` prints a lesson's source with
+// the output it produced tucked under the statement that printed it:
+//
+//     189 │     let doubled: Vec<i32> = v.iter().map(|x| x * 2).collect();
+//     190 │     writeln!(out, "Doubled: {:?}", doubled)?;
+//         ┆ ▶ Doubled: [2, 4, 6, 8, 10]
+//
+// Lessons aren't instrumented, so output is matched back to statements by
+// reading the source: each line of output goes to the `writeln!` whose
+// format string fits it, looking ahead from the last match first, then
+// behind (for loops). Lines no `writeln!` in the lesson could have printed
+// go to the next call that's handed `out`, such as a helper function.
+// ============================================================================
+
+use crate::lesson::Lesson;
+use crate::report;
+use crate::source;
+use std::io::{self, Write};
+
+/// Prints the lesson's source with its output interleaved
+pub fn show(lesson: &dyn Lesson, out: &mut dyn Write) -> io::Result<()> {
+    let example = source::example(lesson).ok_or_else(|| {
+        io::Error::new(io::ErrorKind::NotFound, format!("can't find the source of {}", lesson.id()))
+    })?;
+    let output = report::capture(lesson).stdout;
+
+    let code: Vec<&str> = example.code.lines().collect();
+    let statements = statements(example.code);
+    let placed = place(&statements, &output);
+
+    writeln!(
+        out,
+        "{} — {} (src/{}.rs:{})\n",
+        lesson.id(),
+        lesson.title(),
+        lesson.section(),
+        example.line
+    )?;
+    let width = (example.line + code.len()).to_string().len();
+    for (i, line) in code.iter().enumerate() {
+        writeln!(out, "{:>width$} │ {}", example.line + i, line, width = width)?;
+        for (_, text) in placed.iter().filter(|(line, _)| *line == i) {
+            writeln!(out, "{:>width$} ┆ ▶ {}", "", text, width = width)?;
+        }
+    }
+    Ok(())
+}
+
+/// Something in the source that writes to the output
+#[derive(Debug, PartialEq)]
+struct Statement {
+    /// The line (within the function) its output is shown under
+    line: usize,
+    /// One pattern per line a `writeln!` prints, or `None` for a call that
+    /// is handed `out` and may print anything
+    lines: Option<Vec<Pattern>>,
+}
+
+/// A line of a format string: the literal text between its placeholders
+#[derive(Debug, PartialEq)]
+struct Pattern {
+    pieces: Vec<String>,
+}
+
+impl Pattern {
+    fn parse(format: &str) -> Pattern {
+        let mut pieces = vec![String::new()];
+        let mut chars = format.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    pieces.last_mut().unwrap().push('{');
+                }
+                '{' => {
+                    for c in chars.by_ref() {
+                        if c == '}' {
+                            break;
+                        }
+                    }
+                    pieces.push(String::new());
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    pieces.last_mut().unwrap().push('}');
+                }
+                c => pieces.last_mut().unwrap().push(c),
+            }
+        }
+        Pattern { pieces }
+    }
+
+    /// Whether it has literal text to go on, rather than being `{}` alone
+    fn is_strong(&self) -> bool {
+        self.pieces.len() == 1 || self.pieces.iter().any(|piece| !piece.is_empty())
+    }
+
+    fn matches(&self, line: &str) -> bool {
+        let (first, rest) = self.pieces.split_first().unwrap();
+        let Some((last, middle)) = rest.split_last() else {
+            return line == first;
+        };
+        let Some(mut remaining) = line.strip_prefix(first.as_str()) else {
+            return false;
+        };
+        for piece in middle {
+            match remaining.find(piece.as_str()) {
+                Some(at) => remaining = &remaining[at + piece.len()..],
+                None => return false,
+            }
+        }
+        remaining.ends_with(last.as_str())
+    }
+}
+
+/// Finds every `writeln!` and every call handed `out` in a function
+fn statements(code: &str) -> Vec<Statement> {
+    let mut statements = Vec::new();
+    let mut covered = Vec::new();
+    let line_of = |index: usize| code[..index].matches('\n').count();
+
+    let mut search = 0;
+    while let Some(found) = code[search..].find("writeln!(") {
+        let start = search + found;
+        let open = start + "writeln!".len();
+        let close = closing_paren(code, open);
+        search = close;
+        if is_commented(code, start) {
+            continue;
+        }
+        let args = &code[open + 1..close];
+        let format = match args.find('"') {
+            Some(quote) if args[..quote].contains(',') => string_literal(&args[quote..], &args[..quote]),
+            _ => String::new(),
+        };
+        statements.push(Statement {
+            line: line_of(close),
+            lines: Some(format.split('\n').map(Pattern::parse).collect()),
+        });
+        covered.push(line_of(start)..=line_of(close));
+    }
+
+    for (i, line) in code.lines().enumerate().skip(1) {
+        let text = line.trim_start();
+        if text.starts_with("//") || covered.iter().any(|lines| lines.contains(&i)) {
+            continue;
+        }
+        if has_word(text, "out") {
+            statements.push(Statement { line: i, lines: None });
+        }
+    }
+
+    statements.sort_by_key(|statement| statement.line);
+    statements
+}
+
+/// Gives each output line the line of the statement that most likely
+/// printed it
+fn place(statements: &[Statement], output: &str) -> Vec<(usize, String)> {
+    // One slot per line a statement can print; calls get a single slot
+    // that can take any number of lines
+    let slots: Vec<(usize, Option<&Pattern>)> = statements
+        .iter()
+        .flat_map(|statement| match &statement.lines {
+            Some(patterns) => patterns.iter().map(|pattern| (statement.line, Some(pattern))).collect(),
+            None => vec![(statement.line, None)],
+        })
+        .collect();
+
+    let strong = |k: &usize, line: &str| slots[*k].1.is_some_and(|p| p.is_strong() && p.matches(line));
+    let weak = |k: &usize, line: &str| slots[*k].1.is_none_or(|p| p.matches(line));
+
+    let mut placed = Vec::new();
+    let mut cursor = 0;
+    let mut last = None;
+    for line in output.lines() {
+        let found = (cursor..slots.len())
+            .find(|k| strong(k, line))
+            .or_else(|| (0..cursor).find(|k| strong(k, line)))
+            .or_else(|| (cursor..slots.len()).find(|k| weak(k, line)))
+            .or_else(|| (0..cursor).find(|k| weak(k, line)));
+        match found {
+            Some(k) => {
+                // A call keeps its slot, so it can take the lines after this
+                cursor = if slots[k].1.is_some() { k + 1 } else { k };
+                last = Some(slots[k].0);
+            }
+            None if last.is_none() => last = Some(0),
+            None => {}
+        }
+        placed.push((last.unwrap_or(0), line.to_string()));
+    }
+    placed
+}
+
+/// The index of the `)` matching the `(` at `open`, skipping strings
+fn closing_paren(code: &str, open: usize) -> usize {
+    let bytes = code.as_bytes();
+    let mut depth = 0;
+    let mut i = open;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'(' => depth += 1,
+            b')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return i;
+                }
+            }
+            b'"' => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'"' {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    code.len()
+}
+
+fn is_commented(code: &str, index: usize) -> bool {
+    let line_start = code[..index].rfind('\n').map_or(0, |newline| newline + 1);
+    code[line_start..index].contains("//")
+}
+
+/// The text of the string literal at the start of `text`, with escapes
+/// resolved; `before` is what precedes it, to spot raw strings
+fn string_literal(text: &str, before: &str) -> String {
+    let body = &text[1..];
+    if before.ends_with('r') {
+        return body[..body.find('"').unwrap_or(body.len())].to_string();
+    }
+
+    let mut value = String::new();
+    let mut chars = body.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => break,
+            '\\' => match chars.next() {
+                Some('n') => value.push('\n'),
+                Some('t') => value.push('\t'),
+                Some('r') => value.push('\r'),
+                Some('0') => value.push('\0'),
+                // A line continuation skips the newline and the indent after it
+                Some('\n') => {
+                    let rest = chars.as_str().trim_start();
+                    chars = rest.chars();
+                }
+                Some(other) => value.push(other),
+                None => break,
+            },
+            c => value.push(c),
+        }
+    }
+    value
+}
+
+/// Whether `word` appears in `text` as a whole identifier
+fn has_word(text: &str, word: &str) -> bool {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    text.match_indices(word).any(|(i, _)| {
+        !text[..i].chars().next_back().is_some_and(is_ident)
+            && !text[i + word.len()..].chars().next().is_some_and(is_ident)
+    })
+}
+
+// ============================================================================
+// UNIT TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry;
+
+    #[cfg(any(feature = "functional", feature = "basics"))]
+    fn shown(id: &str) -> String {
+        let mut out = Vec::new();
+        show(registry::find(id).unwrap(), &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_pattern_matching() {
+        assert!(Pattern::parse("Doubled: {:?}").matches("Doubled: [2, 4]"));
+        assert!(Pattern::parse("{} + {} = {}").matches("5 + 3 = 8"));
+        assert!(!Pattern::parse("{} + {} = {}").matches("5 - 3 = 2"));
+        assert!(Pattern::parse("Set {{{}}}").matches("Set {1}"));
+        assert!(Pattern::parse("").matches(""));
+        assert!(!Pattern::parse("").matches("x"));
+        assert!(!Pattern::parse("{}").is_strong());
+    }
+
+    #[test]
+    #[cfg(feature = "functional")]
+    fn test_iterator_adaptors_output_follows_statements() {
+        let shown = shown("functional::iterator_adaptors");
+        assert!(shown.starts_with("functional::iterator_adaptors — Iterator Adaptors (src/functional.rs:"));
+        assert!(shown.contains(
+            "│     writeln!(out, \"Doubled: {:?}\", doubled)?;\n    ┆ ▶ Doubled: [2, 4, 6, 8, 10]\n"
+        ));
+        assert!(shown.contains(
+            "│         writeln!(out, \"  Index {}: {}\", i, val)?;\n    ┆ ▶   Index 0: 1\n    ┆ ▶   Index 1: 2\n"
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "basics")]
+    fn test_helper_output_goes_to_the_call() {
+        let shown = shown("basics::functions_demo");
+        assert!(shown.contains("│     greet(out, \"Rustacean\")?;\n    ┆ ▶ Hello, Rustacean!\n"));
+        assert!(shown.contains("│     writeln!(out, \"5 + 3 = {}\", result)?;\n    ┆ ▶ 5 + 3 = 8\n"));
+    }
+
+    #[test]
+    fn test_every_lesson_can_be_shown() {
+        for lesson in registry::lessons() {
+            let mut out = Vec::new();
+            show(lesson, &mut out).unwrap();
+            let shown = String::from_utf8(out).unwrap();
+            let captured = report::capture(lesson).stdout;
+            assert_eq!(shown.matches(" ▶ ").count(), captured.lines().count(), "{}", lesson.id());
+        }
+    }
+}
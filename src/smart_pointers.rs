@@ -10,6 +10,11 @@
 // - Deref and Drop traits
 // ============================================================================
 
+// Several list and tree types are only built and printed, never read back.
+#![allow(dead_code)]
+
+use crate::lesson::{lesson, Difficulty, Section, SharedOutput};
+use std::io::{self, Write};
 use std::rc::Rc;
 use std::cell::RefCell;
 use std::ops::Deref;
@@ -20,24 +25,26 @@ use std::ops::Deref;
 /// - Allocates data on the heap
 /// - Fixed size (pointer on stack)
 /// - Useful for recursive types, large data, trait objects
-pub fn box_basics() {
-    println!("\n--- Box Basics ---");
+pub fn box_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Box Basics ---")?;
     
     // Simple heap allocation
     let b = Box::new(5);
-    println!("Boxed value: {}", b);
+    writeln!(out, "Boxed value: {}", b)?;
     
     // Box is automatically dereferenced
     let x = *b + 1;
-    println!("Dereferenced: {}", x);
+    writeln!(out, "Dereferenced: {}", x)?;
     
     // Recursive type with Box
     let list = Cons(1, Box::new(Cons(2, Box::new(Cons(3, Box::new(Nil))))));
-    println!("List created: {:?}", list);
+    writeln!(out, "List created: {:?}", list)?;
     
     // Large data on heap
     let large_array = Box::new([0; 1000]);
-    println!("Large array on heap (first element): {}", large_array[0]);
+    writeln!(out, "Large array on heap (first element): {}", large_array[0])?;
+    
+    Ok(())
 }
 
 #[derive(Debug)]
@@ -53,8 +60,8 @@ use List::{Cons, Nil};
 /// DEREF:
 /// - Allows treating smart pointers like references
 /// - Enables deref coercion
-pub fn deref_trait() {
-    println!("\n--- Deref Trait ---");
+pub fn deref_trait(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Deref Trait ---")?;
     
     let x = 5;
     let y = Box::new(x);
@@ -68,9 +75,11 @@ pub fn deref_trait() {
     
     // Deref coercion
     let m = MyBox::new(String::from("Rust"));
-    hello(&m); // &MyBox<String> -> &String -> &str
+    hello(out, &m)?; // &MyBox<String> -> &String -> &str
     
-    println!("Deref coercion works!");
+    writeln!(out, "Deref coercion works!")?;
+    
+    Ok(())
 }
 
 struct MyBox<T>(T);
@@ -89,8 +98,8 @@ impl<T> Deref for MyBox<T> {
     }
 }
 
-fn hello(name: &str) {
-    println!("Hello, {}!", name);
+fn hello(out: &mut dyn Write, name: &str) -> io::Result<()> {
+    writeln!(out, "Hello, {}!", name)
 }
 
 /// Demonstrates the Drop trait
@@ -99,33 +108,44 @@ fn hello(name: &str) {
 /// - Customize cleanup when value goes out of scope
 /// - Called automatically
 /// - Can't call drop() manually (use std::mem::drop)
-pub fn drop_trait() {
-    println!("\n--- Drop Trait ---");
-    
-    let c = CustomSmartPointer {
-        data: String::from("my stuff"),
-    };
-    
-    let d = CustomSmartPointer {
-        data: String::from("other stuff"),
-    };
+pub fn drop_trait(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Drop Trait ---")?;
     
-    println!("CustomSmartPointers created.");
-    
-    // Early drop with std::mem::drop
-    drop(c);
-    println!("CustomSmartPointer c dropped early.");
+    // drop() can't borrow `out`, so the pointers and this function share
+    // a log, which is copied to `out` once everything has been dropped
+    let mut log = SharedOutput::new();
+    {
+        let c = CustomSmartPointer {
+            data: String::from("my stuff"),
+            log: log.clone(),
+        };
+        
+        let _d = CustomSmartPointer {
+            data: String::from("other stuff"),
+            log: log.clone(),
+        };
+        
+        writeln!(log, "CustomSmartPointers created.")?;
+        
+        // Early drop with std::mem::drop
+        drop(c);
+        writeln!(log, "CustomSmartPointer c dropped early.")?;
+        
+        writeln!(log, "End of function (d will be dropped).")?;
+    } // _d is dropped here
     
-    println!("End of function (d will be dropped).");
+    log.drain_into(out)
 }
 
 struct CustomSmartPointer {
     data: String,
+    log: SharedOutput,
 }
 
 impl Drop for CustomSmartPointer {
     fn drop(&mut self) {
-        println!("Dropping CustomSmartPointer with data: {}", self.data);
+        // drop() can't return an error, and writing to the log never fails
+        let _ = writeln!(self.log, "Dropping CustomSmartPointer with data: {}", self.data);
     }
 }
 
@@ -136,23 +156,25 @@ impl Drop for CustomSmartPointer {
 /// - Keeps track of number of references
 /// - Deallocates when count reaches zero
 /// - Only for single-threaded scenarios
-pub fn rc_basics() {
-    println!("\n--- Rc Basics ---");
+pub fn rc_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Rc Basics ---")?;
     
     let a = Rc::new(Cons2(5, Rc::new(Cons2(10, Rc::new(Nil2)))));
-    println!("Reference count after creating a: {}", Rc::strong_count(&a));
+    writeln!(out, "Reference count after creating a: {}", Rc::strong_count(&a))?;
     
     let b = Cons2(3, Rc::clone(&a)); // Increment reference count
-    println!("Reference count after creating b: {}", Rc::strong_count(&a));
+    writeln!(out, "Reference count after creating b: {}", Rc::strong_count(&a))?;
     
     {
-        let c = Cons2(4, Rc::clone(&a));
-        println!("Reference count after creating c: {}", Rc::strong_count(&a));
+        let _c = Cons2(4, Rc::clone(&a));
+        writeln!(out, "Reference count after creating c: {}", Rc::strong_count(&a))?;
     } // c goes out of scope
     
-    println!("Reference count after c goes out of scope: {}", Rc::strong_count(&a));
+    writeln!(out, "Reference count after c goes out of scope: {}", Rc::strong_count(&a))?;
+    
+    writeln!(out, "List b: {:?}", b)?;
     
-    println!("List b: {:?}", b);
+    Ok(())
 }
 
 #[derive(Debug)]
@@ -170,30 +192,32 @@ use List2::{Cons2, Nil2};
 /// - Enforces borrowing rules at runtime (not compile time)
 /// - Panics if rules violated
 /// - Single-threaded only
-pub fn refcell_basics() {
-    println!("\n--- RefCell Basics ---");
+pub fn refcell_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- RefCell Basics ---")?;
     
     let value = RefCell::new(5);
     
     // Borrow immutably
     {
         let borrowed = value.borrow();
-        println!("Borrowed value: {}", *borrowed);
+        writeln!(out, "Borrowed value: {}", *borrowed)?;
     } // borrowed goes out of scope
     
     // Borrow mutably
     {
         let mut borrowed_mut = value.borrow_mut();
         *borrowed_mut += 10;
-        println!("Modified value: {}", *borrowed_mut);
+        writeln!(out, "Modified value: {}", *borrowed_mut)?;
     }
     
-    println!("Final value: {}", *value.borrow());
+    writeln!(out, "Final value: {}", *value.borrow())?;
     
     // Multiple immutable borrows OK
     let borrow1 = value.borrow();
     let borrow2 = value.borrow();
-    println!("Multiple borrows: {} and {}", *borrow1, *borrow2);
+    writeln!(out, "Multiple borrows: {} and {}", *borrow1, *borrow2)?;
+    
+    Ok(())
 }
 
 /// Demonstrates combining Rc and RefCell
@@ -201,8 +225,8 @@ pub fn refcell_basics() {
 /// RC + REFCELL:
 /// - Multiple ownership with interior mutability
 /// - Common pattern in Rust
-pub fn rc_refcell_combination() {
-    println!("\n--- Rc + RefCell ---");
+pub fn rc_refcell_combination(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Rc + RefCell ---")?;
     
     let value = Rc::new(RefCell::new(5));
     
@@ -210,21 +234,23 @@ pub fn rc_refcell_combination() {
     let b = Rc::clone(&value);
     
     *a.borrow_mut() += 10;
-    println!("After a modifies: {}", *value.borrow());
+    writeln!(out, "After a modifies: {}", *value.borrow())?;
     
     *b.borrow_mut() += 20;
-    println!("After b modifies: {}", *value.borrow());
+    writeln!(out, "After b modifies: {}", *value.borrow())?;
+    
+    writeln!(out, "Reference count: {}", Rc::strong_count(&value))?;
     
-    println!("Reference count: {}", Rc::strong_count(&value));
+    Ok(())
 }
 
 /// Demonstrates reference cycles and memory leaks
-pub fn reference_cycles() {
-    println!("\n--- Reference Cycles ---");
+pub fn reference_cycles(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Reference Cycles ---")?;
     
-    println!("Reference cycles can cause memory leaks!");
-    println!("Example: Two Rc values pointing to each other");
-    println!("Solution: Use Weak<T> to break cycles");
+    writeln!(out, "Reference cycles can cause memory leaks!")?;
+    writeln!(out, "Example: Two Rc values pointing to each other")?;
+    writeln!(out, "Solution: Use Weak<T> to break cycles")?;
     
     // This would create a cycle (commented to avoid leak):
     // let a = Rc::new(RefCell::new(Cons3(5, RefCell::new(Rc::new(Nil3)))));
@@ -232,6 +258,8 @@ pub fn reference_cycles() {
     // if let Some(link) = a.borrow_mut().tail() {
     //     *link = Rc::clone(&b); // Creates cycle!
     // }
+    
+    Ok(())
 }
 
 /// Demonstrates Weak<T> for breaking cycles
@@ -241,8 +269,8 @@ pub fn reference_cycles() {
 /// - Doesn't increase reference count
 /// - Must upgrade to Rc to use
 /// - Returns None if value dropped
-pub fn weak_references() {
-    println!("\n--- Weak References ---");
+pub fn weak_references(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Weak References ---")?;
     
     let leaf = Rc::new(Node {
         value: 3,
@@ -250,9 +278,9 @@ pub fn weak_references() {
         children: RefCell::new(vec![]),
     });
     
-    println!("Leaf strong: {}, weak: {}", 
+    writeln!(out, "Leaf strong: {}, weak: {}", 
              Rc::strong_count(&leaf), 
-             Rc::weak_count(&leaf));
+             Rc::weak_count(&leaf))?;
     
     {
         let branch = Rc::new(Node {
@@ -263,19 +291,22 @@ pub fn weak_references() {
         
         *leaf.parent.borrow_mut() = Rc::downgrade(&branch);
         
-        println!("Branch strong: {}, weak: {}", 
+        writeln!(out, "Branch strong: {}, weak: {}", 
                  Rc::strong_count(&branch), 
-                 Rc::weak_count(&branch));
+                 Rc::weak_count(&branch))?;
         
-        println!("Leaf strong: {}, weak: {}", 
+        writeln!(out, "Leaf strong: {}, weak: {}", 
                  Rc::strong_count(&leaf), 
-                 Rc::weak_count(&leaf));
+                 Rc::weak_count(&leaf))?;
     }
     
-    println!("Leaf parent after branch dropped: {:?}", 
-             leaf.parent.borrow().upgrade());
+    writeln!(out, "Leaf parent after branch dropped: {:?}", 
+             leaf.parent.borrow().upgrade())?;
+    
+    Ok(())
 }
 
+#[derive(Debug)]
 struct Node {
     value: i32,
     parent: RefCell<std::rc::Weak<Node>>,
@@ -283,8 +314,8 @@ struct Node {
 }
 
 /// Demonstrates practical use cases
-pub fn practical_examples() {
-    println!("\n--- Practical Examples ---");
+pub fn practical_examples(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Practical Examples ---")?;
     
     // Use case 1: Trait objects with Box
     let shapes: Vec<Box<dyn Shape>> = vec![
@@ -293,7 +324,7 @@ pub fn practical_examples() {
     ];
     
     for shape in shapes {
-        println!("Area: {}", shape.area());
+        writeln!(out, "Area: {}", shape.area())?;
     }
     
     // Use case 2: Shared state with Rc
@@ -301,8 +332,8 @@ pub fn practical_examples() {
     let reader1 = Rc::clone(&shared_data);
     let reader2 = Rc::clone(&shared_data);
     
-    println!("Reader 1: {:?}", reader1);
-    println!("Reader 2: {:?}", reader2);
+    writeln!(out, "Reader 1: {:?}", reader1)?;
+    writeln!(out, "Reader 2: {:?}", reader2)?;
     
     // Use case 3: Mock object with RefCell
     let mock = MockDatabase {
@@ -312,7 +343,9 @@ pub fn practical_examples() {
     mock.insert("key1".to_string(), "value1".to_string());
     mock.insert("key2".to_string(), "value2".to_string());
     
-    println!("Mock database: {:?}", mock.data.borrow());
+    writeln!(out, "Mock database: {:?}", mock.data.borrow())?;
+    
+    Ok(())
 }
 
 trait Shape {
@@ -351,49 +384,78 @@ impl MockDatabase {
 }
 
 /// Demonstrates when to use each smart pointer
-pub fn choosing_smart_pointers() {
-    println!("\n--- Choosing Smart Pointers ---");
-    
-    println!("Use Box<T> when:");
-    println!("  - You have a large amount of data to transfer ownership");
-    println!("  - You want to own a value and only care that it implements a trait");
-    println!("  - You need recursive types");
-    
-    println!("\nUse Rc<T> when:");
-    println!("  - You need multiple owners of the same data");
-    println!("  - Single-threaded scenarios");
-    println!("  - Read-only shared data");
-    
-    println!("\nUse RefCell<T> when:");
-    println!("  - You need interior mutability");
-    println!("  - You're sure borrowing rules are followed (runtime check)");
-    println!("  - Single-threaded scenarios");
-    
-    println!("\nUse Arc<T> when:");
-    println!("  - Like Rc<T> but for multi-threaded scenarios");
-    println!("  - Atomic reference counting (thread-safe)");
-    
-    println!("\nUse Weak<T> when:");
-    println!("  - You need to break reference cycles");
-    println!("  - Parent-child relationships");
+pub fn choosing_smart_pointers(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Choosing Smart Pointers ---")?;
+    
+    writeln!(out, "Use Box<T> when:")?;
+    writeln!(out, "  - You have a large amount of data to transfer ownership")?;
+    writeln!(out, "  - You want to own a value and only care that it implements a trait")?;
+    writeln!(out, "  - You need recursive types")?;
+    
+    writeln!(out, "\nUse Rc<T> when:")?;
+    writeln!(out, "  - You need multiple owners of the same data")?;
+    writeln!(out, "  - Single-threaded scenarios")?;
+    writeln!(out, "  - Read-only shared data")?;
+    
+    writeln!(out, "\nUse RefCell<T> when:")?;
+    writeln!(out, "  - You need interior mutability")?;
+    writeln!(out, "  - You're sure borrowing rules are followed (runtime check)")?;
+    writeln!(out, "  - Single-threaded scenarios")?;
+    
+    writeln!(out, "\nUse Arc<T> when:")?;
+    writeln!(out, "  - Like Rc<T> but for multi-threaded scenarios")?;
+    writeln!(out, "  - Atomic reference counting (thread-safe)")?;
+    
+    writeln!(out, "\nUse Weak<T> when:")?;
+    writeln!(out, "  - You need to break reference cycles")?;
+    writeln!(out, "  - Parent-child relationships")?;
+    
+    Ok(())
 }
 
 // ============================================================================
 // PUBLIC INTERFACE
 // ============================================================================
 
-pub fn run_all_examples() {
-    box_basics();
-    deref_trait();
-    drop_trait();
-    rc_basics();
-    refcell_basics();
-    rc_refcell_combination();
-    reference_cycles();
-    weak_references();
-    practical_examples();
-    choosing_smart_pointers();
-}
+/// This module's lessons, in teaching order
+pub static SECTION: Section = Section {
+    id: "smart_pointers",
+    number: 8,
+    title: "SMART POINTERS",
+    icon: "🧠",
+    lessons: &[
+        &lesson!(box_basics, "Box Basics")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["smart-pointers", "box"]),
+        &lesson!(deref_trait, "Deref Trait")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["smart-pointers", "traits"]),
+        &lesson!(drop_trait, "Drop Trait")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["smart-pointers", "traits", "raii"]),
+        &lesson!(rc_basics, "Rc Basics")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["smart-pointers", "rc"]),
+        &lesson!(refcell_basics, "RefCell Basics")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["smart-pointers", "interior-mutability"]),
+        &lesson!(rc_refcell_combination, "Rc + RefCell")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["rc", "interior-mutability"]),
+        &lesson!(reference_cycles, "Reference Cycles")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["rc", "memory-leaks"]),
+        &lesson!(weak_references, "Weak References")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["rc", "weak"]),
+        &lesson!(practical_examples, "Practical Examples")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["smart-pointers", "interior-mutability"]),
+        &lesson!(choosing_smart_pointers, "Choosing Smart Pointers")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["smart-pointers"]),
+    ],
+};
 
 // ============================================================================
 // UNIT TESTS
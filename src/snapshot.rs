@@ -0,0 +1,375 @@
+// ============================================================================
+// SNAPSHOT TESTS
+// ============================================================================
+// Runs every registered lesson and compares what it writes against a golden
+// file in `snapshots/<section>/<name>.snap`. When output changes on purpose,
+// regenerate the files and review the diff:
+//
+//     UPDATE_SNAPSHOTS=1 cargo test snapshot
+//
+// Lessons run under the seeded scheduler (see `sched`), so the concurrency
+// lessons' threads interleave the same way every time. A few others vary
+// from run to run: some iterate a `HashMap`, one counts its own runs, one
+// prints line numbers of its own source, and the benchmarks print timings.
+// Their output is normalized first, see `ORDER_INDEPENDENT`.
+// ============================================================================
+
+use crate::lesson::Lesson;
+use crate::sched;
+use std::path::PathBuf;
+
+/// How a lesson's output is normalized before comparing it
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Order {
+    /// Printed exactly as written
+    Fixed,
+    /// Prints hash maps or sets: the items of each `{...}` group are sorted,
+    /// and so are runs of indented lines from loops over them
+    Hashed,
+    /// Printed in a fixed order, but counts how often it has run in this
+    /// process, which depends on what other tests ran first. The number
+    /// following the given word is masked
+    Counted(&'static str),
+    /// Prints `bench` tables: every duration and ratio is masked, along
+    /// with the padding in front of it
+    Timed,
+    /// Prints where in its source file something happened, which moves
+    /// whenever the file is edited above it. The line and column of every
+    /// `file.rs:line:column` are masked
+    Located,
+}
+
+impl Order {
+    fn name(self) -> &'static str {
+        match self {
+            Order::Fixed => "fixed",
+            Order::Hashed => "hashed",
+            Order::Counted(_) => "counted",
+            Order::Timed => "timed",
+            Order::Located => "located",
+        }
+    }
+}
+
+/// Lessons whose output order isn't fixed
+const ORDER_INDEPENDENT: &[(&str, Order)] = &[
+    #[cfg(feature = "collections")]
+    ("collections::hashmap_basics", Order::Hashed),
+    #[cfg(feature = "collections")]
+    ("collections::hashmap_updates", Order::Hashed),
+    #[cfg(feature = "collections")]
+    ("collections::hashset_basics", Order::Hashed),
+    #[cfg(feature = "functional")]
+    ("functional::practical_examples", Order::Hashed),
+    #[cfg(feature = "unsafe-lessons")]
+    ("advanced::static_variables", Order::Counted("Counter:")),
+    #[cfg(feature = "functional")]
+    ("functional::iterator_performance", Order::Timed),
+    #[cfg(feature = "collections")]
+    ("collections::sequence_benchmarks", Order::Timed),
+    #[cfg(feature = "collections")]
+    ("collections::map_benchmarks", Order::Timed),
+    #[cfg(feature = "traits_generics")]
+    ("traits_generics::dispatch_benchmark", Order::Timed),
+    #[cfg(feature = "concurrency")]
+    ("concurrency::work_stealing", Order::Timed),
+    #[cfg(feature = "concurrency")]
+    ("concurrency::deadlock_detection", Order::Located),
+];
+
+fn order_of(id: &str) -> Order {
+    ORDER_INDEPENDENT
+        .iter()
+        .find(|(lesson, _)| *lesson == id)
+        .map_or(Order::Fixed, |&(_, order)| order)
+}
+
+fn normalize(output: &str, order: Order) -> String {
+    let mut lines: Vec<String> = match order {
+        Order::Fixed => return output.to_string(),
+        Order::Counted(word) => {
+            return output
+                .lines()
+                .map(|line| format!("{}\n", mask_number_after(line, word)))
+                .collect()
+        }
+        Order::Timed => return output.lines().map(|line| format!("{}\n", mask_timings(line))).collect(),
+        Order::Located => return output.lines().map(|line| format!("{}\n", mask_locations(line))).collect(),
+        Order::Hashed => output.lines().map(|line| sort_groups(line, &[('{', '}')])).collect(),
+    };
+
+    // Sort each run of consecutive indented lines on its own
+    let mut start = 0;
+    while start < lines.len() {
+        let mut end = start;
+        while end < lines.len() && lines[end].starts_with("  ") {
+            end += 1;
+        }
+        lines[start..end].sort();
+        start = end + 1;
+    }
+
+    lines.iter().map(|line| format!("{}\n", line)).collect()
+}
+
+/// Replaces the number after each `word ` in `line` with `_`
+fn mask_number_after(line: &str, word: &str) -> String {
+    let prefix = format!("{} ", word);
+    let mut masked = String::new();
+    let mut rest = line;
+    while let Some(found) = rest.find(&prefix) {
+        let (before, after) = rest.split_at(found + prefix.len());
+        masked.push_str(before);
+        let digits = after.len() - after.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        if digits > 0 {
+            masked.push('_');
+        }
+        rest = &after[digits..];
+    }
+    masked.push_str(rest);
+    masked
+}
+
+/// Replaces each timing in `line`, such as `12.3µs` or `1.00x`, and the
+/// spaces padding it with ` _`
+fn mask_timings(line: &str) -> String {
+    let mut masked = String::new();
+    let mut rest = line;
+    while !rest.is_empty() {
+        let (spaces, after) = rest.split_at(rest.len() - rest.trim_start_matches(' ').len());
+        let (word, after) = after.split_at(after.find(' ').unwrap_or(after.len()));
+        if is_timing(word) {
+            masked.push_str(" _");
+        } else {
+            masked.push_str(spaces);
+            masked.push_str(word);
+        }
+        rest = after;
+    }
+    masked
+}
+
+/// Replaces the line and column after each `.rs:` in `line` with `_:_`
+fn mask_locations(line: &str) -> String {
+    let digits = |text: &str| text.len() - text.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let mut masked = String::new();
+    let mut rest = line;
+    while let Some(found) = rest.find(".rs:") {
+        let (before, after) = rest.split_at(found + ".rs:".len());
+        masked.push_str(before);
+        rest = after;
+        let line_digits = digits(after);
+        if line_digits > 0 && after[line_digits..].starts_with(':') {
+            let column = &after[line_digits + 1..];
+            if digits(column) > 0 {
+                masked.push_str("_:_");
+                rest = &column[digits(column)..];
+            }
+        }
+    }
+    masked.push_str(rest);
+    masked
+}
+
+fn is_timing(word: &str) -> bool {
+    let number = word.trim_end_matches(|c: char| !c.is_ascii_digit());
+    !number.is_empty()
+        && number.chars().all(|c| c.is_ascii_digit() || c == '.')
+        && ["ns", "µs", "ms", "s", "x"].contains(&&word[number.len()..])
+}
+
+/// Sorts the comma-separated items inside every bracketed group of `line`,
+/// innermost groups first
+fn sort_groups(line: &str, brackets: &[(char, char)]) -> String {
+    let chars: Vec<char> = line.chars().collect();
+    let mut pos = 0;
+    sort_groups_until(&chars, &mut pos, brackets, None)
+}
+
+fn sort_groups_until(
+    chars: &[char],
+    pos: &mut usize,
+    brackets: &[(char, char)],
+    close: Option<char>,
+) -> String {
+    let mut text = String::new();
+    while let Some(&c) = chars.get(*pos) {
+        *pos += 1;
+        if Some(c) == close {
+            break;
+        }
+        text.push(c);
+        if let Some(&(_, inner_close)) = brackets.iter().find(|&&(open, _)| open == c) {
+            let inner = sort_groups_until(chars, pos, brackets, Some(inner_close));
+            let mut items = split_items(&inner);
+            items.sort_unstable();
+            text.push_str(&items.join(", "));
+            text.push(inner_close);
+        }
+    }
+    text
+}
+
+/// Splits on the `, ` separators that aren't nested in another group
+fn split_items(text: &str) -> Vec<&str> {
+    let mut items = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '{' | '[' | '(' => depth += 1,
+            '}' | ']' | ')' => depth -= 1,
+            ',' if depth == 0 && text[i..].starts_with(", ") => {
+                items.push(&text[start..i]);
+                start = i + 2;
+            }
+            _ => {}
+        }
+    }
+    items.push(&text[start..]);
+    items
+}
+
+fn snapshot_path(lesson: &dyn Lesson) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("snapshots")
+        .join(lesson.section())
+        .join(format!("{}.snap", lesson.name()))
+}
+
+/// Runs a lesson and renders the snapshot file expected for it
+fn render(lesson: &dyn Lesson) -> String {
+    let mut out = Vec::new();
+    sched::run_seeded(sched::DEFAULT_SEED, || lesson.run(&mut out))
+        .unwrap_or_else(|e| panic!("{} failed: {}", lesson.id(), e));
+    let output = String::from_utf8(out).expect("lesson output is UTF-8");
+
+    let order = order_of(&lesson.id());
+    format!(
+        "lesson: {}\norder: {}\n---\n{}",
+        lesson.id(),
+        order.name(),
+        normalize(&output, order)
+    )
+}
+
+/// Describes the first line that differs between two snapshots
+fn first_difference(expected: &str, actual: &str) -> String {
+    let mut expected_lines = expected.lines();
+    let mut actual_lines = actual.lines();
+    for number in 1.. {
+        match (expected_lines.next(), actual_lines.next()) {
+            (Some(e), Some(a)) if e == a => continue,
+            (None, None) => break,
+            (e, a) => {
+                return format!(
+                    "line {}\n    expected: {}\n    actual:   {}",
+                    number,
+                    e.unwrap_or("<end of snapshot>"),
+                    a.unwrap_or("<end of output>")
+                )
+            }
+        }
+    }
+    "differs only in trailing whitespace".to_string()
+}
+
+// ============================================================================
+// UNIT TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry;
+    use std::fs;
+
+    #[test]
+    fn test_lesson_snapshots() {
+        let update = std::env::var_os("UPDATE_SNAPSHOTS").is_some();
+        let mut failures = Vec::new();
+
+        for lesson in registry::lessons() {
+            // Prints which lesson packs are in the build, and the snapshots
+            // are of a build with all of them
+            if lesson.id() == "advanced::conditional_compilation" && !registry::missing_features().is_empty() {
+                continue;
+            }
+            let actual = render(lesson);
+            let path = snapshot_path(lesson);
+            match fs::read_to_string(&path) {
+                Ok(expected) if expected == actual => {}
+                _ if update => {
+                    fs::create_dir_all(path.parent().unwrap()).unwrap();
+                    fs::write(&path, &actual).unwrap();
+                }
+                Ok(expected) => failures.push(format!(
+                    "{}: {}",
+                    lesson.id(),
+                    first_difference(&expected, &actual)
+                )),
+                Err(_) => failures.push(format!("{}: missing {}", lesson.id(), path.display())),
+            }
+        }
+
+        assert!(
+            failures.is_empty(),
+            "{} snapshot(s) out of date, rerun with UPDATE_SNAPSHOTS=1 if the change is intended:\n{}",
+            failures.len(),
+            failures.join("\n")
+        );
+    }
+
+    #[test]
+    fn test_order_independent_lessons_exist() {
+        let ids: Vec<String> = registry::lessons().map(|lesson| lesson.id()).collect();
+        for (id, _) in ORDER_INDEPENDENT {
+            assert!(ids.iter().any(|known| known == id), "unknown lesson {}", id);
+        }
+    }
+
+    #[test]
+    fn test_sort_groups() {
+        let braces = &[('{', '}')];
+        assert_eq!(
+            sort_groups(r#"Scores: {"Yellow": 50, "Blue": 10}"#, braces),
+            r#"Scores: {"Blue": 10, "Yellow": 50}"#
+        );
+        assert_eq!(sort_groups("Set: {3, 1, 2} [3, 1]", braces), "Set: {1, 2, 3} [3, 1]");
+        assert_eq!(
+            sort_groups("{b: [2, 1], a: [4, 3]}", &[('{', '}'), ('[', ']')]),
+            "{a: [3, 4], b: [1, 2]}"
+        );
+    }
+
+    #[test]
+    fn test_normalize_hashed_sorts_indented_runs() {
+        let output = "All scores:\n  Yellow: 50\n  Blue: 10\nDone\n  b\n  a\n";
+        assert_eq!(
+            normalize(output, Order::Hashed),
+            "All scores:\n  Blue: 10\n  Yellow: 50\nDone\n  a\n  b\n"
+        );
+    }
+
+    #[test]
+    fn test_normalize_fixed_and_counted() {
+        assert_eq!(normalize("b\na\n", Order::Fixed), "b\na\n");
+        assert_eq!(normalize("Counter: 3\nb\na\n", Order::Counted("Counter:")), "Counter: _\nb\na\n");
+        assert_eq!(mask_number_after("Worker 2 and Worker 10", "Worker"), "Worker _ and Worker _");
+    }
+
+    #[test]
+    fn test_normalize_timed_masks_durations() {
+        let output = "  Vec sum         812ns    1.25ms    1.00x\nSumming 1000 numbers in 2 tables:\n";
+        assert_eq!(normalize(output, Order::Timed), "  Vec sum _ _ _\nSumming 1000 numbers in 2 tables:\n");
+    }
+
+    #[test]
+    fn test_normalize_located_masks_lines_and_columns() {
+        let output = "`a` at src/concurrency.rs:473:43, then `b` at src/lib.rs:9:5\nsee main.rs: 12\n";
+        assert_eq!(
+            normalize(output, Order::Located),
+            "`a` at src/concurrency.rs:_:_, then `b` at src/lib.rs:_:_\nsee main.rs: 12\n"
+        );
+    }
+}
@@ -0,0 +1,220 @@
+// ============================================================================
+// LESSON SOURCES
+// ============================================================================
+// The lesson modules' own source code, embedded at build time, so lessons
+// can be shown and exported next to their output without the source tree.
+//
+// Finding an example means finding `fn <name>` in its module and then the
+// brace that closes its body. The scan skips strings, character literals and
+// comments, so a `"{"` in a `writeln!` can't throw the brace count off.
+// ============================================================================
+
+use crate::lesson::Lesson;
+use std::ops::Range;
+
+/// Each lesson module's source, by section id
+static MODULES: &[(&str, &str)] = &[
+    #[cfg(feature = "basics")]
+    ("basics", include_str!("basics.rs")),
+    #[cfg(feature = "ownership")]
+    ("ownership", include_str!("ownership.rs")),
+    #[cfg(feature = "structures")]
+    ("structures", include_str!("structures.rs")),
+    #[cfg(feature = "traits_generics")]
+    ("traits_generics", include_str!("traits_generics.rs")),
+    #[cfg(feature = "collections")]
+    ("collections", include_str!("collections.rs")),
+    #[cfg(feature = "error_handling")]
+    ("error_handling", include_str!("error_handling.rs")),
+    #[cfg(feature = "functional")]
+    ("functional", include_str!("functional.rs")),
+    #[cfg(feature = "smart_pointers")]
+    ("smart_pointers", include_str!("smart_pointers.rs")),
+    #[cfg(feature = "concurrency")]
+    ("concurrency", include_str!("concurrency.rs")),
+    #[cfg(feature = "advanced")]
+    ("advanced", include_str!("advanced.rs")),
+];
+
+pub fn module(section: &str) -> Option<&'static str> {
+    MODULES
+        .iter()
+        .find(|(id, _)| *id == section)
+        .map(|&(_, source)| source)
+}
+
+/// An example function as written in its module
+#[derive(Debug, PartialEq)]
+pub struct Example {
+    /// The `///` comment above the function, without the slashes
+    pub doc: String,
+    /// From `pub fn` to the closing brace
+    pub code: &'static str,
+    /// The line `code` starts on, counting from 1
+    pub line: usize,
+}
+
+/// Finds a lesson's function in its module's source
+pub fn example(lesson: &dyn Lesson) -> Option<Example> {
+    let source = module(lesson.section())?;
+    let span = function_span(source, lesson.name())?;
+    let start = line_start(source, span.start);
+
+    let mut doc: Vec<&str> = source[..start]
+        .lines()
+        .rev()
+        .map_while(|line| line.trim().strip_prefix("///"))
+        .map(|line| line.strip_prefix(' ').unwrap_or(line))
+        .collect();
+    doc.reverse();
+
+    Some(Example {
+        doc: doc.join("\n").trim().to_string(),
+        code: &source[start..span.end],
+        line: source[..start].lines().count() + 1,
+    })
+}
+
+/// The prose of a module's header comment, between its second and third
+/// `// ====` rules
+pub fn module_intro(section: &str) -> Option<String> {
+    let lines: Vec<&str> = module(section)?
+        .lines()
+        .take_while(|line| line.starts_with("//"))
+        .collect();
+    let rules: Vec<usize> = (0..lines.len()).filter(|&i| lines[i].starts_with("// ====")).collect();
+    let (&first, &last) = (rules.get(1)?, rules.get(2)?);
+    let text: Vec<&str> = lines[first + 1..last]
+        .iter()
+        .map(|line| line.trim_start_matches("//").strip_prefix(' ').unwrap_or(""))
+        .collect();
+    Some(text.join("\n"))
+}
+
+fn line_start(source: &str, index: usize) -> usize {
+    source[..index].rfind('\n').map_or(0, |newline| newline + 1)
+}
+
+/// The byte range of `fn name...{ ... }` in `source`, from `fn` to the
+/// closing brace of the body
+pub fn function_span(source: &str, name: &str) -> Option<Range<usize>> {
+    let signature = format!("fn {}", name);
+    let start = source.match_indices(&signature).map(|(i, _)| i).find(|&i| {
+        let after = source[i + signature.len()..].chars().next();
+        let before = source[..i].chars().next_back();
+        matches!(after, Some('(' | '<')) && !before.is_some_and(|c| c.is_alphanumeric() || c == '_')
+    })?;
+
+    let bytes = source.as_bytes();
+    let mut depth = 0;
+    let mut i = start;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(start..i + 1);
+                }
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                i += source[i..].find('\n').unwrap_or(source.len() - i);
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i += source[i..].find("*/").map_or(source.len() - i, |end| end + 1);
+            }
+            b'"' => i = skip_string(bytes, i),
+            b'r' if bytes.get(i + 1).is_some_and(|&b| b == b'#' || b == b'"')
+                && !bytes[i - 1].is_ascii_alphanumeric() =>
+            {
+                let hashes = bytes[i + 1..].iter().take_while(|&&b| b == b'#').count();
+                let close = format!("\"{}", "#".repeat(hashes));
+                let body = i + 2 + hashes;
+                i = source[body..].find(&close).map_or(source.len(), |end| body + end + close.len() - 1);
+            }
+            // A character literal such as '{' or '\'', but not a lifetime
+            b'\'' if bytes.get(i + 2) == Some(&b'\'') => i += 2,
+            b'\'' if bytes.get(i + 1) == Some(&b'\\') => {
+                i += source[i + 3..].find('\'').map_or(0, |end| end + 3);
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Given the index of an opening `"`, returns the index of the closing one
+fn skip_string(bytes: &[u8], open: usize) -> usize {
+    let mut i = open + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 1,
+            b'"' => return i,
+            _ => {}
+        }
+        i += 1;
+    }
+    bytes.len()
+}
+
+// ============================================================================
+// UNIT TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry;
+
+    #[test]
+    fn test_every_lesson_has_source() {
+        for lesson in registry::lessons() {
+            let example = example(lesson).unwrap_or_else(|| panic!("no source for {}", lesson.id()));
+            assert!(example.code.starts_with(&format!("pub fn {}(", lesson.name())), "{}", lesson.id());
+            assert!(example.code.ends_with('}'));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "ownership")]
+    fn test_example_doc_and_line() {
+        let lesson = registry::find("ownership::ownership_basics").unwrap();
+        let example = example(lesson).unwrap();
+        assert!(example.doc.starts_with("Demonstrates the three ownership rules in Rust\n\nOWNERSHIP RULES:\n1."));
+        let source = module("ownership").unwrap();
+        assert_eq!(
+            source.lines().nth(example.line - 1),
+            Some("pub fn ownership_basics(out: &mut dyn Write) -> io::Result<()> {")
+        );
+    }
+
+    #[test]
+    fn test_function_span_skips_literals_and_comments() {
+        let source = r#"
+fn other_thing() {}
+fn thing<T>(x: T) {
+    let a = "}{";
+    let b = '}';
+    let c = '\'';
+    let d = r"}";
+    // }
+    /* } */
+    fn nested<'a>(s: &'a str) {}
+}
+fn after() {}
+"#;
+        let span = function_span(source, "thing").unwrap();
+        assert!(source[span.clone()].starts_with("fn thing<T>"));
+        assert!(source[span].ends_with("fn nested<'a>(s: &'a str) {}\n}"));
+        assert_eq!(function_span(source, "missing"), None);
+    }
+
+    #[test]
+    #[cfg(feature = "ownership")]
+    fn test_module_intro() {
+        let intro = module_intro("ownership").unwrap();
+        assert!(intro.starts_with("This module covers Rust's most unique feature"));
+        assert!(intro.ends_with("- The slice type"));
+    }
+}
@@ -10,14 +10,27 @@
 // - Option and Result enums
 // ============================================================================
 
+// Many types exist only to show the syntax, and several matches are the
+// verbose form that a later example rewrites with if let or Option::map.
+#![allow(
+    dead_code,
+    clippy::manual_map,
+    clippy::match_single_binding,
+    clippy::single_match,
+    clippy::unnecessary_literal_unwrap,
+)]
+
+use crate::lesson::{lesson, Difficulty, Section};
+use std::io::{self, Write};
+
 /// Demonstrates classic struct definition and usage
 /// 
 /// STRUCTS:
 /// - Custom data types that group related data
 /// - Named fields with types
 /// - Similar to classes in other languages (but no inheritance)
-pub fn struct_basics() {
-    println!("\n--- Struct Basics ---");
+pub fn struct_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Struct Basics ---")?;
     
     // Creating a struct instance
     let user1 = User {
@@ -27,8 +40,8 @@ pub fn struct_basics() {
         active: true,
     };
     
-    println!("User: {} ({})", user1.username, user1.email);
-    println!("Active: {}, Sign-ins: {}", user1.active, user1.sign_in_count);
+    writeln!(out, "User: {} ({})", user1.username, user1.email)?;
+    writeln!(out, "Active: {}, Sign-ins: {}", user1.active, user1.sign_in_count)?;
     
     // Mutable struct
     let mut user2 = User {
@@ -40,7 +53,7 @@ pub fn struct_basics() {
     
     user2.sign_in_count += 1;
     user2.active = true;
-    println!("Updated user: {} with {} sign-ins", user2.username, user2.sign_in_count);
+    writeln!(out, "Updated user: {} with {} sign-ins", user2.username, user2.sign_in_count)?;
     
     // Struct update syntax (copy fields from another struct)
     let user3 = User {
@@ -48,7 +61,9 @@ pub fn struct_basics() {
         username: String::from("charlie"),
         ..user1 // Copy remaining fields from user1
     };
-    println!("User3: {} ({})", user3.username, user3.email);
+    writeln!(out, "User3: {} ({})", user3.username, user3.email)?;
+    
+    Ok(())
 }
 
 // Classic struct with named fields
@@ -64,17 +79,19 @@ struct User {
 /// TUPLE STRUCTS:
 /// - Structs without named fields
 /// - Useful for creating distinct types
-pub fn tuple_structs() {
-    println!("\n--- Tuple Structs ---");
+pub fn tuple_structs(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Tuple Structs ---")?;
     
     let black = Color(0, 0, 0);
     let origin = Point(0, 0, 0);
     
-    println!("Color: RGB({}, {}, {})", black.0, black.1, black.2);
-    println!("Point: ({}, {}, {})", origin.0, origin.1, origin.2);
+    writeln!(out, "Color: RGB({}, {}, {})", black.0, black.1, black.2)?;
+    writeln!(out, "Point: ({}, {}, {})", origin.0, origin.1, origin.2)?;
     
     // Different types even with same structure
     // let color: Color = origin; // ❌ Error! Different types
+    
+    Ok(())
 }
 
 struct Color(i32, i32, i32);
@@ -85,11 +102,13 @@ struct Point(i32, i32, i32);
 /// UNIT STRUCTS:
 /// - Structs with no fields
 /// - Useful for implementing traits without data
-pub fn unit_structs() {
-    println!("\n--- Unit Structs ---");
+pub fn unit_structs(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Unit Structs ---")?;
     
     let _marker = AlwaysEqual;
-    println!("Unit struct created (no data stored)");
+    writeln!(out, "Unit struct created (no data stored)")?;
+    
+    Ok(())
 }
 
 struct AlwaysEqual;
@@ -100,28 +119,30 @@ struct AlwaysEqual;
 /// - Functions defined within impl block
 /// - First parameter is &self, &mut self, or self
 /// - Called with dot notation
-pub fn struct_methods() {
-    println!("\n--- Struct Methods ---");
+pub fn struct_methods(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Struct Methods ---")?;
     
     let rect = Rectangle {
         width: 30,
         height: 50,
     };
     
-    println!("Rectangle: {}x{}", rect.width, rect.height);
-    println!("Area: {}", rect.area());
-    println!("Is square: {}", rect.is_square());
+    writeln!(out, "Rectangle: {}x{}", rect.width, rect.height)?;
+    writeln!(out, "Area: {}", rect.area())?;
+    writeln!(out, "Is square: {}", rect.is_square())?;
     
     let rect2 = Rectangle {
         width: 10,
         height: 40,
     };
     
-    println!("Can hold rect2: {}", rect.can_hold(&rect2));
+    writeln!(out, "Can hold rect2: {}", rect.can_hold(&rect2))?;
     
     // Method that takes ownership
     let rect3 = Rectangle::square(25);
-    println!("Square: {}x{}", rect3.width, rect3.height);
+    writeln!(out, "Square: {}x{}", rect3.width, rect3.height)?;
+    
+    Ok(())
 }
 
 struct Rectangle {
@@ -167,30 +188,32 @@ impl Rectangle {
 /// ENUMS:
 /// - Type that can be one of several variants
 /// - Each variant can have different data
-pub fn enum_basics() {
-    println!("\n--- Enum Basics ---");
+pub fn enum_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Enum Basics ---")?;
     
     let ipv4 = IpAddrKind::V4;
     let ipv6 = IpAddrKind::V6;
     
-    route(ipv4);
-    route(ipv6);
+    route(out, ipv4)?;
+    route(out, ipv6)?;
     
     // Enums with data
     let home = IpAddr::V4(127, 0, 0, 1);
     let loopback = IpAddr::V6(String::from("::1"));
     
-    println!("Home IP: {:?}", home);
-    println!("Loopback IP: {:?}", loopback);
+    writeln!(out, "Home IP: {:?}", home)?;
+    writeln!(out, "Loopback IP: {:?}", loopback)?;
     
     // Enums can have different types per variant
     let msg1 = Message::Write(String::from("hello"));
     let msg2 = Message::Move { x: 10, y: 20 };
     let msg3 = Message::ChangeColor(255, 0, 0);
     
-    msg1.call();
-    msg2.call();
-    msg3.call();
+    msg1.call(out)?;
+    msg2.call(out)?;
+    msg3.call(out)?;
+    
+    Ok(())
 }
 
 #[derive(Debug)]
@@ -199,8 +222,8 @@ enum IpAddrKind {
     V6,
 }
 
-fn route(ip_kind: IpAddrKind) {
-    println!("Routing to: {:?}", ip_kind);
+fn route(out: &mut dyn Write, ip_kind: IpAddrKind) -> io::Result<()> {
+    writeln!(out, "Routing to: {:?}", ip_kind)
 }
 
 #[derive(Debug)]
@@ -217,12 +240,12 @@ enum Message {
 }
 
 impl Message {
-    fn call(&self) {
+    fn call(&self, out: &mut dyn Write) -> io::Result<()> {
         match self {
-            Message::Quit => println!("Quit message"),
-            Message::Move { x, y } => println!("Move to ({}, {})", x, y),
-            Message::Write(text) => println!("Write: {}", text),
-            Message::ChangeColor(r, g, b) => println!("Color: RGB({}, {}, {})", r, g, b),
+            Message::Quit => writeln!(out, "Quit message"),
+            Message::Move { x, y } => writeln!(out, "Move to ({}, {})", x, y),
+            Message::Write(text) => writeln!(out, "Write: {}", text),
+            Message::ChangeColor(r, g, b) => writeln!(out, "Color: RGB({}, {}, {})", r, g, b),
         }
     }
 }
@@ -233,16 +256,16 @@ impl Message {
 /// - Rust doesn't have null
 /// - Option<T> represents optional values
 /// - Variants: Some(T) or None
-pub fn option_enum() {
-    println!("\n--- Option Enum ---");
+pub fn option_enum(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Option Enum ---")?;
     
     let some_number = Some(5);
     let some_string = Some("a string");
     let absent_number: Option<i32> = None;
     
-    println!("Some number: {:?}", some_number);
-    println!("Some string: {:?}", some_string);
-    println!("Absent: {:?}", absent_number);
+    writeln!(out, "Some number: {:?}", some_number)?;
+    writeln!(out, "Some string: {:?}", some_string)?;
+    writeln!(out, "Absent: {:?}", absent_number)?;
     
     // Must handle None case
     let x = 5;
@@ -253,16 +276,18 @@ pub fn option_enum() {
     
     // Must extract value from Option
     match y {
-        Some(value) => println!("Sum: {}", x + value),
-        None => println!("No value to add"),
+        Some(value) => writeln!(out, "Sum: {}", x + value)?,
+        None => writeln!(out, "No value to add")?,
     }
     
     // Using Option methods
     let doubled = some_number.map(|n| n * 2);
-    println!("Doubled: {:?}", doubled);
+    writeln!(out, "Doubled: {:?}", doubled)?;
     
     let default = absent_number.unwrap_or(0);
-    println!("With default: {}", default);
+    writeln!(out, "With default: {}", default)?;
+    
+    Ok(())
 }
 
 /// Demonstrates pattern matching with match
@@ -271,34 +296,36 @@ pub fn option_enum() {
 /// - Powerful control flow operator
 /// - Must be exhaustive (handle all cases)
 /// - Can bind to values in patterns
-pub fn pattern_matching() {
-    println!("\n--- Pattern Matching ---");
+pub fn pattern_matching(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Pattern Matching ---")?;
     
     let coin = Coin::Quarter(UsState::Alaska);
-    let value = value_in_cents(coin);
-    println!("Coin value: {} cents", value);
+    let value = value_in_cents(out, coin)?;
+    writeln!(out, "Coin value: {} cents", value)?;
     
     // Matching with Option
     let five = Some(5);
     let six = plus_one(five);
     let none = plus_one(None);
     
-    println!("Five + 1: {:?}", six);
-    println!("None + 1: {:?}", none);
+    writeln!(out, "Five + 1: {:?}", six)?;
+    writeln!(out, "None + 1: {:?}", none)?;
     
     // Catch-all pattern
     let dice_roll = 9;
     match dice_roll {
-        3 => println!("You get a fancy hat!"),
-        7 => println!("You lose your hat!"),
-        other => println!("Move {} spaces", other), // Catch-all
+        3 => writeln!(out, "You get a fancy hat!")?,
+        7 => writeln!(out, "You lose your hat!")?,
+        other => writeln!(out, "Move {} spaces", other)?, // Catch-all
     }
     
     // Ignore value with _
     match dice_roll {
-        3 => println!("Special!"),
-        _ => println!("Nothing special"), // Ignore value
+        3 => writeln!(out, "Special!")?,
+        _ => writeln!(out, "Nothing special")?, // Ignore value
     }
+    
+    Ok(())
 }
 
 #[derive(Debug)]
@@ -315,19 +342,20 @@ enum Coin {
     Quarter(UsState),
 }
 
-fn value_in_cents(coin: Coin) -> u8 {
-    match coin {
+fn value_in_cents(out: &mut dyn Write, coin: Coin) -> io::Result<u8> {
+    let cents = match coin {
         Coin::Penny => {
-            println!("Lucky penny!");
+            writeln!(out, "Lucky penny!")?;
             1
         }
         Coin::Nickel => 5,
         Coin::Dime => 10,
         Coin::Quarter(state) => {
-            println!("Quarter from {:?}!", state);
+            writeln!(out, "Quarter from {:?}!", state)?;
             25
         }
-    }
+    };
+    Ok(cents)
 }
 
 fn plus_one(x: Option<i32>) -> Option<i32> {
@@ -342,20 +370,20 @@ fn plus_one(x: Option<i32>) -> Option<i32> {
 /// IF LET:
 /// - Concise way to match one pattern
 /// - Less verbose than match for single case
-pub fn if_let_syntax() {
-    println!("\n--- if let Syntax ---");
+pub fn if_let_syntax(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- if let Syntax ---")?;
     
     let some_value = Some(3);
     
     // Using match (verbose)
     match some_value {
-        Some(3) => println!("Three!"),
+        Some(3) => writeln!(out, "Three!")?,
         _ => (),
     }
     
     // Using if let (concise)
     if let Some(3) = some_value {
-        println!("Three!");
+        writeln!(out, "Three!")?;
     }
     
     // With else
@@ -363,66 +391,72 @@ pub fn if_let_syntax() {
     let mut count = 0;
     
     if let Coin::Quarter(state) = coin {
-        println!("Quarter from {:?}", state);
+        writeln!(out, "Quarter from {:?}", state)?;
     } else {
         count += 1;
-        println!("Not a quarter, count: {}", count);
+        writeln!(out, "Not a quarter, count: {}", count)?;
     }
+    
+    Ok(())
 }
 
 /// Demonstrates while let syntax
-pub fn while_let_syntax() {
-    println!("\n--- while let Syntax ---");
+pub fn while_let_syntax(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- while let Syntax ---")?;
     
     let mut stack = vec![1, 2, 3];
     
     // Pop values while Some
     while let Some(top) = stack.pop() {
-        println!("Popped: {}", top);
+        writeln!(out, "Popped: {}", top)?;
     }
     
-    println!("Stack is empty!");
+    writeln!(out, "Stack is empty!")?;
+    
+    Ok(())
 }
 
 /// Demonstrates advanced pattern matching
-pub fn advanced_patterns() {
-    println!("\n--- Advanced Patterns ---");
+pub fn advanced_patterns(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Advanced Patterns ---")?;
     
     // Matching ranges
     let x = 5;
     match x {
-        1..=5 => println!("Between 1 and 5"),
-        _ => println!("Something else"),
+        1..=5 => writeln!(out, "Between 1 and 5")?,
+        _ => writeln!(out, "Something else")?,
     }
     
     // Destructuring structs
     let p = Point3D { x: 0, y: 7, z: 10 };
     let Point3D { x, y, z } = p;
-    println!("Point: x={}, y={}, z={}", x, y, z);
+    writeln!(out, "Point: x={}, y={}, z={}", x, y, z)?;
     
     // Matching struct fields
     match p {
-        Point3D { x: 0, y, z } => println!("On x-axis at y={}, z={}", y, z),
-        Point3D { x, y: 0, z } => println!("On y-axis at x={}, z={}", x, z),
-        Point3D { x, y, z: 0 } => println!("On z-axis at x={}, y={}", x, y),
-        Point3D { x, y, z } => println!("Not on axis: ({}, {}, {})", x, y, z),
+        Point3D { x: 0, y, z } => writeln!(out, "On x-axis at y={}, z={}", y, z)?,
+        Point3D { x, y: 0, z } => writeln!(out, "On y-axis at x={}, z={}", x, z)?,
+        Point3D { x, y, z: 0 } => writeln!(out, "On z-axis at x={}, y={}", x, y)?,
+        Point3D { x, y, z } => writeln!(out, "Not on axis: ({}, {}, {})", x, y, z)?,
     }
     
     // Ignoring values
     let numbers = (2, 4, 8, 16, 32);
     match numbers {
         (first, _, third, _, fifth) => {
-            println!("Some numbers: {}, {}, {}", first, third, fifth);
+            writeln!(out, "Some numbers: {}, {}, {}", first, third, fifth)?;
         }
     }
     
     // Match guards
     let num = Some(4);
     match num {
-        Some(x) if x < 5 => println!("Less than five: {}", x),
-        Some(x) => println!("Greater or equal to five: {}", x),
-        None => println!("None"),
+        Some(x) if x < 5 => writeln!(out, "Less than five: {}", x)?,
+        Some(x) => writeln!(out, "Greater or equal to five: {}", x)?,
+        None => writeln!(out, "None")?,
     }
+    
+    Ok(())
 }
 
 struct Point3D {
@@ -435,18 +469,27 @@ struct Point3D {
 // PUBLIC INTERFACE
 // ============================================================================
 
-pub fn run_all_examples() {
-    struct_basics();
-    tuple_structs();
-    unit_structs();
-    struct_methods();
-    enum_basics();
-    option_enum();
-    pattern_matching();
-    if_let_syntax();
-    while_let_syntax();
-    advanced_patterns();
-}
+/// This module's lessons, in teaching order
+pub static SECTION: Section = Section {
+    id: "structures",
+    number: 3,
+    title: "STRUCTURES & ENUMS",
+    icon: "🏗️",
+    lessons: &[
+        &lesson!(struct_basics, "Struct Basics").with_tags(&["structs"]),
+        &lesson!(tuple_structs, "Tuple Structs").with_tags(&["structs", "tuples"]),
+        &lesson!(unit_structs, "Unit Structs").with_tags(&["structs"]),
+        &lesson!(struct_methods, "Struct Methods").with_tags(&["structs", "methods"]),
+        &lesson!(enum_basics, "Enum Basics").with_tags(&["enums"]),
+        &lesson!(option_enum, "Option Enum").with_tags(&["enums", "option"]),
+        &lesson!(pattern_matching, "Pattern Matching").with_tags(&["pattern-matching", "enums"]),
+        &lesson!(if_let_syntax, "if let Syntax").with_tags(&["pattern-matching", "option"]),
+        &lesson!(while_let_syntax, "while let Syntax").with_tags(&["pattern-matching", "loops"]),
+        &lesson!(advanced_patterns, "Advanced Patterns")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["pattern-matching"]),
+    ],
+};
 
 // ============================================================================
 // UNIT TESTS
@@ -487,8 +530,8 @@ mod tests {
     
     #[test]
     fn test_coin_value() {
-        assert_eq!(value_in_cents(Coin::Penny), 1);
-        assert_eq!(value_in_cents(Coin::Nickel), 5);
-        assert_eq!(value_in_cents(Coin::Dime), 10);
+        assert_eq!(value_in_cents(&mut io::sink(), Coin::Penny).unwrap(), 1);
+        assert_eq!(value_in_cents(&mut io::sink(), Coin::Nickel).unwrap(), 5);
+        assert_eq!(value_in_cents(&mut io::sink(), Coin::Dime).unwrap(), 10);
     }
 }
@@ -10,35 +10,46 @@
 // - Associated types
 // ============================================================================
 
+// Some fields and functions exist only to show a signature.
+#![allow(dead_code)]
+
+use crate::bench::{black_box, Bench};
+use crate::lesson::{lesson, Difficulty, Section};
+use std::io::{self, Write};
+
 /// Demonstrates generic functions
 /// 
 /// GENERICS:
 /// - Write code that works with multiple types
 /// - Type parameters in angle brackets: <T>
 /// - No runtime cost (monomorphization)
-pub fn generic_functions() {
-    println!("\n--- Generic Functions ---");
+pub fn generic_functions(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Generic Functions ---")?;
     
     let numbers = vec![34, 50, 25, 100, 65];
     let result = largest(&numbers);
-    println!("Largest number: {}", result);
+    writeln!(out, "Largest number: {}", result)?;
     
     let chars = vec!['y', 'm', 'a', 'q'];
     let result = largest(&chars);
-    println!("Largest char: {}", result);
+    writeln!(out, "Largest char: {}", result)?;
     
     // Multiple type parameters
     let p1 = Point { x: 5, y: 10 };
     let p2 = Point { x: 1.0, y: 4.0 };
-    println!("Integer point: ({}, {})", p1.x, p1.y);
-    println!("Float point: ({}, {})", p2.x, p2.y);
+    writeln!(out, "Integer point: ({}, {})", p1.x, p1.y)?;
+    writeln!(out, "Float point: ({}, {})", p2.x, p2.y)?;
     
     let p3 = MixedPoint { x: 5, y: 4.0 };
-    println!("Mixed point: ({}, {})", p3.x, p3.y);
+    writeln!(out, "Mixed point: ({}, {})", p3.x, p3.y)?;
+    
+    Ok(())
 }
 
-// Generic function with trait bound
-fn largest<T: PartialOrd + Copy>(list: &[T]) -> T {
+/// The largest item of `list`, a generic function with a trait bound
+///
+/// Panics if `list` is empty.
+pub fn largest<T: PartialOrd + Copy>(list: &[T]) -> T {
     let mut largest = list[0];
     
     for &item in list.iter() {
@@ -63,19 +74,21 @@ struct MixedPoint<T, U> {
 }
 
 /// Demonstrates generic implementations
-pub fn generic_implementations() {
-    println!("\n--- Generic Implementations ---");
+pub fn generic_implementations(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Generic Implementations ---")?;
     
     let p = Point { x: 5, y: 10 };
-    println!("X coordinate: {}", p.x());
+    writeln!(out, "X coordinate: {}", p.x())?;
     
     let p2 = Point { x: 1.0, y: 4.0 };
-    println!("Distance from origin: {:.2}", p2.distance_from_origin());
+    writeln!(out, "Distance from origin: {:.2}", p2.distance_from_origin())?;
     
-    let p3 = Point { x: 5, y: 10 };
-    let p4 = Point { x: 1, y: 2 };
+    let p3 = MixedPoint { x: 5, y: 10.4 };
+    let p4 = MixedPoint { x: "Hello", y: 'c' };
     let p5 = p3.mixup(p4);
-    println!("Mixed up point: ({}, {})", p5.x, p5.y);
+    writeln!(out, "Mixed up point: ({}, {})", p5.x, p5.y)?;
+    
+    Ok(())
 }
 
 // Implementation for all types T
@@ -108,8 +121,8 @@ impl<T, U> MixedPoint<T, U> {
 /// - Define shared behavior
 /// - Similar to interfaces in other languages
 /// - Can have default implementations
-pub fn trait_basics() {
-    println!("\n--- Trait Basics ---");
+pub fn trait_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Trait Basics ---")?;
     
     let article = NewsArticle {
         headline: String::from("Rust 2.0 Released!"),
@@ -125,11 +138,13 @@ pub fn trait_basics() {
         retweet: false,
     };
     
-    println!("Article summary: {}", article.summarize());
-    println!("Tweet summary: {}", tweet.summarize());
+    writeln!(out, "Article summary: {}", article.summarize())?;
+    writeln!(out, "Tweet summary: {}", tweet.summarize())?;
     
     // Using default implementation
-    println!("Article author: {}", article.author_summary());
+    writeln!(out, "Article author: {}", article.author_summary())?;
+    
+    Ok(())
 }
 
 // Trait definition
@@ -178,8 +193,8 @@ impl Summary for Tweet {
 /// TRAIT BOUNDS:
 /// - Restrict generic types to those implementing specific traits
 /// - Syntax: <T: Trait> or where T: Trait
-pub fn trait_bounds() {
-    println!("\n--- Trait Bounds ---");
+pub fn trait_bounds(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Trait Bounds ---")?;
     
     let article = NewsArticle {
         headline: String::from("Breaking News"),
@@ -188,8 +203,8 @@ pub fn trait_bounds() {
         content: String::from("Important content..."),
     };
     
-    notify(&article);
-    notify_verbose(&article);
+    notify(out, &article)?;
+    notify_verbose(out, &article)?;
     
     let tweet = Tweet {
         username: String::from("user123"),
@@ -198,22 +213,24 @@ pub fn trait_bounds() {
         retweet: false,
     };
     
-    notify(&tweet);
+    notify(out, &tweet)?;
+    
+    Ok(())
 }
 
 // Trait bound syntax
-pub fn notify(item: &impl Summary) {
-    println!("Breaking news! {}", item.summarize());
+pub fn notify(out: &mut dyn Write, item: &impl Summary) -> io::Result<()> {
+    writeln!(out, "Breaking news! {}", item.summarize())
 }
 
 // Trait bound with generic type
-pub fn notify_verbose<T: Summary>(item: &T) {
-    println!("Notification: {}", item.summarize());
+pub fn notify_verbose<T: Summary>(out: &mut dyn Write, item: &T) -> io::Result<()> {
+    writeln!(out, "Notification: {}", item.summarize())
 }
 
 // Multiple trait bounds
-pub fn notify_display<T: Summary + std::fmt::Display>(item: &T) {
-    println!("Display: {}", item);
+pub fn notify_display<T: Summary + std::fmt::Display>(out: &mut dyn Write, item: &T) -> io::Result<()> {
+    writeln!(out, "Display: {}", item)
 }
 
 // Where clause for complex bounds
@@ -226,15 +243,17 @@ where
 }
 
 /// Demonstrates returning types that implement traits
-pub fn returning_traits() {
-    println!("\n--- Returning Traits ---");
+pub fn returning_traits(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Returning Traits ---")?;
     
     let tweet = returns_summarizable(true);
-    println!("Returned: {}", tweet.summarize());
+    writeln!(out, "Returned: {}", tweet.summarize())?;
+    
+    Ok(())
 }
 
 // Return type that implements trait
-fn returns_summarizable(is_tweet: bool) -> impl Summary {
+fn returns_summarizable(_is_tweet: bool) -> impl Summary {
     // Note: Can only return one concrete type
     // This would fail if we tried to return different types
     Tweet {
@@ -246,14 +265,16 @@ fn returns_summarizable(is_tweet: bool) -> impl Summary {
 }
 
 /// Demonstrates trait bounds with conditional implementations
-pub fn conditional_implementations() {
-    println!("\n--- Conditional Implementations ---");
+pub fn conditional_implementations(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Conditional Implementations ---")?;
     
     let pair1 = Pair::new(10, 20);
-    pair1.cmp_display();
+    pair1.cmp_display(out)?;
     
-    let pair2 = Pair::new("hello", "world");
-    // pair2.cmp_display(); // Would work if &str implemented PartialOrd
+    let _pair2 = Pair::new("hello", "world");
+    // _pair2.cmp_display(out)?; // Would work if &str implemented PartialOrd
+    
+    Ok(())
 }
 
 struct Pair<T> {
@@ -269,41 +290,43 @@ impl<T> Pair<T> {
 
 // Conditional implementation: only if T implements Display + PartialOrd
 impl<T: std::fmt::Display + PartialOrd> Pair<T> {
-    fn cmp_display(&self) {
+    fn cmp_display(&self, out: &mut dyn Write) -> io::Result<()> {
         if self.x >= self.y {
-            println!("The largest member is x = {}", self.x);
+            writeln!(out, "The largest member is x = {}", self.x)
         } else {
-            println!("The largest member is y = {}", self.y);
+            writeln!(out, "The largest member is y = {}", self.y)
         }
     }
 }
 
 /// Demonstrates common standard library traits
-pub fn standard_traits() {
-    println!("\n--- Standard Library Traits ---");
+pub fn standard_traits(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Standard Library Traits ---")?;
     
     // Clone trait
     let s1 = String::from("hello");
     let s2 = s1.clone();
-    println!("Original: {}, Clone: {}", s1, s2);
+    writeln!(out, "Original: {}, Clone: {}", s1, s2)?;
     
     // Copy trait (implicit)
     let x = 5;
     let y = x; // Copy, not move
-    println!("x: {}, y: {}", x, y);
+    writeln!(out, "x: {}, y: {}", x, y)?;
     
     // Debug trait
     let point = DebugPoint { x: 10, y: 20 };
-    println!("Debug: {:?}", point);
-    println!("Pretty debug: {:#?}", point);
+    writeln!(out, "Debug: {:?}", point)?;
+    writeln!(out, "Pretty debug: {:#?}", point)?;
     
     // PartialEq and Eq
     let p1 = DebugPoint { x: 1, y: 2 };
     let p2 = DebugPoint { x: 1, y: 2 };
-    println!("Points equal: {}", p1 == p2);
+    writeln!(out, "Points equal: {}", p1 == p2)?;
     
     // PartialOrd and Ord
-    println!("Point comparison: {:?}", p1.partial_cmp(&p2));
+    writeln!(out, "Point comparison: {:?}", p1.partial_cmp(&p2))?;
+    
+    Ok(())
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -313,15 +336,17 @@ struct DebugPoint {
 }
 
 /// Demonstrates operator overloading with traits
-pub fn operator_overloading() {
-    println!("\n--- Operator Overloading ---");
+pub fn operator_overloading(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Operator Overloading ---")?;
     
     let p1 = AddPoint { x: 1, y: 2 };
     let p2 = AddPoint { x: 3, y: 4 };
     let p3 = p1 + p2; // Uses Add trait
     
-    println!("({}, {}) + ({}, {}) = ({}, {})", 
-             p1.x, p1.y, p2.x, p2.y, p3.x, p3.y);
+    writeln!(out, "({}, {}) + ({}, {}) = ({}, {})", 
+             p1.x, p1.y, p2.x, p2.y, p3.x, p3.y)?;
+    
+    Ok(())
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -347,8 +372,8 @@ impl std::ops::Add for AddPoint {
 /// ASSOCIATED TYPES:
 /// - Placeholder types in trait definitions
 /// - Specified when implementing the trait
-pub fn associated_types() {
-    println!("\n--- Associated Types ---");
+pub fn associated_types(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Associated Types ---")?;
     
     let counter = Counter { count: 0 };
     
@@ -356,8 +381,10 @@ pub fn associated_types() {
         if i >= 5 {
             break;
         }
-        println!("Count: {}", num);
+        writeln!(out, "Count: {}", num)?;
     }
+    
+    Ok(())
 }
 
 struct Counter {
@@ -378,11 +405,13 @@ impl Iterator for Counter {
 }
 
 /// Demonstrates supertraits
-pub fn supertraits() {
-    println!("\n--- Supertraits ---");
+pub fn supertraits(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Supertraits ---")?;
     
     let point = OutlinePoint { x: 1, y: 2 };
-    point.outline_print();
+    point.outline_print(out)?;
+    
+    Ok(())
 }
 
 struct OutlinePoint {
@@ -392,14 +421,14 @@ struct OutlinePoint {
 
 // Display is a supertrait of OutlinePrint
 trait OutlinePrint: std::fmt::Display {
-    fn outline_print(&self) {
+    fn outline_print(&self, out: &mut dyn Write) -> io::Result<()> {
         let output = self.to_string();
         let len = output.len();
-        println!("{}", "*".repeat(len + 4));
-        println!("*{}*", " ".repeat(len + 2));
-        println!("* {} *", output);
-        println!("*{}*", " ".repeat(len + 2));
-        println!("{}", "*".repeat(len + 4));
+        writeln!(out, "{}", "*".repeat(len + 4))?;
+        writeln!(out, "*{}*", " ".repeat(len + 2))?;
+        writeln!(out, "* {} *", output)?;
+        writeln!(out, "*{}*", " ".repeat(len + 2))?;
+        writeln!(out, "{}", "*".repeat(len + 4))
     }
 }
 
@@ -412,11 +441,13 @@ impl std::fmt::Display for OutlinePoint {
 }
 
 /// Demonstrates newtype pattern
-pub fn newtype_pattern() {
-    println!("\n--- Newtype Pattern ---");
+pub fn newtype_pattern(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Newtype Pattern ---")?;
     
     let w = Wrapper(vec![String::from("hello"), String::from("world")]);
-    println!("Wrapper: {}", w);
+    writeln!(out, "Wrapper: {}", w)?;
+    
+    Ok(())
 }
 
 // Newtype: wrapper around Vec to implement Display
@@ -428,23 +459,148 @@ impl std::fmt::Display for Wrapper {
     }
 }
 
+/// Benchmarks dynamic against static dispatch
+/// 
+/// DISPATCH:
+/// - Box<dyn Shape>: each call looks area() up in a vtable at runtime, and
+///   every shape is a separate heap allocation
+/// - Generics (T: Shape): monomorphized, so the call is direct and can be
+///   inlined, but a Vec<T> holds only one kind of shape
+/// - An enum is a middle ground: a closed set of kinds, stored inline
+pub fn dispatch_benchmark(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Dynamic vs Static Dispatch ---")?;
+    
+    const N: usize = 1_000;
+    
+    let boxed: Vec<Box<dyn Shape>> = (0..N)
+        .map(|i| -> Box<dyn Shape> {
+            if i % 2 == 0 {
+                Box::new(Square { side: i as f64 })
+            } else {
+                Box::new(Circle { radius: i as f64 })
+            }
+        })
+        .collect();
+    let squares: Vec<Square> = (0..N).map(|i| Square { side: i as f64 }).collect();
+    let circles: Vec<Circle> = (0..N).map(|i| Circle { radius: i as f64 }).collect();
+    let shapes: Vec<AnyShape> = (0..N)
+        .map(|i| {
+            if i % 2 == 0 {
+                AnyShape::Square(Square { side: i as f64 })
+            } else {
+                AnyShape::Circle(Circle { radius: i as f64 })
+            }
+        })
+        .collect();
+    
+    let mut bench = Bench::new();
+    bench.run("Vec<Box<dyn Shape>>", || {
+        black_box(&boxed).iter().map(|shape| shape.area()).sum::<f64>()
+    });
+    bench.run("Vec<Square> + Vec<Circle>", || {
+        total_area(black_box(&squares[..N / 2])) + total_area(black_box(&circles[..N / 2]))
+    });
+    bench.run("Vec<AnyShape> (enum)", || total_area(black_box(&shapes)));
+    writeln!(out, "Summing the areas of {} shapes:", N)?;
+    bench.write_table(out)?;
+    writeln!(out, "\nThe vtable costs little by itself; losing inlining and cache")?;
+    writeln!(out, "locality to the boxes is what shows up in a --release build.")?;
+    
+    Ok(())
+}
+
+trait Shape {
+    fn area(&self) -> f64;
+}
+
+struct Square {
+    side: f64,
+}
+
+struct Circle {
+    radius: f64,
+}
+
+enum AnyShape {
+    Square(Square),
+    Circle(Circle),
+}
+
+impl Shape for Square {
+    fn area(&self) -> f64 {
+        self.side * self.side
+    }
+}
+
+impl Shape for Circle {
+    fn area(&self) -> f64 {
+        std::f64::consts::PI * self.radius * self.radius
+    }
+}
+
+impl Shape for AnyShape {
+    fn area(&self) -> f64 {
+        match self {
+            AnyShape::Square(square) => square.area(),
+            AnyShape::Circle(circle) => circle.area(),
+        }
+    }
+}
+
+/// Statically dispatched: compiled once for each shape type it's called with
+fn total_area<T: Shape>(shapes: &[T]) -> f64 {
+    shapes.iter().map(Shape::area).sum()
+}
+
 // ============================================================================
 // PUBLIC INTERFACE
 // ============================================================================
 
-pub fn run_all_examples() {
-    generic_functions();
-    generic_implementations();
-    trait_basics();
-    trait_bounds();
-    returning_traits();
-    conditional_implementations();
-    standard_traits();
-    operator_overloading();
-    associated_types();
-    supertraits();
-    newtype_pattern();
-}
+/// This module's lessons, in teaching order
+pub static SECTION: Section = Section {
+    id: "traits_generics",
+    number: 4,
+    title: "TRAITS & GENERICS",
+    icon: "🎭",
+    lessons: &[
+        &lesson!(generic_functions, "Generic Functions")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["generics"]),
+        &lesson!(generic_implementations, "Generic Implementations")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["generics", "methods"]),
+        &lesson!(trait_basics, "Trait Basics")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["traits"]),
+        &lesson!(trait_bounds, "Trait Bounds")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["traits", "generics"]),
+        &lesson!(returning_traits, "Returning Traits")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["traits", "impl-trait"]),
+        &lesson!(conditional_implementations, "Conditional Implementations")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["traits", "generics"]),
+        &lesson!(standard_traits, "Standard Library Traits")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["traits", "std::fmt"]),
+        &lesson!(operator_overloading, "Operator Overloading")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["traits", "operators"]),
+        &lesson!(associated_types, "Associated Types")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["traits", "associated-types"]),
+        &lesson!(supertraits, "Supertraits")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["traits"]),
+        &lesson!(newtype_pattern, "Newtype Pattern")
+            .with_difficulty(Difficulty::Intermediate)
+            .with_tags(&["traits", "newtype"]),
+        &lesson!(dispatch_benchmark, "Dynamic vs Static Dispatch")
+            .with_difficulty(Difficulty::Advanced)
+            .with_tags(&["traits", "generics", "trait-objects", "performance"]),
+    ],
+};
 
 // ============================================================================
 // UNIT TESTS
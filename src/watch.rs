@@ -0,0 +1,210 @@
+// ============================================================================
+// WATCH MODE
+// ============================================================================
+// `happyr watch concurrency::arc_mutex` runs one lesson, then keeps an eye on
+// `src/` while you experiment with it. Every time a file is saved it rebuilds
+// with `cargo build` and runs the lesson again; if the build fails, the
+// compiler's errors are shown instead, and the next save tries again.
+//
+// Changes are found by polling modification times, which needs nothing but
+// std. The lesson runs in a child process of the freshly built binary, with
+// the `--isolate` timeout, so an accidental infinite loop doesn't stall the
+// watch. Stop it with Ctrl-C.
+// ============================================================================
+
+use crate::isolate::{self, Outcome, Report};
+use crate::registry;
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// How often `src/` is checked for changes
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How long to wait after a change before building, since editors often
+/// save a file in several writes
+const SETTLE: Duration = Duration::from_millis(150);
+
+/// Runs `id`, then rebuilds and re-runs it after every change to the
+/// project's sources, until the process is interrupted
+pub fn watch(id: &str, timeout: Duration, out: &mut dyn Write) -> io::Result<()> {
+    let project = Path::new(env!("CARGO_MANIFEST_DIR"));
+    // Captured before the first rebuild replaces the file
+    let exe = std::env::current_exe()?;
+    let mut files = modified_times(project)?;
+
+    writeln!(out, "👀 Watching {} (save a file in src/ to re-run it, Ctrl-C to stop)", id)?;
+    run(&exe, id, timeout, out)?;
+
+    loop {
+        thread::sleep(POLL_INTERVAL);
+        if changed(&files, &modified_times(project)?).is_empty() {
+            continue;
+        }
+        thread::sleep(SETTLE);
+        let now = modified_times(project)?;
+        let names: Vec<String> = changed(&files, &now)
+            .iter()
+            .map(|path| path.strip_prefix(project).unwrap_or(path).display().to_string())
+            .collect();
+        files = now;
+
+        writeln!(out, "\n{}", "=".repeat(70))?;
+        writeln!(out, "🔨 {} changed, rebuilding...", names.join(", "))?;
+        let (built, messages) = build(project)?;
+        if built {
+            out.write_all(messages.as_bytes())?;
+            run(&exe, id, timeout, out)?;
+        } else {
+            writeln!(out, "\n{}❌ The build failed, fix it and save again.", messages)?;
+        }
+        out.flush()?;
+    }
+}
+
+/// The modification time of every `.rs` file under `project/src`, and of
+/// `Cargo.toml`
+fn modified_times(project: &Path) -> io::Result<BTreeMap<PathBuf, SystemTime>> {
+    let mut times = BTreeMap::new();
+    let mut dirs = vec![project.join("src")];
+    while let Some(dir) = dirs.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            let metadata = entry.metadata()?;
+            if metadata.is_dir() {
+                dirs.push(path);
+            } else if path.extension().is_some_and(|extension| extension == "rs") {
+                times.insert(path, metadata.modified()?);
+            }
+        }
+    }
+    let manifest = project.join("Cargo.toml");
+    if let Ok(metadata) = fs::metadata(&manifest) {
+        times.insert(manifest, metadata.modified()?);
+    }
+    Ok(times)
+}
+
+/// Files added, modified or removed between two scans
+fn changed(before: &BTreeMap<PathBuf, SystemTime>, after: &BTreeMap<PathBuf, SystemTime>) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = after
+        .iter()
+        .filter(|(path, time)| before.get(*path) != Some(*time))
+        .map(|(path, _)| path.clone())
+        .collect();
+    paths.extend(before.keys().filter(|path| !after.contains_key(*path)).cloned());
+    paths.sort();
+    paths
+}
+
+/// Runs `cargo build` with the same profile and features as this binary,
+/// returning whether it succeeded and what the compiler said
+fn build(project: &Path) -> io::Result<(bool, String)> {
+    // Set by `cargo run`, so the same toolchain builds it again
+    let cargo = std::env::var_os("CARGO").unwrap_or_else(|| OsString::from("cargo"));
+    let mut command = Command::new(cargo);
+    command
+        .arg("build")
+        .arg("--quiet")
+        .arg("--manifest-path")
+        .arg(project.join("Cargo.toml"));
+    // Otherwise cargo would swap this build's features for the defaults
+    let features: Vec<&str> = registry::FEATURES
+        .iter()
+        .filter(|&&(_, enabled)| enabled)
+        .map(|&(feature, _)| feature)
+        .collect();
+    command.arg("--no-default-features");
+    if !features.is_empty() {
+        command.arg("--features").arg(features.join(","));
+    }
+    if !cfg!(debug_assertions) {
+        command.arg("--release");
+    }
+    let output = command.output()?;
+    Ok((output.status.success(), String::from_utf8_lossy(&output.stderr).into_owned()))
+}
+
+/// Runs the lesson in a child process of `exe` and prints how it went
+fn run(exe: &Path, id: &str, timeout: Duration, out: &mut dyn Write) -> io::Result<()> {
+    let report = isolate::run(isolate::lesson_command(exe, id), id, timeout)?;
+    write_report(&report, timeout, out)
+}
+
+fn write_report(report: &Report, timeout: Duration, out: &mut dyn Write) -> io::Result<()> {
+    write!(out, "{}", report.stdout)?;
+    write!(out, "{}", report.stderr)?;
+    let time = format!("{}ms", report.elapsed.as_millis());
+    match &report.outcome {
+        Outcome::Passed => writeln!(out, "\n✅ {} finished in {}", report.id, time),
+        Outcome::TimedOut => writeln!(
+            out,
+            "\n⏱️  {} timed out after {:.1}s (change it with --timeout)",
+            report.id,
+            timeout.as_secs_f64()
+        ),
+        outcome => writeln!(out, "\n💥 {} {} after {}", report.id, outcome.label(), time),
+    }
+}
+
+// ============================================================================
+// UNIT TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_project(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("happyr-watch-{}-{}", name, std::process::id()));
+        fs::create_dir_all(dir.join("src/nested")).unwrap();
+        fs::write(dir.join("Cargo.toml"), "[package]\n").unwrap();
+        fs::write(dir.join("src/main.rs"), "fn main() {}\n").unwrap();
+        fs::write(dir.join("src/nested/lesson.rs"), "").unwrap();
+        fs::write(dir.join("src/notes.txt"), "").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_modified_times_covers_sources_and_manifest() {
+        let dir = temp_project("scan");
+        let times = modified_times(&dir).unwrap();
+        let names: Vec<_> = times.keys().map(|path| path.strip_prefix(&dir).unwrap()).collect();
+        assert_eq!(
+            names,
+            [Path::new("Cargo.toml"), Path::new("src/main.rs"), Path::new("src/nested/lesson.rs")]
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_changed_files() {
+        let at = |secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+        let before = BTreeMap::from([(PathBuf::from("a.rs"), at(1)), (PathBuf::from("b.rs"), at(1))]);
+        let after = BTreeMap::from([(PathBuf::from("a.rs"), at(2)), (PathBuf::from("c.rs"), at(1))]);
+        assert_eq!(changed(&before, &after), ["a.rs", "b.rs", "c.rs"].map(PathBuf::from));
+        assert!(changed(&after, &after).is_empty());
+    }
+
+    #[test]
+    fn test_report_shows_output_then_outcome() {
+        let report = Report {
+            id: "error_handling::panic_basics".to_string(),
+            outcome: Outcome::Panicked("crash and burn".to_string()),
+            stdout: "--- Panic Basics ---\n".to_string(),
+            stderr: "thread 'main' panicked at src/error_handling.rs:38:5:\ncrash and burn\n".to_string(),
+            elapsed: Duration::from_millis(3),
+        };
+        let mut out = Vec::new();
+        write_report(&report, isolate::DEFAULT_TIMEOUT, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.starts_with("--- Panic Basics ---\nthread 'main' panicked"));
+        assert!(text.ends_with("\n💥 error_handling::panic_basics panicked after 3ms\n"));
+    }
+}
@@ -0,0 +1,125 @@
+// ============================================================================
+// THE HAPPYR COMMAND
+// ============================================================================
+// Runs the built binary the way a learner would and checks what it prints
+// and how it exits. Each test points `HAPPYR_HOME` at its own directory, so
+// progress files never touch the real `~/.happyr`.
+// ============================================================================
+
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+fn home(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("happyr-cli-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+fn happyr(home: &PathBuf, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_happyr"))
+        .args(args)
+        .env("HAPPYR_HOME", home)
+        .output()
+        .expect("the happyr binary runs")
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+#[cfg(feature = "ownership")]
+fn test_help_and_list() {
+    let home = home("list");
+    let help = happyr(&home, &["help"]);
+    assert!(help.status.success());
+    assert!(stdout(&help).starts_with("Usage: happyr [COMMAND]"));
+
+    let list = happyr(&home, &["list", "--tag", "lifetimes"]);
+    assert!(list.status.success());
+    let text = stdout(&list);
+    assert!(text.contains("ownership::lifetime_basics"), "{}", text);
+    assert!(!text.contains("basics::control_flow"), "{}", text);
+    let complete = happyr::registry::missing_features().is_empty();
+    assert_eq!(text.contains("Not in this build"), !complete, "{}", text);
+    let _ = std::fs::remove_dir_all(home);
+}
+
+#[test]
+#[cfg(feature = "basics")]
+fn test_run_prints_the_lesson_and_records_progress() {
+    let home = home("run");
+    let output = happyr(&home, &["run", "basics::control_flow"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let text = stdout(&output);
+    assert!(text.contains("SECTION 1: RUST BASICS"), "{}", text);
+    assert!(text.contains("--- Control Flow ---"), "{}", text);
+
+    let progress = std::fs::read_to_string(home.join("progress")).unwrap();
+    assert!(progress.contains("done basics::control_flow"), "{}", progress);
+    std::fs::remove_dir_all(home).unwrap();
+}
+
+#[test]
+fn test_a_closed_pipe_ends_the_output_quietly() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_happyr"))
+        .arg("help")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("the happyr binary runs");
+    // Like `happyr help | head -0`, which closes the pipe before reading
+    drop(child.stdout.take());
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stderr(&output).is_empty(), "{}", stderr(&output));
+}
+
+#[test]
+fn test_bad_arguments_exit_with_an_error() {
+    let home = home("errors");
+    let output = happyr(&home, &["run", "--format", "yaml"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).starts_with("error: --format needs one of text, json or ndjson"));
+    assert!(stderr(&output).contains("Usage: happyr"));
+
+    let output = happyr(&home, &["run", "--seed", "soon"]);
+    assert_eq!(output.status.code(), Some(2));
+
+    let output = happyr(&home, &["run", "nope"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).starts_with("error: no lesson matches 'nope'"));
+    assert!(stdout(&output).is_empty());
+    let _ = std::fs::remove_dir_all(home);
+}
+
+#[test]
+fn test_every_lesson_passes_in_its_own_process() {
+    let home = home("isolate");
+    let output = happyr(&home, &["run", "--isolate", "--deterministic", "--format", "ndjson"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let text = stdout(&output);
+    let records: Vec<&str> = text.lines().collect();
+    assert_eq!(records.len(), happyr::registry::lessons().count());
+    for record in records {
+        assert!(record.contains("\"status\":\"ok\""), "{}", record);
+    }
+    // Never created in a build without lessons, as nothing records progress
+    let _ = std::fs::remove_dir_all(home);
+}
+
+#[test]
+#[cfg(feature = "concurrency")]
+fn test_a_seed_replays_the_same_interleaving() {
+    let home = home("seed");
+    // The channel lessons, since the section's benchmarks print timings
+    let run = || stdout(&happyr(&home, &["run", "--seed", "5", "--tag", "channels"]));
+    let first = run();
+    assert!(first.contains("--- Multiple Producers ---"), "{}", first);
+    assert_eq!(first, run());
+    std::fs::remove_dir_all(home).unwrap();
+}
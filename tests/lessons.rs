@@ -0,0 +1,86 @@
+// ============================================================================
+// LESSONS THROUGH THE PUBLIC API
+// ============================================================================
+// Uses happyr as another crate would: every lesson through the registry,
+// the tables that refer to lessons by id, and the helpers the lessons
+// export. Tests that name a lesson only run in builds that include it.
+// ============================================================================
+
+use happyr::isolate::Outcome;
+use happyr::lesson::Lesson;
+use happyr::{exercise, playlist, quiz, registry, report, sched, source};
+
+#[test]
+fn test_every_lesson_runs_without_panicking() {
+    let mut failures = Vec::new();
+    for lesson in registry::lessons() {
+        // Seeded, so the concurrency lessons' sleeps take no real time
+        let report = sched::run_seeded(sched::DEFAULT_SEED, || report::capture(lesson));
+        if report.outcome != Outcome::Passed {
+            failures.push(format!("{}: {} {}", lesson.id(), report.outcome.label(), report.stderr));
+        } else if report.stdout.trim().is_empty() {
+            failures.push(format!("{}: printed nothing", lesson.id()));
+        }
+    }
+    assert!(failures.is_empty(), "{} lesson(s) failed:\n{}", failures.len(), failures.join("\n"));
+}
+
+#[test]
+fn test_registry_covers_every_section() {
+    let ids: Vec<&str> = registry::SECTIONS.iter().map(|section| section.id).collect();
+    let features: Vec<&str> = registry::FEATURES
+        .iter()
+        .filter(|&&(feature, enabled)| enabled && !feature.ends_with("-lessons"))
+        .map(|&(feature, _)| feature)
+        .collect();
+    assert_eq!(ids, features);
+
+    for lesson in registry::lessons() {
+        let id = lesson.id();
+        assert_eq!(registry::find(&id).map(|found| found.id()), Some(id.clone()));
+        assert!(registry::section(lesson.section()).is_some(), "{} has no section", id);
+        assert!(source::example(lesson).is_some(), "{} has no source", id);
+    }
+    assert_eq!(registry::find("advanced::unsafe_functions").is_some(), cfg!(feature = "unsafe-lessons"));
+    assert_eq!(registry::find("advanced::extern_functions").is_some(), cfg!(feature = "ffi-lessons"));
+}
+
+#[test]
+fn test_quizzes_exercises_and_playlists_refer_to_real_lessons() {
+    for section in registry::SECTIONS {
+        let bank = quiz::bank(section.id).unwrap_or_else(|| panic!("{} has no quiz", section.id));
+        let questions = quiz::parse(bank).unwrap_or_else(|error| panic!("{}: {}", section.id, error));
+        assert!(!questions.is_empty(), "{}'s quiz is empty", section.id);
+    }
+    for exercise in exercise::EXERCISES {
+        assert!(registry::find(exercise.lesson).is_some(), "{} points at {}", exercise.id, exercise.lesson);
+        assert!(exercise.path().exists(), "{} has no stub", exercise.id);
+    }
+    // A build without some sections can leave a playlist with nothing in it
+    if registry::missing_features().is_empty() {
+        for playlist in playlist::PLAYLISTS {
+            let lessons: Vec<&dyn Lesson> = playlist.lessons(registry::SECTIONS);
+            assert!(!lessons.is_empty(), "{} is empty", playlist.name);
+        }
+    }
+}
+
+#[test]
+#[cfg(all(feature = "ownership", feature = "traits_generics", feature = "error_handling", feature = "unsafe-lessons"))]
+fn test_lesson_helpers_are_reusable() {
+    use happyr::{advanced, error_handling, ownership, traits_generics};
+
+    assert_eq!(traits_generics::largest(&[3, 7, 2]), 7);
+    assert_eq!(traits_generics::largest(&['r', 'u', 's', 't']), 'u');
+    assert_eq!(error_handling::parse_age("42"), Ok(42));
+    assert_eq!(error_handling::parse_age("200"), Err(error_handling::AgeError::TooOld));
+    assert_eq!(error_handling::parse_age("old"), Err(error_handling::AgeError::InvalidFormat));
+    assert_eq!(ownership::first_word("hello world"), "hello");
+    assert_eq!(ownership::longest("long", "longer"), "longer");
+
+    let mut numbers = [1, 2, 3, 4, 5];
+    let (left, right) = advanced::split_at_mut(&mut numbers, 2);
+    left[0] = 10;
+    right[0] = 30;
+    assert_eq!(numbers, [10, 2, 30, 4, 5]);
+}